@@ -0,0 +1,59 @@
+/// keywords which can be used as raw identifiers
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+/// keywords which can't be raw identifiers
+const RESERVED: &[&str] = &["self", "Self", "super", "crate", "_"];
+
+/// `camelCase` or `snake_case` into `snake_case`, escaping keywords
+pub fn field_name(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len());
+
+    for (index, char) in name.chars().enumerate() {
+        if char.is_ascii_uppercase() {
+            if index > 0 && !snake.ends_with('_') {
+                snake.push('_');
+            }
+
+            snake.push(char.to_ascii_lowercase());
+        } else if char.is_ascii_alphanumeric() {
+            snake.push(char);
+        } else {
+            snake.push('_');
+        }
+    }
+
+    if snake.starts_with(|c: char| c.is_ascii_digit()) {
+        snake.insert(0, '_');
+    }
+
+    match snake.as_str() {
+        keyword if KEYWORDS.contains(&keyword) => format!("r#{keyword}"),
+        reserved if RESERVED.contains(&reserved) => format!("{reserved}_"),
+        _ => snake,
+    }
+}
+
+/// `snake_case` or `camelCase` into `PascalCase`
+pub fn type_name(name: &str) -> String {
+    let words = name.split(|c: char| !c.is_ascii_alphanumeric());
+
+    let mut pascal: String = words
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars)
+        })
+        .collect();
+
+    if pascal.starts_with(|c: char| c.is_ascii_digit()) || pascal.is_empty() {
+        pascal.insert(0, '_');
+    }
+
+    pascal
+}
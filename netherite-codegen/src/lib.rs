@@ -0,0 +1,350 @@
+//! Generates netherite packet definitions from a
+//! [minecraft-data](https://github.com/PrismarineJS/minecraft-data)
+//! `protocol.json` file.
+//!
+//! Each file describes a single version of the protocol, so the generated
+//! code targets the version of the file it's generated from. It's meant to
+//! be used from a build script:
+//!
+//! ```no_run
+//! // build.rs
+//! let out = std::env::var("OUT_DIR").unwrap();
+//! netherite_codegen::generate_file("protocol.json", format!("{out}/protocol.rs")).unwrap();
+//! ```
+//!
+//! and then included with `include!(concat!(env!("OUT_DIR"), "/protocol.rs"));`
+//!
+//! Packets are grouped by state and direction (e.g. `play::clientbound`). Packets
+//! using types that can't be mapped to a netherite type (like switches or
+//! bitfields) are skipped, and a comment is left in their place.
+#![warn(missing_docs)]
+
+use std::{collections::HashSet, fmt::Write, fs, path::Path};
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+mod ident;
+#[cfg(test)]
+mod test;
+
+use ident::{field_name, type_name};
+
+/// Error generating packet definitions
+#[derive(Debug, Error)]
+pub enum CodegenError {
+    /// Error reading or writing files
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Protocol file isn't valid JSON
+    #[error("json: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Protocol file doesn't have the expected structure
+    #[error("invalid protocol file: {0}")]
+    Format(String),
+}
+
+const STATES: [&str; 5] = ["handshaking", "status", "login", "configuration", "play"];
+const DIRECTIONS: [(&str, &str); 2] = [("toServer", "serverbound"), ("toClient", "clientbound")];
+
+const HEADER: &str = "\
+#[allow(unused_imports)]
+use netherite::{
+    encoding::{
+        position::Position,
+        remaining::Remaining,
+        str::Str,
+        varint::{VarInt, VarLong},
+    },
+    nbt::Nbt,
+    Deserialize, Serialize,
+};
+#[allow(unused_imports)]
+use netherite::_bytes_export::Bytes;
+";
+
+/// Packet definitions generator for a `protocol.json` file
+pub struct Generator {
+    protocol: Value,
+}
+
+/// type of a field that can't be generated
+struct Unsupported(String);
+
+/// types definitions visible from a packet
+struct Scope<'a> {
+    global: Option<&'a Map<String, Value>>,
+    local: Option<&'a Map<String, Value>>,
+}
+
+impl Scope<'_> {
+    fn get(&self, name: &str) -> Option<&Value> {
+        let local = self.local.and_then(|types| types.get(name));
+        local.or_else(|| self.global.and_then(|types| types.get(name)))
+    }
+}
+
+/// structs being generated for a direction of a state
+#[derive(Default)]
+struct Module {
+    code: String,
+    structs: HashSet<String>,
+}
+
+impl Generator {
+    /// Parses the content of a `protocol.json` file
+    pub fn new(protocol: &str) -> Result<Self, CodegenError> {
+        let protocol = serde_json::from_str(protocol)?;
+        Ok(Self { protocol })
+    }
+
+    /// Reads and parses a `protocol.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, CodegenError> {
+        Self::new(&fs::read_to_string(path)?)
+    }
+
+    /// Generates a module for each state found in the
+    /// protocol file, with one submodule per direction
+    pub fn generate(&self) -> Result<String, CodegenError> {
+        let mut code = String::from("// generated by netherite-codegen, do not edit\n");
+        let global = self.protocol.get("types").and_then(Value::as_object);
+
+        for state in STATES {
+            let Some(state_value) = self.protocol.get(state) else {
+                continue;
+            };
+
+            writeln!(code, "\npub mod {state} {{").unwrap();
+
+            for (key, direction) in DIRECTIONS {
+                let local = state_value
+                    .get(key)
+                    .and_then(|direction| direction.get("types"))
+                    .and_then(Value::as_object);
+
+                let scope = Scope { global, local };
+                let module = generate_direction(&scope)
+                    .map_err(|err| CodegenError::Format(format!("{state} {direction}: {err}")))?;
+
+                writeln!(code, "    pub mod {direction} {{").unwrap();
+                for line in HEADER.lines().chain(module.code.lines()) {
+                    match line.is_empty() {
+                        true => code.push('\n'),
+                        false => writeln!(code, "        {line}").unwrap(),
+                    }
+                }
+                writeln!(code, "    }}").unwrap();
+            }
+
+            writeln!(code, "}}").unwrap();
+        }
+
+        Ok(code)
+    }
+}
+
+/// Generates the definitions of `input` into `output`
+pub fn generate_file(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> Result<(), CodegenError> {
+    let code = Generator::from_file(input)?.generate()?;
+    fs::write(output, code)?;
+
+    Ok(())
+}
+
+/// (id, packet name, packet type name) of every packet in `scope`
+fn packets<'a>(scope: &Scope<'a>) -> Result<Vec<(i32, &'a str, &'a str)>, String> {
+    let Some(packet) = scope.local.and_then(|types| types.get("packet")) else {
+        return Ok(vec![]);
+    };
+
+    let fields = packet
+        .get(1)
+        .and_then(Value::as_array)
+        .ok_or("expected packet container")?;
+
+    let field = |name| fields.iter().find(|field| field["name"] == name);
+
+    let mappings = field("name")
+        .and_then(|field| field["type"][1]["mappings"].as_object())
+        .ok_or("expected packet name mappings")?;
+
+    let types = field("params").and_then(|field| field["type"][1]["fields"].as_object());
+
+    let mut packets = mappings
+        .iter()
+        .map(|(raw_id, name)| {
+            let id = match raw_id.strip_prefix("0x") {
+                Some(hex) => i32::from_str_radix(hex, 16),
+                None => raw_id.parse(),
+            };
+
+            let id = id.map_err(|_| format!("invalid packet id {raw_id:?}"))?;
+            let name = name.as_str().ok_or("expected packet name")?;
+            let ty = types
+                .and_then(|types| types.get(name))
+                .and_then(Value::as_str)
+                .unwrap_or(name);
+
+            Ok((id, name, ty))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    packets.sort();
+    Ok(packets)
+}
+
+fn generate_direction(scope: &Scope) -> Result<Module, String> {
+    let mut module = Module::default();
+
+    for (id, name, ty) in packets(scope)? {
+        let definition = scope
+            .get(ty)
+            .or_else(|| scope.get(&format!("packet_{name}")))
+            .ok_or_else(|| format!("missing definition of {name}"))?;
+
+        let struct_name = type_name(name);
+
+        // generated separately, discarded if unsupported
+        let mut packet = Module {
+            code: String::new(),
+            structs: module.structs.clone(),
+        };
+
+        let doc = format!("`{name}` packet ({id:#04x})");
+        let attrs = format!(
+            "#[derive(Serialize, Deserialize, netherite::PacketId)]\n#[packet(id = {id:#04x})]"
+        );
+
+        match container(scope, definition, &struct_name, &doc, &attrs, &mut packet) {
+            Ok(()) => {
+                module.code.push_str(&packet.code);
+                module.structs = packet.structs;
+            }
+            Err(Unsupported(ty)) => writeln!(
+                module.code,
+                "\n// skipped {name} ({id:#04x}): unsupported type {ty}"
+            )
+            .unwrap(),
+        }
+    }
+
+    Ok(module)
+}
+
+/// Generates a struct named `name` from a container definition
+fn container(
+    scope: &Scope,
+    definition: &Value,
+    name: &str,
+    doc: &str,
+    attrs: &str,
+    module: &mut Module,
+) -> Result<(), Unsupported> {
+    let unsupported = || Unsupported(definition.to_string());
+
+    let fields = match definition.as_array().map(Vec::as_slice) {
+        Some([kind, fields]) if kind == "container" => fields.as_array().ok_or_else(unsupported)?,
+        _ => return Err(unsupported()),
+    };
+
+    let mut body = String::new();
+    for field in fields {
+        let field_ident = field["name"]
+            .as_str()
+            .ok_or_else(|| Unsupported("anonymous field".into()))?;
+
+        let ty = rust_type(
+            scope,
+            &field["type"],
+            &format!("{name}{}", type_name(field_ident)),
+            module,
+        )?;
+        writeln!(body, "    pub {}: {ty},", field_name(field_ident)).unwrap();
+    }
+
+    let body = match body.is_empty() {
+        true => "{}".to_string(),
+        false => format!("{{\n{body}}}"),
+    };
+
+    module.structs.insert(name.to_string());
+    write!(
+        module.code,
+        "\n/// {doc}\n#[derive(Debug, Clone, PartialEq)]\n{attrs}\npub struct {name} {body}\n"
+    )
+    .unwrap();
+
+    Ok(())
+}
+
+/// Maps a protocol type to a rust type, generating
+/// containers as structs named `name`
+fn rust_type(
+    scope: &Scope,
+    ty: &Value,
+    name: &str,
+    module: &mut Module,
+) -> Result<String, Unsupported> {
+    let unsupported = || Unsupported(ty.to_string());
+
+    let primitive = match ty {
+        Value::String(ty) => match ty.as_str() {
+            "varint" => "VarInt",
+            "varlong" => "VarLong",
+            "bool" => "bool",
+            "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "f32" | "f64" => ty,
+            "UUID" => "u128",
+            "string" => "Str",
+            "position" => "Position",
+            "restBuffer" => "Remaining",
+            "void" => "()",
+            "anonymousNbt" | "anonOptionalNbt" => "Nbt",
+            alias => {
+                let definition = scope.get(alias).ok_or_else(unsupported)?;
+                if definition == "native" {
+                    return Err(unsupported());
+                }
+
+                return rust_type(scope, definition, &type_name(alias), module);
+            }
+        },
+        Value::Array(parts) => match parts.as_slice() {
+            [kind, options] => {
+                let varint_count = options["countType"] == "varint";
+
+                match kind.as_str().ok_or_else(unsupported)? {
+                    "pstring" if varint_count => "Str",
+                    "buffer" if varint_count => "Bytes",
+                    "option" => {
+                        return Ok(format!(
+                            "Option<{}>",
+                            rust_type(scope, options, name, module)?
+                        ))
+                    }
+                    "array" if varint_count => {
+                        let item = rust_type(scope, &options["type"], name, module)?;
+                        return Ok(format!("Vec<{item}>"));
+                    }
+                    "container" => {
+                        if !module.structs.contains(name) {
+                            let attrs = "#[derive(Serialize, Deserialize)]";
+                            container(scope, ty, name, "part of a packet", attrs, module)?;
+                        }
+
+                        return Ok(name.to_string());
+                    }
+                    _ => return Err(unsupported()),
+                }
+            }
+            _ => return Err(unsupported()),
+        },
+        _ => return Err(unsupported()),
+    };
+
+    Ok(primitive.to_string())
+}
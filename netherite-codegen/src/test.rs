@@ -0,0 +1,36 @@
+use super::{
+    ident::{field_name, type_name},
+    Generator,
+};
+
+#[test]
+fn identifiers() {
+    assert_eq!(field_name("protocolVersion"), "protocol_version");
+    assert_eq!(field_name("entity_id"), "entity_id");
+    assert_eq!(field_name("type"), "r#type");
+    assert_eq!(field_name("self"), "self_");
+    assert_eq!(field_name("2d"), "_2d");
+
+    assert_eq!(type_name("set_protocol"), "SetProtocol");
+    assert_eq!(type_name("previousMessages"), "PreviousMessages");
+}
+
+#[test]
+fn fixture() {
+    // the fixture is also compiled by the integration tests
+    let protocol = include_str!("../tests/fixtures/protocol.json");
+    let expected = include_str!("../tests/fixtures/protocol.rs");
+
+    let code = Generator::new(protocol).unwrap().generate().unwrap();
+    assert_eq!(code, expected);
+}
+
+#[test]
+fn invalid_mappings() {
+    let protocol = r#"{"status": {"toClient": {"types": {"packet": ["container", [
+        {"name": "name", "type": ["mapper", {"type": "varint", "mappings": {"zero": "a"}}]}
+    ]]}}}}"#;
+
+    let generator = Generator::new(protocol).unwrap();
+    assert!(generator.generate().is_err());
+}
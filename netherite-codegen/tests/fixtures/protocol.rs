@@ -0,0 +1,188 @@
+// generated by netherite-codegen, do not edit
+
+pub mod handshaking {
+    pub mod serverbound {
+        #[allow(unused_imports)]
+        use netherite::{
+            encoding::{
+                position::Position,
+                remaining::Remaining,
+                str::Str,
+                varint::{VarInt, VarLong},
+            },
+            nbt::Nbt,
+            Deserialize, Serialize,
+        };
+        #[allow(unused_imports)]
+        use netherite::_bytes_export::Bytes;
+
+        /// `set_protocol` packet (0x00)
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize, netherite::PacketId)]
+        #[packet(id = 0x00)]
+        pub struct SetProtocol {
+            pub protocol_version: VarInt,
+            pub server_host: Str,
+            pub server_port: u16,
+            pub next_state: VarInt,
+        }
+    }
+    pub mod clientbound {
+        #[allow(unused_imports)]
+        use netherite::{
+            encoding::{
+                position::Position,
+                remaining::Remaining,
+                str::Str,
+                varint::{VarInt, VarLong},
+            },
+            nbt::Nbt,
+            Deserialize, Serialize,
+        };
+        #[allow(unused_imports)]
+        use netherite::_bytes_export::Bytes;
+    }
+}
+
+pub mod status {
+    pub mod serverbound {
+        #[allow(unused_imports)]
+        use netherite::{
+            encoding::{
+                position::Position,
+                remaining::Remaining,
+                str::Str,
+                varint::{VarInt, VarLong},
+            },
+            nbt::Nbt,
+            Deserialize, Serialize,
+        };
+        #[allow(unused_imports)]
+        use netherite::_bytes_export::Bytes;
+
+        /// `ping_start` packet (0x00)
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize, netherite::PacketId)]
+        #[packet(id = 0x00)]
+        pub struct PingStart {}
+
+        /// `ping` packet (0x01)
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize, netherite::PacketId)]
+        #[packet(id = 0x01)]
+        pub struct Ping {
+            pub time: i64,
+        }
+    }
+    pub mod clientbound {
+        #[allow(unused_imports)]
+        use netherite::{
+            encoding::{
+                position::Position,
+                remaining::Remaining,
+                str::Str,
+                varint::{VarInt, VarLong},
+            },
+            nbt::Nbt,
+            Deserialize, Serialize,
+        };
+        #[allow(unused_imports)]
+        use netherite::_bytes_export::Bytes;
+
+        /// `server_info` packet (0x00)
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize, netherite::PacketId)]
+        #[packet(id = 0x00)]
+        pub struct ServerInfo {
+            pub response: Str,
+        }
+
+        /// `ping` packet (0x01)
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize, netherite::PacketId)]
+        #[packet(id = 0x01)]
+        pub struct Ping {
+            pub time: i64,
+        }
+    }
+}
+
+pub mod play {
+    pub mod serverbound {
+        #[allow(unused_imports)]
+        use netherite::{
+            encoding::{
+                position::Position,
+                remaining::Remaining,
+                str::Str,
+                varint::{VarInt, VarLong},
+            },
+            nbt::Nbt,
+            Deserialize, Serialize,
+        };
+        #[allow(unused_imports)]
+        use netherite::_bytes_export::Bytes;
+    }
+    pub mod clientbound {
+        #[allow(unused_imports)]
+        use netherite::{
+            encoding::{
+                position::Position,
+                remaining::Remaining,
+                str::Str,
+                varint::{VarInt, VarLong},
+            },
+            nbt::Nbt,
+            Deserialize, Serialize,
+        };
+        #[allow(unused_imports)]
+        use netherite::_bytes_export::Bytes;
+
+        /// `custom_payload` packet (0x10)
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize, netherite::PacketId)]
+        #[packet(id = 0x10)]
+        pub struct CustomPayload {
+            pub channel: Str,
+            pub data: Remaining,
+        }
+
+        // skipped entity_effect (0x11): unsupported type ["bitfield",[{"name":"ambient","signed":false,"size":1}]]
+
+        /// `spawn_position` packet (0x12)
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize, netherite::PacketId)]
+        #[packet(id = 0x12)]
+        pub struct SpawnPosition {
+            pub location: Position,
+            pub angle: f32,
+            pub r#type: u8,
+        }
+
+        /// part of a packet
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize)]
+        pub struct PreviousMessages {
+            pub id: VarInt,
+            pub signature: Option<Bytes>,
+        }
+
+        /// part of a packet
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize)]
+        pub struct ChatFilter {
+            pub mask: Vec<i64>,
+        }
+
+        /// `chat` packet (0x13)
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Serialize, Deserialize, netherite::PacketId)]
+        #[packet(id = 0x13)]
+        pub struct Chat {
+            pub sender: u128,
+            pub previous: Vec<PreviousMessages>,
+            pub filter: Option<ChatFilter>,
+            pub nbt: Nbt,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+#[allow(dead_code)]
+mod protocol {
+    include!("fixtures/protocol.rs");
+}
+
+use bytes::Bytes;
+use netherite::{
+    encoding::{packetid::PacketId, position::Position, remaining::Remaining, varint::VarInt},
+    nbt::{Compound, Nbt},
+    packet::RawPacket,
+};
+use protocol::{handshaking, play, status};
+
+fn roundtrip<T>(packet: T) -> T
+where
+    T: netherite::Serialize + netherite::Deserialize + PacketId + Clone,
+{
+    let raw = RawPacket::from(packet);
+    raw.deserialize::<T>().unwrap().unwrap()
+}
+
+#[test]
+fn packet_ids() {
+    assert_eq!(handshaking::serverbound::SetProtocol::ID, 0x00);
+    assert_eq!(status::clientbound::Ping::ID, 0x01);
+    assert_eq!(play::clientbound::Chat::ID, 0x13);
+}
+
+#[test]
+fn encoding() {
+    let handshake = handshaking::serverbound::SetProtocol {
+        protocol_version: VarInt(765),
+        server_host: String::from("localhost").into(),
+        server_port: 25565,
+        next_state: VarInt(1),
+    };
+
+    let raw = RawPacket::from(handshake.clone());
+    assert_eq!(raw.data.as_ref(), b"\xfd\x05\x09localhost\x63\xdd\x01");
+    assert_eq!(roundtrip(handshake.clone()), handshake);
+}
+
+#[test]
+fn nested() {
+    let chat = play::clientbound::Chat {
+        sender: 42,
+        previous: vec![play::clientbound::PreviousMessages {
+            id: VarInt(1),
+            signature: Some(Bytes::from_static(b"sig")),
+        }],
+        filter: Some(play::clientbound::ChatFilter { mask: vec![1, 2] }),
+        nbt: Nbt::from(Compound::new().with("a", 1)),
+    };
+
+    assert_eq!(roundtrip(chat.clone()), chat);
+
+    let payload = play::clientbound::CustomPayload {
+        channel: String::from("minecraft:brand").into(),
+        data: Remaining(Bytes::from_static(b"\x07vanilla")),
+    };
+    assert_eq!(roundtrip(payload.clone()), payload);
+
+    let spawn = play::clientbound::SpawnPosition {
+        location: Position::new(1, 2, 3),
+        angle: 0.5,
+        r#type: 1,
+    };
+    assert_eq!(roundtrip(spawn.clone()), spawn);
+}
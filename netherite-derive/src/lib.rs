@@ -1,6 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, parse_quote, Data, DeriveInput};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Expr, Fields};
 
 #[proc_macro_derive(Serialize)]
 pub fn serialize(tree: TokenStream) -> TokenStream {
@@ -56,3 +56,119 @@ pub fn deserialize(tree: TokenStream) -> TokenStream {
     )
     .into()
 }
+
+/// Generates `TryFrom<&RawPacket>` and `TryFrom<RawPacket>` for an enum whose
+/// variants wrap a single `Deserialize + PacketId` type, dispatching with a
+/// single match on the packet id.
+///
+/// A variant wrapping `RawPacket` can be marked with `#[packet(unknown)]`
+/// to receive packets with an unknown id, which otherwise fail with
+/// `DeError::InvalidData`
+#[proc_macro_derive(PacketEnum, attributes(packet))]
+pub fn packet_enum(tree: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tree as DeriveInput);
+    let Data::Enum(data) = input.data else {
+        panic!("expected enum")
+    };
+
+    let mut unknown = None;
+    let mut variants = vec![];
+
+    for variant in data.variants {
+        let Fields::Unnamed(fields) = variant.fields else {
+            panic!("expected tuple variant")
+        };
+
+        let [field] = &fields.unnamed.into_iter().collect::<Vec<_>>()[..] else {
+            panic!("expected variant with a single field")
+        };
+
+        let mut is_unknown = false;
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("packet")) {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("unknown") {
+                    return Err(meta.error("unsupported packet attribute"));
+                }
+
+                is_unknown = true;
+                Ok(())
+            })
+            .expect("invalid packet attribute");
+        }
+
+        match is_unknown {
+            true if unknown.is_some() => panic!("only one variant can be unknown"),
+            true => unknown = Some(variant.ident),
+            false => variants.push((variant.ident, field.ty.clone())),
+        }
+    }
+
+    let ident = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let arms = variants.iter().map(|(variant, ty)| {
+        quote!(
+            <#ty as netherite::encoding::packetid::PacketId>::ID => {
+                raw.deserialize_unchecked::<#ty>().map(Self::#variant)
+            }
+        )
+    });
+
+    let fallback = match unknown {
+        Some(variant) => quote!(Ok(Self::#variant(raw.clone()))),
+        None => quote!(Err(netherite::DeError::InvalidData)),
+    };
+
+    quote!(
+        impl #impl_generics TryFrom<&netherite::packet::RawPacket> for #ident #ty_generics #where_clause {
+            type Error = netherite::DeError;
+
+            fn try_from(raw: &netherite::packet::RawPacket) -> std::result::Result<Self, Self::Error> {
+                match raw.packet_id {
+                    #(#arms)*
+                    _ => #fallback,
+                }
+            }
+        }
+
+        impl #impl_generics TryFrom<netherite::packet::RawPacket> for #ident #ty_generics #where_clause {
+            type Error = netherite::DeError;
+
+            fn try_from(raw: netherite::packet::RawPacket) -> std::result::Result<Self, Self::Error> {
+                Self::try_from(&raw)
+            }
+        }
+    )
+    .into()
+}
+
+/// Implements `PacketId` with the id set by the `#[packet(id = ...)]` attribute
+#[proc_macro_derive(PacketId, attributes(packet))]
+pub fn packet_id(tree: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tree as DeriveInput);
+
+    let mut id: Option<Expr> = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("packet")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("id") {
+                return Err(meta.error("unsupported packet attribute"));
+            }
+
+            id = Some(meta.value()?.parse()?);
+            Ok(())
+        })
+        .expect("invalid packet attribute");
+    }
+
+    let id = id.expect("expected #[packet(id = ...)] attribute");
+
+    let ident = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics netherite::encoding::packetid::PacketId for #ident #ty_generics #where_clause {
+            const ID: i32 = #id;
+        }
+    )
+    .into()
+}
@@ -0,0 +1,392 @@
+use std::{
+    any::type_name,
+    collections::{HashMap, HashSet},
+    future::Future,
+};
+
+use bytes::{Buf, BufMut};
+use futures::{future::BoxFuture, FutureExt};
+use thiserror::Error;
+
+use crate::{
+    encoding::{
+        deserialize_bytes, identifier::Identifier, remaining::Remaining, serialize_bytes, str::Str,
+    },
+    packet::RawPacket,
+    DeError, Deserialize, Serialize,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Payload of a plugin message channel, (de)serialized with
+/// [`Serialize`] and [`Deserialize`] like packets are
+pub trait Channel {
+    /// identifier of the channel, including the namespace
+    const CHANNEL: &'static str;
+
+    /// [`Self::CHANNEL`] as an identifier
+    fn identifier() -> Identifier {
+        Identifier::from_static(Self::CHANNEL)
+    }
+}
+
+/// Data of the custom payload (plugin message) packets, in the configuration
+/// and play states. Their id depends on the version and the state, so they
+/// are read from [`RawPacket`]s recognized through a
+/// [`PacketRegistry`](crate::registry::PacketRegistry).
+///
+/// Channels were namespaced with 1.13, older versions can
+/// go through a [`Translator`](crate::translate::Translator)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginMessage {
+    /// channel of the message
+    pub channel: Identifier,
+    /// payload, encoded as defined by the channel
+    pub data: Remaining,
+}
+
+impl PluginMessage {
+    /// Creates the message carrying `payload` on its channel
+    pub fn new<T: Channel + Serialize>(payload: T) -> Self {
+        Self {
+            channel: T::identifier(),
+            data: Remaining(serialize_bytes(payload)),
+        }
+    }
+
+    /// Reads the message from the data of a custom payload `packet`
+    pub fn from_packet(packet: &RawPacket) -> Result<Self, DeError> {
+        deserialize_bytes(packet.data.clone())
+    }
+
+    /// custom payload packet with `packet_id`, carrying the message
+    pub fn to_packet(&self, packet_id: i32) -> RawPacket {
+        RawPacket {
+            packet_id,
+            data: serialize_bytes(self),
+        }
+    }
+
+    /// whether the message was sent on the channel of `T`
+    pub fn is<T: Channel>(&self) -> bool {
+        self.channel == *T::CHANNEL
+    }
+
+    /// If the message was sent on the channel of `T`, deserializes its payload
+    pub fn deserialize<T: Channel + Deserialize>(&self) -> Option<Result<T, DeError>> {
+        self.is::<T>()
+            .then(|| deserialize_bytes(self.data.0.clone()))
+    }
+}
+
+/// channels listed in [`Register`] and [`Unregister`], separated by null characters
+fn serialize_channels(channels: &[Identifier], mut buf: impl BufMut) {
+    for (i, channel) in channels.iter().enumerate() {
+        if i > 0 {
+            buf.put_u8(0);
+        }
+        buf.put_slice(channel.as_bytes());
+    }
+}
+
+fn channels_size(channels: &[Identifier]) -> usize {
+    let separators = channels.len().saturating_sub(1);
+    channels.iter().map(|channel| channel.len()).sum::<usize>() + separators
+}
+
+fn deserialize_channels(mut buffer: impl Buf) -> Result<Vec<Identifier>, DeError> {
+    let data = buffer.copy_to_bytes(buffer.remaining());
+
+    std::str::from_utf8(&data)?
+        .split('\0')
+        .filter(|channel| !channel.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Channels the sender is listening on, `minecraft:register`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Register(pub Vec<Identifier>);
+
+impl Channel for Register {
+    const CHANNEL: &'static str = "minecraft:register";
+}
+
+impl Serialize for Register {
+    fn serialize(&self, buf: impl BufMut) {
+        serialize_channels(&self.0, buf)
+    }
+
+    fn size(&self) -> usize {
+        channels_size(&self.0)
+    }
+}
+
+impl Deserialize for Register {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        deserialize_channels(buffer).map(Self)
+    }
+}
+
+/// Channels the sender stopped listening on, `minecraft:unregister`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Unregister(pub Vec<Identifier>);
+
+impl Channel for Unregister {
+    const CHANNEL: &'static str = "minecraft:unregister";
+}
+
+impl Serialize for Unregister {
+    fn serialize(&self, buf: impl BufMut) {
+        serialize_channels(&self.0, buf)
+    }
+
+    fn size(&self) -> usize {
+        channels_size(&self.0)
+    }
+}
+
+impl Deserialize for Unregister {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        deserialize_channels(buffer).map(Self)
+    }
+}
+
+/// Name of the client or server software, `minecraft:brand`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Brand(pub Str);
+
+impl Channel for Brand {
+    const CHANNEL: &'static str = "minecraft:brand";
+}
+
+impl Serialize for Brand {
+    fn serialize(&self, buf: impl BufMut) {
+        self.0.serialize(buf)
+    }
+
+    fn size(&self) -> usize {
+        self.0.size()
+    }
+}
+
+impl Deserialize for Brand {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        Str::deserialize(buffer).map(Self)
+    }
+}
+
+/// Error returned while dispatching a plugin message
+#[derive(Debug, Error)]
+pub enum ChannelError<E> {
+    /// Custom payload packet couldn't be read
+    #[error("invalid plugin message: {0}")]
+    Message(DeError),
+
+    /// Payload couldn't be deserialized into
+    /// the type its handler was registered with
+    #[error("channel {channel} as {type_name}: {source}")]
+    Deserialize {
+        /// channel of the message
+        channel: Identifier,
+        /// name of the type the payload was deserialized into
+        type_name: &'static str,
+        /// deserialization error
+        source: DeError,
+    },
+
+    /// The other side registered more channels than the limit,
+    /// see [`Channels::max_registered`]
+    #[error("more than {0} channels registered")]
+    TooManyChannels(usize),
+
+    /// Error returned by a handler
+    #[error("handler: {0}")]
+    Handler(E),
+}
+
+fn read<T: Channel + Deserialize, E>(message: &PluginMessage) -> Result<T, ChannelError<E>> {
+    deserialize_bytes(message.data.0.clone()).map_err(|source| ChannelError::Deserialize {
+        channel: message.channel.clone(),
+        type_name: type_name::<T>(),
+        source,
+    })
+}
+
+/// Maximum number of channels the other side can register by default, as Bukkit
+pub const MAX_REGISTERED: usize = 128;
+
+type Handler<C, E> =
+    Box<dyn Fn(C, PluginMessage) -> BoxFuture<'static, Result<(), ChannelError<E>>> + Send + Sync>;
+
+/// Dispatches plugin messages to async handlers registered for their channel.
+///
+/// `minecraft:register`, `minecraft:unregister` and `minecraft:brand` are
+/// handled automatically, keeping track of the channels and the brand of
+/// the other side. Handlers can still be registered for them.
+///
+/// `C` is a context passed to every handler, usually
+/// a cheaply clonable handle to the connection
+pub struct Channels<C, E> {
+    handlers: HashMap<Identifier, Handler<C, E>>,
+    fallback: Option<Handler<C, E>>,
+    registered: HashSet<Identifier>,
+    max_registered: usize,
+    brand: Option<Str>,
+}
+
+impl<C, E> Default for Channels<C, E> {
+    fn default() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            fallback: None,
+            registered: HashSet::new(),
+            max_registered: MAX_REGISTERED,
+            brand: None,
+        }
+    }
+}
+
+impl<C, E> Channels<C, E>
+where
+    C: Send + 'static,
+    E: Send + 'static,
+{
+    /// creates a dispatcher without handlers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` for the channel of `T`,
+    /// replacing the previous handler of the channel
+    pub fn channel<T, F, Fut>(mut self, handler: F) -> Self
+    where
+        T: Channel + Deserialize + Send + 'static,
+        F: Fn(C, T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        let handler = move |ctx, message: PluginMessage| match read::<T, E>(&message) {
+            Ok(payload) => handler(ctx, payload)
+                .map(|res| res.map_err(ChannelError::Handler))
+                .boxed(),
+            Err(err) => futures::future::ready(Err(err)).boxed(),
+        };
+
+        self.handlers.insert(T::identifier(), Box::new(handler));
+        self
+    }
+
+    /// Sets the handler of the messages sent on
+    /// channels without a handler, which are otherwise ignored
+    pub fn fallback<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(C, PluginMessage) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        let handler = move |ctx, message| {
+            handler(ctx, message)
+                .map(|res| res.map_err(ChannelError::Handler))
+                .boxed()
+        };
+
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Maximum number of channels the other side can register,
+    /// [`MAX_REGISTERED`] by default
+    pub fn max_registered(self, max_registered: usize) -> Self {
+        Self {
+            max_registered,
+            ..self
+        }
+    }
+
+    /// whether a handler is registered for `channel`
+    pub fn handles(&self, channel: &Identifier) -> bool {
+        self.handlers.contains_key(channel)
+    }
+
+    /// Announces the channels with a handler to the other side,
+    /// except the ones handled automatically
+    pub fn register(&self) -> PluginMessage {
+        let mut channels: Vec<_> = self
+            .handlers
+            .keys()
+            .filter(|channel| !builtin(channel))
+            .cloned()
+            .collect();
+        channels.sort_by(|a, b| str::cmp(a, b));
+
+        PluginMessage::new(Register(channels))
+    }
+
+    /// whether the other side registered `channel`
+    pub fn is_registered(&self, channel: &Identifier) -> bool {
+        self.registered.contains(channel)
+    }
+
+    /// channels registered by the other side
+    pub fn registered(&self) -> impl Iterator<Item = &Identifier> {
+        self.registered.iter()
+    }
+
+    /// brand of the other side, if it sent one
+    pub fn brand(&self) -> Option<&str> {
+        self.brand.as_deref()
+    }
+
+    /// Handles the built in channels, then calls the handler registered
+    /// for the channel of `message`
+    pub async fn dispatch(
+        &mut self,
+        ctx: C,
+        message: PluginMessage,
+    ) -> Result<(), ChannelError<E>> {
+        if message.is::<Register>() {
+            let Register(channels) = read(&message)?;
+            for channel in channels {
+                let full = self.registered.len() >= self.max_registered;
+                if full && !self.registered.contains(&channel) {
+                    return Err(ChannelError::TooManyChannels(self.max_registered));
+                }
+
+                self.registered.insert(channel);
+            }
+        } else if message.is::<Unregister>() {
+            let Unregister(channels) = read(&message)?;
+            for channel in channels {
+                self.registered.remove(&channel);
+            }
+        } else if message.is::<Brand>() {
+            let Brand(brand) = read(&message)?;
+            self.brand = Some(brand);
+        }
+
+        let handler = self.handlers.get(&message.channel);
+        match handler {
+            Some(handler) => handler(ctx, message).await,
+            None if builtin(&message.channel) => Ok(()),
+            None => match &self.fallback {
+                Some(fallback) => fallback(ctx, message).await,
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Reads a plugin message from the data of a custom
+    /// payload `packet`, then [dispatches](Self::dispatch) it
+    pub async fn dispatch_packet(
+        &mut self,
+        ctx: C,
+        packet: &RawPacket,
+    ) -> Result<(), ChannelError<E>> {
+        let message = PluginMessage::from_packet(packet).map_err(ChannelError::Message)?;
+        self.dispatch(ctx, message).await
+    }
+}
+
+fn builtin(channel: &Identifier) -> bool {
+    [Register::CHANNEL, Unregister::CHANNEL, Brand::CHANNEL].contains(&&**channel)
+}
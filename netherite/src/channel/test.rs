@@ -0,0 +1,194 @@
+use std::sync::{Arc, Mutex};
+
+use bytes::{Buf, BufMut, Bytes};
+
+use super::{Brand, Channel, ChannelError, Channels, PluginMessage, Register, Unregister};
+use crate::{
+    encoding::{identifier::Identifier, remaining::Remaining, str::Str},
+    packet::RawPacket,
+    DeError, Deserialize, Serialize,
+};
+
+#[derive(Debug, PartialEq)]
+struct Counter(u32);
+
+impl Channel for Counter {
+    const CHANNEL: &'static str = "netherite:counter";
+}
+
+impl Serialize for Counter {
+    fn serialize(&self, mut buf: impl BufMut) {
+        buf.put_u32(self.0)
+    }
+
+    fn size(&self) -> usize {
+        4
+    }
+}
+
+impl Deserialize for Counter {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        u32::deserialize(buffer).map(Self)
+    }
+}
+
+#[derive(Clone, Default)]
+struct Ctx {
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Ctx {
+    fn log(&self, line: String) {
+        self.log.lock().unwrap().push(line)
+    }
+}
+
+fn channels() -> Channels<Ctx, &'static str> {
+    Channels::new().channel(|ctx: Ctx, counter: Counter| async move {
+        ctx.log(format!("counter {}", counter.0));
+        match counter.0 {
+            0 => Err("zero"),
+            _ => Ok(()),
+        }
+    })
+}
+
+fn id(identifier: &'static str) -> Identifier {
+    Identifier::from_static(identifier)
+}
+
+#[test]
+fn message() {
+    let message = PluginMessage::new(Counter(7));
+    assert_eq!(message.channel, id("netherite:counter"));
+    assert_eq!(&*message.data, &[0, 0, 0, 7]);
+
+    let packet = message.to_packet(0x18);
+    assert_eq!(&packet.data[..], b"\x11netherite:counter\x00\x00\x00\x07");
+    assert_eq!(PluginMessage::from_packet(&packet).unwrap(), message);
+
+    assert_eq!(
+        message.deserialize::<Counter>().unwrap().unwrap(),
+        Counter(7)
+    );
+    assert!(message.deserialize::<Brand>().is_none());
+}
+
+#[tokio::test]
+async fn builtin() {
+    let register = PluginMessage::new(Register(vec![id("a:b"), id("c:d")]));
+    assert_eq!(&*register.data, b"a:b\0c:d");
+    assert_eq!(
+        register.deserialize::<Register>().unwrap().unwrap(),
+        Register(vec![id("a:b"), id("c:d")])
+    );
+
+    let brand = PluginMessage::new(Brand(Str::from_static("vanilla")));
+    assert_eq!(&*brand.data, b"\x07vanilla");
+
+    let mut channels = channels();
+    let ctx = Ctx::default();
+
+    channels.dispatch(ctx.clone(), register).await.unwrap();
+    channels.dispatch(ctx.clone(), brand).await.unwrap();
+    let unregister = PluginMessage::new(Unregister(vec![id("a:b")]));
+    channels.dispatch(ctx.clone(), unregister).await.unwrap();
+
+    assert!(!channels.is_registered(&id("a:b")));
+    assert!(channels.is_registered(&id("c:d")));
+    assert_eq!(channels.brand(), Some("vanilla"));
+    assert!(ctx.log.lock().unwrap().is_empty());
+
+    // only the channels with a handler are announced
+    let register = channels.register();
+    assert_eq!(&*register.data, b"netherite:counter");
+}
+
+#[tokio::test]
+async fn max_registered() {
+    let mut channels = channels().max_registered(2);
+    let ctx = Ctx::default();
+
+    let register = PluginMessage::new(Register(vec![id("a:b"), id("c:d")]));
+    channels
+        .dispatch(ctx.clone(), register.clone())
+        .await
+        .unwrap();
+    // registering the same channels again doesn't count
+    channels.dispatch(ctx.clone(), register).await.unwrap();
+
+    let register = PluginMessage::new(Register(vec![id("e:f")]));
+    let result = channels.dispatch(ctx.clone(), register).await;
+    assert!(matches!(result, Err(ChannelError::TooManyChannels(2))));
+    assert!(!channels.is_registered(&id("e:f")));
+    assert_eq!(channels.registered().count(), 2);
+}
+
+#[tokio::test]
+async fn dispatch() {
+    let mut channels = channels().fallback(|ctx: Ctx, message: PluginMessage| async move {
+        ctx.log(format!("unknown {}", message.channel));
+        Ok(())
+    });
+    let ctx = Ctx::default();
+
+    let packet = PluginMessage::new(Counter(3)).to_packet(0x0d);
+    channels
+        .dispatch_packet(ctx.clone(), &packet)
+        .await
+        .unwrap();
+
+    let other = PluginMessage {
+        channel: id("other:channel"),
+        data: Remaining::default(),
+    };
+    channels.dispatch(ctx.clone(), other).await.unwrap();
+
+    // built in channels don't reach the fallback
+    let brand = PluginMessage::new(Brand(Str::from_static("vanilla")));
+    channels.dispatch(ctx.clone(), brand).await.unwrap();
+
+    assert_eq!(
+        *ctx.log.lock().unwrap(),
+        ["counter 3", "unknown other:channel"]
+    );
+    assert!(channels.handles(&Counter::identifier()));
+}
+
+#[tokio::test]
+async fn errors() {
+    let mut channels = channels();
+    let ctx = Ctx::default();
+
+    let short = PluginMessage {
+        channel: Counter::identifier(),
+        data: Remaining(Bytes::from_static(&[0])),
+    };
+    let Err(ChannelError::Deserialize {
+        channel,
+        type_name,
+        source,
+    }) = channels.dispatch(ctx.clone(), short).await
+    else {
+        panic!("expected deserialize error")
+    };
+
+    assert_eq!(channel, Counter::identifier());
+    assert!(type_name.ends_with("Counter"));
+    assert!(matches!(source, DeError::Eof));
+
+    let res = channels
+        .dispatch(ctx.clone(), PluginMessage::new(Counter(0)))
+        .await;
+    assert!(matches!(res, Err(ChannelError::Handler("zero"))));
+
+    let packet = RawPacket {
+        packet_id: 0x18,
+        data: Bytes::from_static(b"\x08MC|Brand"),
+    };
+    let res = channels.dispatch_packet(ctx, &packet).await;
+    assert!(matches!(
+        res,
+        Err(ChannelError::Message(DeError::InvalidData))
+    ));
+}
@@ -0,0 +1,152 @@
+use bytes::{Buf, BufMut};
+
+use crate::{
+    encoding::versioned::{VersionedDeserialize, VersionedSerialize},
+    protocol::ProtocolVersion,
+    DeError, Deserialize, Serialize,
+};
+
+mod palette;
+#[cfg(test)]
+mod test;
+
+pub use palette::{ContainerKind, PalettedContainer};
+
+/// Side of a chunk section, in blocks
+pub const SECTION_SIDE: usize = 16;
+
+/// Side of a chunk section, in biome cells (4x4x4 blocks)
+pub const BIOME_SIDE: usize = 4;
+
+/// A 16x16x16 section of a chunk, as sent in the Chunk Data packet (1.18+)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkSection {
+    /// Number of non-air blocks, used by the client for lighting
+    /// and rendering. Not kept up to date by [`Self::set_block`],
+    /// see [`Self::recount_blocks`]
+    pub block_count: i16,
+    /// block state ids, indexed by `(y * 16 + z) * 16 + x`
+    pub block_states: PalettedContainer,
+    /// biome ids, indexed by `(y * 4 + z) * 4 + x`
+    pub biomes: PalettedContainer,
+}
+
+fn block_index(x: usize, y: usize, z: usize) -> usize {
+    assert!(x < SECTION_SIDE && y < SECTION_SIDE && z < SECTION_SIDE);
+    (y * SECTION_SIDE + z) * SECTION_SIDE + x
+}
+
+fn biome_index(x: usize, y: usize, z: usize) -> usize {
+    assert!(x < BIOME_SIDE && y < BIOME_SIDE && z < BIOME_SIDE);
+    (y * BIOME_SIDE + z) * BIOME_SIDE + x
+}
+
+impl ChunkSection {
+    /// Creates a section made only of `block` in `biome`,
+    /// using the vanilla registry sizes
+    pub fn filled(block: u32, biome: u32) -> Self {
+        Self {
+            block_count: 0,
+            block_states: PalettedContainer::filled(ContainerKind::BLOCK_STATES, block),
+            biomes: PalettedContainer::filled(ContainerKind::BIOMES, biome),
+        }
+    }
+
+    /// block state id at section coordinates `x`, `y`, `z` (`0..16`)
+    ///
+    /// Panic:
+    /// panics if coordinates are out of the section
+    pub fn block(&self, x: usize, y: usize, z: usize) -> u32 {
+        self.block_states.get(block_index(x, y, z))
+    }
+
+    /// sets the block state id at section coordinates `x`, `y`, `z` (`0..16`)
+    ///
+    /// Panic:
+    /// panics if coordinates are out of the section
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, state: u32) {
+        self.block_states.set(block_index(x, y, z), state)
+    }
+
+    /// biome id at biome coordinates `x`, `y`, `z` (`0..4`)
+    ///
+    /// Panic:
+    /// panics if coordinates are out of the section
+    pub fn biome(&self, x: usize, y: usize, z: usize) -> u32 {
+        self.biomes.get(biome_index(x, y, z))
+    }
+
+    /// sets the biome id at biome coordinates `x`, `y`, `z` (`0..4`)
+    ///
+    /// Panic:
+    /// panics if coordinates are out of the section
+    pub fn set_biome(&mut self, x: usize, y: usize, z: usize, biome: u32) {
+        self.biomes.set(biome_index(x, y, z), biome)
+    }
+
+    /// Updates [`Self::block_count`], counting the block states
+    /// for which `is_air` returns false
+    pub fn recount_blocks(&mut self, is_air: impl Fn(u32) -> bool) {
+        let count = self
+            .block_states
+            .values()
+            .iter()
+            .filter(|&&state| !is_air(state))
+            .count();
+
+        self.block_count = count as i16;
+    }
+
+    /// Reads a section whose containers have
+    /// non-vanilla `blocks` and `biomes` kinds
+    pub fn deserialize_with(
+        mut buffer: impl Buf,
+        blocks: ContainerKind,
+        biomes: ContainerKind,
+        version: ProtocolVersion,
+    ) -> Result<Self, DeError> {
+        let block_count = i16::deserialize(&mut buffer)?;
+        let block_states = PalettedContainer::deserialize_with(&mut buffer, blocks, version)?;
+        let biomes = PalettedContainer::deserialize_with(&mut buffer, biomes, version)?;
+
+        Ok(Self {
+            block_count,
+            block_states,
+            biomes,
+        })
+    }
+}
+
+impl VersionedSerialize for ChunkSection {
+    fn serialize_versioned(&self, mut buf: impl BufMut, version: ProtocolVersion) {
+        self.block_count.serialize(&mut buf);
+        self.block_states.serialize_with(&mut buf, version);
+        self.biomes.serialize_with(&mut buf, version);
+    }
+
+    fn size_versioned(&self, version: ProtocolVersion) -> usize {
+        self.block_count.size()
+            + self.block_states.size_with(version)
+            + self.biomes.size_with(version)
+    }
+}
+
+impl VersionedDeserialize for ChunkSection {
+    fn deserialize_versioned(buffer: impl Buf, version: ProtocolVersion) -> Result<Self, DeError> {
+        let (blocks, biomes) = (ContainerKind::BLOCK_STATES, ContainerKind::BIOMES);
+        Self::deserialize_with(buffer, blocks, biomes, version)
+    }
+}
+
+/// Reads all the sections of a Chunk Data packet from its data
+/// buffer, which doesn't carry how many sections it contains:
+/// it depends on the height of the dimension
+pub fn read_sections(
+    mut buffer: impl Buf,
+    count: usize,
+    version: ProtocolVersion,
+) -> Result<Vec<ChunkSection>, DeError> {
+    (0..count)
+        .map(|_| ChunkSection::deserialize_versioned(&mut buffer, version))
+        .collect()
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use bytes::{Buf, BufMut};
+
+use crate::{
+    encoding::varint::VarInt, protocol::ProtocolVersion, varint, DeError, Deserialize, Serialize,
+};
+
+/// Parameters of a paletted container, which
+/// depend on what it's storing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainerKind {
+    /// number of entries of the container
+    pub entries: usize,
+    /// smallest bits per entry used with an indirect palette
+    pub min_indirect_bits: u8,
+    /// biggest bits per entry used with an indirect palette
+    pub max_indirect_bits: u8,
+    /// bits per entry of the direct (global) palette, which depends on
+    /// the size of the registry: `ceil(log2(registry size))`
+    pub direct_bits: u8,
+}
+
+impl ContainerKind {
+    /// block states of a chunk section, with the vanilla block state registry
+    pub const BLOCK_STATES: Self = Self {
+        entries: 4096,
+        min_indirect_bits: 4,
+        max_indirect_bits: 8,
+        direct_bits: 15,
+    };
+
+    /// biomes of a chunk section, with the vanilla biome registry
+    pub const BIOMES: Self = Self {
+        entries: 64,
+        min_indirect_bits: 1,
+        max_indirect_bits: 3,
+        direct_bits: 6,
+    };
+
+    /// same kind with a different global palette size
+    pub const fn direct_bits(self, direct_bits: u8) -> Self {
+        Self {
+            direct_bits,
+            ..self
+        }
+    }
+}
+
+/// palette format chosen to encode a container
+enum Plan {
+    Single(u32),
+    Indirect(u8, Vec<u32>),
+    Direct(u8),
+}
+
+/// number of longs needed to pack `entries` of `bits` without spanning
+fn longs(entries: usize, bits: u8) -> usize {
+    match bits {
+        0 => 0,
+        bits => entries.div_ceil(64 / bits as usize),
+    }
+}
+
+/// minimum amount of bits needed to represent `count` distinct values
+fn bits_for(count: usize) -> u8 {
+    (usize::BITS - count.saturating_sub(1).leading_zeros()) as u8
+}
+
+/// A paletted container, expanded into one value per entry.
+///
+/// The palette is computed again on every serialization, picking
+/// the smallest format able to represent the values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalettedContainer {
+    kind: ContainerKind,
+    values: Box<[u32]>,
+}
+
+impl PalettedContainer {
+    /// creates a container of `kind` filled with `value`
+    pub fn filled(kind: ContainerKind, value: u32) -> Self {
+        let values = vec![value; kind.entries].into_boxed_slice();
+        Self { kind, values }
+    }
+
+    /// kind of this container
+    pub fn kind(&self) -> ContainerKind {
+        self.kind
+    }
+
+    /// value of every entry
+    pub fn values(&self) -> &[u32] {
+        &self.values
+    }
+
+    /// value at `index`
+    ///
+    /// Panic:
+    /// panics if `index` is out of bounds
+    pub fn get(&self, index: usize) -> u32 {
+        self.values[index]
+    }
+
+    /// sets the value at `index`
+    ///
+    /// Panic:
+    /// panics if `index` is out of bounds
+    pub fn set(&mut self, index: usize, value: u32) {
+        self.values[index] = value;
+    }
+
+    fn plan(&self) -> Plan {
+        let mut palette: Vec<u32> = vec![];
+
+        for &value in self.values.iter() {
+            if !palette.contains(&value) {
+                palette.push(value);
+            }
+
+            // no need to keep looking, it's going to be direct
+            if bits_for(palette.len()) > self.kind.max_indirect_bits {
+                return Plan::Direct(self.kind.direct_bits);
+            }
+        }
+
+        match palette[..] {
+            [single] => Plan::Single(single),
+            _ => {
+                let bits = bits_for(palette.len()).max(self.kind.min_indirect_bits);
+                Plan::Indirect(bits, palette)
+            }
+        }
+    }
+
+    /// Reads a container of `kind`. Since 1.21.5 the length of
+    /// the data array isn't sent and is derived from the bits per entry
+    pub fn deserialize_with(
+        mut buffer: impl Buf,
+        kind: ContainerKind,
+        version: ProtocolVersion,
+    ) -> Result<Self, DeError> {
+        let bits = u8::deserialize(&mut buffer)?;
+
+        let (bits, palette) = match bits {
+            0 => {
+                let VarInt(value) = VarInt::deserialize(&mut buffer)?;
+                (0, vec![value as u32])
+            }
+            bits if bits <= kind.max_indirect_bits => {
+                let palette = Vec::<VarInt>::deserialize(&mut buffer)?;
+                if palette.is_empty() {
+                    return Err(DeError::InvalidData);
+                }
+                let palette = palette.into_iter().map(|VarInt(v)| v as u32).collect();
+
+                (bits.max(kind.min_indirect_bits), palette)
+            }
+            bits if bits <= 32 => (bits, vec![]),
+            _ => return Err(DeError::InvalidData),
+        };
+
+        let expected = longs(kind.entries, bits);
+        if version < ProtocolVersion::V1_21_5 {
+            let VarInt(len) = VarInt::deserialize(&mut buffer)?;
+            if usize::try_from(len)? != expected {
+                return Err(DeError::InvalidData);
+            }
+        }
+
+        if buffer.remaining() < expected * 8 {
+            return Err(DeError::Eof);
+        }
+
+        let values = match bits {
+            0 => vec![palette[0]; kind.entries],
+            bits => {
+                let per_long = 64 / bits as usize;
+                let mask = (1u64 << bits) - 1;
+
+                let mut values = Vec::with_capacity(kind.entries);
+                for _ in 0..expected {
+                    let long = buffer.get_u64();
+                    let remaining = (kind.entries - values.len()).min(per_long);
+
+                    values.extend((0..remaining).map(|i| (long >> (i * bits as usize)) & mask));
+                }
+
+                let values = values.into_iter().map(|v| v as u32);
+                match bits {
+                    bits if bits <= kind.max_indirect_bits => values
+                        .map(|index| palette.get(index as usize).copied())
+                        .collect::<Option<_>>()
+                        .ok_or(DeError::InvalidData)?,
+                    _ => values.collect(),
+                }
+            }
+        };
+
+        let values = values.into_boxed_slice();
+        Ok(Self { kind, values })
+    }
+
+    /// Writes the container with the format of `version`
+    pub fn serialize_with(&self, mut buf: impl BufMut, version: ProtocolVersion) {
+        let (bits, palette) = match self.plan() {
+            Plan::Single(value) => {
+                0u8.serialize(&mut buf);
+                VarInt(value as i32).serialize(&mut buf);
+                (0, None)
+            }
+            Plan::Indirect(bits, palette) => {
+                bits.serialize(&mut buf);
+                VarInt(palette.len() as i32).serialize(&mut buf);
+                palette
+                    .iter()
+                    .for_each(|&v| VarInt(v as i32).serialize(&mut buf));
+
+                (bits, Some(palette))
+            }
+            Plan::Direct(bits) => {
+                bits.serialize(&mut buf);
+                (bits, None)
+            }
+        };
+
+        if version < ProtocolVersion::V1_21_5 {
+            VarInt(longs(self.kind.entries, bits) as i32).serialize(&mut buf);
+        }
+
+        if bits == 0 {
+            return;
+        }
+
+        let indices: Option<HashMap<u32, u32>> = palette.map(|palette| {
+            let indices = palette.into_iter().enumerate();
+            indices
+                .map(|(index, value)| (value, index as u32))
+                .collect()
+        });
+
+        let per_long = 64 / bits as usize;
+        let mask = (1u64 << bits) - 1;
+
+        for chunk in self.values.chunks(per_long) {
+            let long = chunk.iter().enumerate().fold(0u64, |long, (i, value)| {
+                let value = match &indices {
+                    Some(indices) => indices[value],
+                    None => *value,
+                };
+
+                long | (value as u64 & mask) << (i * bits as usize)
+            });
+
+            buf.put_u64(long);
+        }
+    }
+
+    /// exact size of [`Self::serialize_with`]
+    pub fn size_with(&self, version: ProtocolVersion) -> usize {
+        let (bits, header) = match self.plan() {
+            Plan::Single(value) => (0, varint::size(value as i32)),
+            Plan::Indirect(bits, palette) => {
+                let palette_size = palette.iter().map(|&v| varint::size(v as i32));
+                (
+                    bits,
+                    varint::size(palette.len() as i32) + palette_size.sum::<usize>(),
+                )
+            }
+            Plan::Direct(bits) => (bits, 0),
+        };
+
+        let longs = longs(self.kind.entries, bits);
+        let length = match version < ProtocolVersion::V1_21_5 {
+            true => varint::size(longs as i32),
+            false => 0,
+        };
+
+        1 + header + length + longs * 8
+    }
+}
@@ -0,0 +1,140 @@
+use bytes::BytesMut;
+
+use super::{ChunkSection, ContainerKind, PalettedContainer};
+use crate::{
+    assert_serialization,
+    encoding::versioned::{Versioned, VersionedDeserialize, VersionedSerialize},
+    protocol::ProtocolVersion,
+    DeError,
+};
+
+const VERSION: ProtocolVersion = ProtocolVersion::V1_20_5;
+
+fn roundtrip(section: &ChunkSection, version: ProtocolVersion) -> ChunkSection {
+    let mut buf = BytesMut::new();
+    section.serialize_versioned(&mut buf, version);
+    assert_eq!(buf.len(), section.size_versioned(version));
+
+    let res = ChunkSection::deserialize_versioned(&mut buf, version).unwrap();
+    assert!(buf.is_empty());
+
+    res
+}
+
+#[test]
+fn single_value() {
+    let section = ChunkSection::filled(0, 1);
+    let expected = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00];
+
+    assert_serialization!(Versioned::new(&section, VERSION) => &expected);
+    assert_eq!(roundtrip(&section, VERSION), section);
+}
+
+#[test]
+fn single_value_no_length() {
+    let section = ChunkSection::filled(0, 1);
+    let expected = [0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+
+    assert_serialization!(Versioned::new(&section, ProtocolVersion::V1_21_5) => &expected);
+}
+
+#[test]
+fn indirect_biomes() {
+    let mut section = ChunkSection::filled(0, 0);
+    section.set_biome(3, 3, 3, 7);
+
+    let mut buf = BytesMut::new();
+    section.serialize_versioned(&mut buf, VERSION);
+
+    // block count + single value blocks
+    let biomes = &buf[5..];
+    // 1 bit per entry, palette [0, 7], 1 long
+    assert_eq!(biomes[..5], [0x01, 0x02, 0x00, 0x07, 0x01]);
+    assert_eq!(biomes[5..], 0x8000_0000_0000_0000u64.to_be_bytes());
+
+    let res = roundtrip(&section, VERSION);
+    assert_eq!(res.biome(3, 3, 3), 7);
+    assert_eq!(res.biome(0, 0, 0), 0);
+}
+
+#[test]
+fn palette_resizing() {
+    let mut section = ChunkSection::filled(0, 0);
+
+    // 2 values: still 4 bits, 16 entries per long
+    section.set_block(1, 0, 0, 10);
+    let size = section.block_states.size_with(VERSION);
+    // bits, palette length, palette, data length, data
+    assert_eq!(size, 1 + 1 + 2 + 2 + 256 * 8);
+
+    // 20 values: 5 bits, 12 entries per long without spanning
+    for i in 0..20 {
+        section.set_block(i % 16, i / 16, 5, 100 + i as u32);
+    }
+
+    let size = section.block_states.size_with(VERSION);
+    assert_eq!(size, 1 + 1 + 22 + 2 + 342 * 8);
+
+    let res = roundtrip(&section, VERSION);
+    assert_eq!(res, section);
+    assert_eq!(res.block(1, 0, 0), 10);
+    assert_eq!(res.block(3, 1, 5), 119);
+}
+
+#[test]
+fn direct_palette() {
+    let mut section = ChunkSection::filled(0, 0);
+    for (i, (x, z)) in (0..16)
+        .flat_map(|x| (0..16).map(move |z| (x, z)))
+        .enumerate()
+    {
+        section.set_block(x, 0, z, i as u32);
+        section.set_block(x, 1, z, 1000 + i as u32);
+    }
+
+    // 15 bits, 4 entries per long
+    let size = section.block_states.size_with(VERSION);
+    assert_eq!(size, 1 + 2 + 1024 * 8);
+
+    let res = roundtrip(&section, VERSION);
+    assert_eq!(res.block(15, 1, 15), 1255);
+
+    let res = roundtrip(&section, ProtocolVersion::V1_21_5);
+    assert_eq!(res, section);
+}
+
+#[test]
+fn recount() {
+    let mut section = ChunkSection::filled(0, 0);
+    section.set_block(0, 0, 0, 1);
+    section.set_block(0, 15, 0, 1);
+    section.recount_blocks(|state| state == 0);
+
+    assert_eq!(section.block_count, 2);
+}
+
+#[test]
+fn custom_kind() {
+    let kind = ContainerKind::BIOMES.direct_bits(8);
+    let mut container = PalettedContainer::filled(kind, 0);
+    (0..64).for_each(|i| container.set(i, i as u32 + 150));
+
+    let mut buf = BytesMut::new();
+    container.serialize_with(&mut buf, VERSION);
+
+    assert_eq!(buf[0], 8);
+    let res = PalettedContainer::deserialize_with(buf, kind, VERSION).unwrap();
+    assert_eq!(res, container);
+}
+
+#[test]
+fn empty_palette() {
+    let kind = ContainerKind::BIOMES;
+
+    // 1 bit per entry with an empty indirect palette, then 1 long
+    let mut buf = vec![0x01, 0x00];
+    buf.extend(u64::MAX.to_be_bytes());
+
+    let res = PalettedContainer::deserialize_with(&buf[..], kind, ProtocolVersion::V1_21_5);
+    assert!(matches!(res, Err(DeError::InvalidData)));
+}
@@ -1,21 +1,25 @@
-use std::{cmp::Ordering, io::Write, ops::Deref, usize};
+use std::io::{Read, Write};
 
 use bytes::{Buf, BufMut, BytesMut};
-use flate2::{write::ZlibEncoder, Compression};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 use thiserror::Error;
 use tokio_util::codec::{Decoder, Encoder};
 
 #[cfg(test)]
 mod test;
 
+/// codec able to switch between compressed and uncompressed framing
 pub mod dual;
+/// encryption layer wrapping another codec
+#[cfg(feature = "crypto")]
+pub mod encrypted;
 
 use crate::{
     encoding::packetid::PacketId,
     packet::RawPacket,
     peek::PeekBuffer,
     varint::{self, VarIntError},
-    Serialize,
+    DeError, Serialize,
 };
 
 #[derive(Debug, Error)]
@@ -34,6 +38,11 @@ pub enum CodecError {
     /// Packet is either too big or too small
     #[error("packet has invalid size")]
     Size,
+
+    /// Packet content couldn't be deserialized
+    /// by a layer inspecting it
+    #[error("packet: {0}")]
+    Deserialize(#[from] DeError),
 }
 
 /// kept for backwards compatibility with old naming
@@ -134,6 +143,12 @@ impl<T: Serialize + PacketId> Encoder<T> for UncompressedCodec {
     }
 }
 
+/// Maximum uncompressed size of compressed packets accepted by default,
+/// the protocol maximum of vanilla
+pub const MAX_DATA_SIZE: usize = 8 * 1024 * 1024;
+
+/// Codec for compressed (but unencrypted)
+/// Minecraft packets
 pub struct CompressedCodec {
     /// the treshold at which the codec will start zlib compressing the packet data
     compression_threshold: usize,
@@ -141,6 +156,9 @@ pub struct CompressedCodec {
     /// max packet size the codec is willing to decode from read stream
     max_size: usize,
 
+    /// max size of the packet data once decompressed
+    max_data_size: usize,
+
     // internal reusable buffers
     compressed_buffer: Vec<u8>,
     uncompressed_buffer: Vec<u8>,
@@ -151,6 +169,7 @@ impl Default for CompressedCodec {
         Self {
             compression_threshold: 256,
             max_size: usize::MAX,
+            max_data_size: MAX_DATA_SIZE,
 
             compressed_buffer: vec![],
             uncompressed_buffer: vec![],
@@ -175,6 +194,15 @@ impl CompressedCodec {
         Self { max_size, ..self }
     }
 
+    /// Maximum size of the data of compressed packets once decompressed,
+    /// [`MAX_DATA_SIZE`] by default
+    pub fn max_data_size(self, max_data_size: usize) -> Self {
+        Self {
+            max_data_size,
+            ..self
+        }
+    }
+
     /// sets the compression treshold for the Codec
     pub fn set_compression(&mut self, treshold: usize) {
         self.compression_threshold = treshold
@@ -190,7 +218,7 @@ impl Decoder for CompressedCodec {
     type Item = RawPacket;
     type Error = CodecError;
 
-    fn decode(&mut self, mut src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         let (packet_len_size, packet_len) = match varint::read_varint(src.peek()) {
             Err(VarIntError::Eof) => return Ok(None),
             r => r?,
@@ -215,9 +243,40 @@ impl Decoder for CompressedCodec {
 
         src.advance(packet_len_size);
 
-        let data_length = varint::read_varint(src)?;
+        let mut packet = src.split_to(packet_len);
+        let (_, data_length) = varint::read_varint(&mut packet)?;
+
+        // data length is zero for packets sent uncompressed
+        let mut packet = match data_length {
+            0 => packet,
+            _ => {
+                let data_length: usize = data_length.try_into().map_err(|_| CodecError::Size)?;
+                if data_length > self.max_data_size {
+                    return Err(CodecError::Size);
+                }
+
+                // the data length is chosen by the peer, so the buffer
+                // only grows as the data is actually decompressed
+                self.uncompressed_buffer.clear();
+
+                // read one byte more than expected to detect longer data
+                let mut decoder = ZlibDecoder::new(&packet[..]).take(data_length as u64 + 1);
+                decoder.read_to_end(&mut self.uncompressed_buffer)?;
+
+                if self.uncompressed_buffer.len() != data_length {
+                    return Err(CodecError::Size);
+                }
+
+                BytesMut::from(&self.uncompressed_buffer[..])
+            }
+        };
+
+        let (_, packet_id) = varint::read_varint(&mut packet)?;
 
-        todo!()
+        Ok(Some(RawPacket {
+            packet_id,
+            data: packet.freeze(),
+        }))
     }
 }
 
@@ -236,7 +295,7 @@ impl Encoder<&RawPacket> for CompressedCodec {
             let mut encoder = ZlibEncoder::new(&mut self.compressed_buffer, Compression::default());
 
             // ce ripassiamo
-            let mut varint = [0; 4];
+            let mut varint = [0; 5];
             let varint_written = varint::write(&mut varint[..], item.packet_id);
             encoder.write_all(&varint[..varint_written])?;
             encoder.write_all(&item.data)?;
@@ -256,11 +315,12 @@ impl Encoder<&RawPacket> for CompressedCodec {
         } else {
             let data_length = 0i32;
 
-            let packet_length = varint::size(data_length) + item.data.len();
+            let packet_length = varint::size(data_length) + size;
             let packet_length = packet_length.try_into().map_err(|_| CodecError::Size)?;
 
             varint::write(&mut dst, packet_length);
             varint::write(&mut dst, data_length);
+            varint::write(&mut dst, item.packet_id);
             dst.extend_from_slice(&item.data)
         }
         Ok(())
@@ -1,10 +1,24 @@
-use super::{CompressedCodec, UncompressedCodec};
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
 
-enum SwitchCodec {
+use super::{CodecError, CompressedCodec, UncompressedCodec};
+use crate::{encoding::packetid::PacketId, packet::RawPacket, Serialize};
+
+/// Codec starting uncompressed and switching to the compressed
+/// framing once the Set Compression packet is exchanged
+pub enum SwitchCodec {
+    /// packets are not compressed
     Uncompressed(UncompressedCodec),
+    /// packets bigger than the threshold are compressed
     Compressed(CompressedCodec),
 }
 
+impl Default for SwitchCodec {
+    fn default() -> Self {
+        Self::Uncompressed(UncompressedCodec::default())
+    }
+}
+
 impl From<UncompressedCodec> for SwitchCodec {
     fn from(value: UncompressedCodec) -> Self {
         Self::Uncompressed(value)
@@ -18,6 +32,7 @@ impl From<CompressedCodec> for SwitchCodec {
 }
 
 impl SwitchCodec {
+    /// Maximum size the codec is willing to receive from the connection
     pub fn max_size(self, max_size: usize) -> Self {
         match self {
             Self::Uncompressed(codec) => Self::Uncompressed(codec.max_size(max_size)),
@@ -25,6 +40,7 @@ impl SwitchCodec {
         }
     }
 
+    /// retrieves the maximum size of received packets
     pub fn get_max_size(&self) -> usize {
         let &(SwitchCodec::Uncompressed(UncompressedCodec { max_size, .. })
         | SwitchCodec::Compressed(CompressedCodec { max_size, .. })) = self;
@@ -32,35 +48,78 @@ impl SwitchCodec {
         max_size
     }
 
-    // sets whether the switch codec should process packet
-    // - using the uncompressed codec (`threshold = None`)
-    // - or through the compressed codec (`threshold = Some(..)`)
-    // pub fn set_treshold(&mut self, threshold: Option<usize>) {
-    //     match threshold {
-    //         Some(threshold) => self.set_compressed(threshold),
-    //         None => self.set_uncompressed(),
-    //     }
-    // }
-
-    // pub fn set_compressed(&mut self, threshold: usize) {
-    //     match self {
-    //         SwitchCodec::Compressed(compressed) => compressed.set_compression(threshold),
-    //         SwitchCodec::Uncompressed(MinecraftCodec { max_size, .. }) => {
-    //             let minecraft_compressed = MinecraftCompressed::default()
-    //                 .max_size(*max_size)
-    //                 .compression(threshold);
-
-    //             *self = SwitchCodec::Compressed(minecraft_compressed);
-    //         }
-    //     };
-    // }
-
-    // pub fn set_uncompressed(&mut self) {
-    //     let SwitchCodec::Compressed(MinecraftCompressed { max_size, .. }) = self else {
-    //         return;
-    //     };
-
-    //     // *self = SwitchCodec::Uncompressed(MinecraftCodec { max_size })
-    //     todo!()
-    // }
+    /// compression threshold, if compression is enabled
+    pub fn threshold(&self) -> Option<usize> {
+        match self {
+            SwitchCodec::Uncompressed(_) => None,
+            SwitchCodec::Compressed(codec) => Some(codec.compression_treshold()),
+        }
+    }
+
+    /// Sets whether the switch codec should process packets
+    /// - using the uncompressed codec (`threshold = None`)
+    /// - or through the compressed codec (`threshold = Some(..)`)
+    pub fn set_threshold(&mut self, threshold: Option<usize>) {
+        match threshold {
+            Some(threshold) => self.set_compressed(threshold),
+            None => self.set_uncompressed(),
+        }
+    }
+
+    /// switches to the compressed codec with `threshold`
+    pub fn set_compressed(&mut self, threshold: usize) {
+        match self {
+            SwitchCodec::Compressed(compressed) => compressed.set_compression(threshold),
+            SwitchCodec::Uncompressed(UncompressedCodec { max_size }) => {
+                let compressed = CompressedCodec::default()
+                    .max_size(*max_size)
+                    .compression(threshold);
+
+                *self = SwitchCodec::Compressed(compressed);
+            }
+        };
+    }
+
+    /// switches to the uncompressed codec
+    pub fn set_uncompressed(&mut self) {
+        let SwitchCodec::Compressed(CompressedCodec { max_size, .. }) = self else {
+            return;
+        };
+
+        *self = SwitchCodec::Uncompressed(UncompressedCodec::default().max_size(*max_size))
+    }
+}
+
+impl Decoder for SwitchCodec {
+    type Item = RawPacket;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self {
+            SwitchCodec::Uncompressed(codec) => codec.decode(src),
+            SwitchCodec::Compressed(codec) => codec.decode(src),
+        }
+    }
+}
+
+impl Encoder<&RawPacket> for SwitchCodec {
+    type Error = CodecError;
+
+    fn encode(&mut self, item: &RawPacket, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match self {
+            SwitchCodec::Uncompressed(codec) => codec.encode(item, dst),
+            SwitchCodec::Compressed(codec) => codec.encode(item, dst),
+        }
+    }
+}
+
+impl<T: Serialize + PacketId> Encoder<T> for SwitchCodec {
+    type Error = CodecError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match self {
+            SwitchCodec::Uncompressed(codec) => codec.encode(item, dst),
+            SwitchCodec::Compressed(codec) => codec.encode(item, dst),
+        }
+    }
 }
@@ -0,0 +1,86 @@
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::crypto::Cfb8;
+
+/// Codec layer encrypting what the inner codec writes and decrypting
+/// what it reads, once enabled with the shared secret of the login
+pub struct Encrypted<C> {
+    inner: C,
+    cipher: Option<(Cfb8, Cfb8)>,
+    /// bytes at the start of the read buffer which are already decrypted
+    decrypted: usize,
+}
+
+impl<C: Default> Default for Encrypted<C> {
+    fn default() -> Self {
+        Self::new(C::default())
+    }
+}
+
+impl<C> Encrypted<C> {
+    /// wraps `inner`, without encryption until [`Encrypted::enable`]
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            cipher: None,
+            decrypted: 0,
+        }
+    }
+
+    /// Encrypts everything written from now on, and everything
+    /// read which wasn't decoded yet, with `shared_secret`
+    pub fn enable(&mut self, shared_secret: &[u8; 16]) {
+        let cipher = Cfb8::from_secret(shared_secret);
+        self.cipher = Some((cipher.clone(), cipher));
+        self.decrypted = 0;
+    }
+
+    /// whether encryption is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// wrapped codec
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// wrapped codec
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+}
+
+impl<C: Decoder> Decoder for Encrypted<C> {
+    type Item = C::Item;
+    type Error = C::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if let Some((_, decryptor)) = &mut self.cipher {
+            decryptor.decrypt(&mut src[self.decrypted..]);
+            self.decrypted = src.len();
+        }
+
+        let len = src.len();
+        let result = self.inner.decode(src);
+        self.decrypted = self.decrypted.saturating_sub(len - src.len());
+
+        result
+    }
+}
+
+impl<T, C: Encoder<T>> Encoder<T> for Encrypted<C> {
+    type Error = C::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let start = dst.len();
+        self.inner.encode(item, dst)?;
+
+        if let Some((encryptor, _)) = &mut self.cipher {
+            encryptor.encrypt(&mut dst[start..]);
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,10 @@
 use std::io::Cursor;
 
+use bytes::{Bytes, BytesMut};
 use futures::{Stream, StreamExt};
-use tokio_util::codec::FramedRead;
+use tokio_util::codec::{Decoder, Encoder, FramedRead};
 
-use super::UncompressedCodec;
+use super::{dual::SwitchCodec, CompressedCodec, UncompressedCodec};
 use crate::codec::{CodecError, RawPacket};
 
 macro_rules! block {
@@ -66,7 +67,169 @@ fn zero_data() {
     assert_eq!(packet.data.len(), 0);
 }
 
+fn roundtrip(codec: &mut SwitchCodec, packet: &RawPacket) -> (usize, RawPacket) {
+    let mut buf = BytesMut::new();
+    codec.encode(packet, &mut buf).unwrap();
+
+    let len = buf.len();
+    let decoded = codec.decode(&mut buf).unwrap().unwrap();
+    assert!(buf.is_empty());
+
+    (len, decoded)
+}
+
+#[test]
+fn compressed() {
+    let mut codec = SwitchCodec::from(CompressedCodec::default().compression(64));
+    let packet = RawPacket {
+        packet_id: 0x10,
+        data: Bytes::from(vec![0x42; 1024]),
+    };
+
+    let (len, decoded) = roundtrip(&mut codec, &packet);
+    assert!(len < 64, "packet wasn't compressed");
+    assert_eq!(
+        (decoded.packet_id, decoded.data),
+        (packet.packet_id, packet.data)
+    );
+
+    // below the threshold: length, zero data length, id
+    let small = RawPacket {
+        packet_id: 0x10,
+        data: Bytes::from_static(&[1, 2]),
+    };
+
+    let mut buf = BytesMut::new();
+    codec.encode(&small, &mut buf).unwrap();
+    assert_eq!(buf.as_ref(), [0x04, 0x00, 0x10, 1, 2]);
+    assert_eq!(codec.decode(&mut buf).unwrap().unwrap().data, small.data);
+}
+
+#[test]
+fn compressed_partial() {
+    let mut codec = CompressedCodec::default().compression(0);
+    let mut buf = BytesMut::new();
+    let packet = RawPacket {
+        packet_id: 0x01,
+        data: Bytes::from_static(b"hello"),
+    };
+
+    codec.encode(&packet, &mut buf).unwrap();
+    let mut partial = buf.split_to(buf.len() - 1);
+    assert!(codec.decode(&mut partial).unwrap().is_none());
+
+    partial.unsplit(buf);
+    assert_eq!(
+        codec.decode(&mut partial).unwrap().unwrap().data,
+        packet.data
+    );
+}
+
+#[test]
+fn compressed_wrong_length() {
+    let mut codec = CompressedCodec::default().compression(0);
+    let mut buf = BytesMut::new();
+    let packet = RawPacket {
+        packet_id: 0x01,
+        data: Bytes::from_static(b"hello"),
+    };
+
+    codec.encode(&packet, &mut buf).unwrap();
+    buf[1] += 1; // declared data length
+
+    assert!(matches!(codec.decode(&mut buf), Err(CodecError::Size)));
+}
+
+#[test]
+fn compressed_data_size() {
+    let packet = RawPacket {
+        packet_id: 0x01,
+        data: Bytes::from(vec![0; 64]),
+    };
+
+    let mut buf = BytesMut::new();
+    let mut codec = CompressedCodec::default().compression(0);
+    codec.encode(&packet, &mut buf).unwrap();
+
+    // a small frame declaring a huge uncompressed size is refused
+    let compressed = &buf[2..];
+    let mut huge = BytesMut::from(&[4 + compressed.len() as u8, 0xff, 0xff, 0xff, 0x07][..]);
+    huge.extend_from_slice(compressed);
+    assert!(matches!(codec.decode(&mut huge), Err(CodecError::Size)));
+
+    let mut codec = codec.max_data_size(65);
+    assert!(codec.decode(&mut buf.clone()).unwrap().is_some());
+    let mut codec = codec.max_data_size(64);
+    assert!(matches!(codec.decode(&mut buf), Err(CodecError::Size)));
+}
+
+#[test]
+fn switch() {
+    let mut codec = SwitchCodec::default().max_size(1024);
+    assert_eq!(codec.threshold(), None);
+
+    codec.set_threshold(Some(256));
+    assert_eq!(codec.threshold(), Some(256));
+    assert_eq!(codec.get_max_size(), 1024);
+
+    let packet = RawPacket {
+        packet_id: 0x00,
+        data: Bytes::from_static(&[0; 512]),
+    };
+    assert_eq!(roundtrip(&mut codec, &packet).1.data, packet.data);
+
+    codec.set_threshold(None);
+    assert!(matches!(codec, SwitchCodec::Uncompressed(_)));
+    assert_eq!(codec.get_max_size(), 1024);
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn encrypted() {
+    use super::encrypted::Encrypted;
+
+    let secret = [7; 16];
+    let mut sender = Encrypted::new(SwitchCodec::default());
+    let mut receiver = Encrypted::new(SwitchCodec::default());
+    let packet = RawPacket {
+        packet_id: 0x01,
+        data: Bytes::from_static(b"hello"),
+    };
+
+    let mut buf = BytesMut::new();
+    sender.encode(&packet, &mut buf).unwrap();
+    sender.inner_mut().set_threshold(Some(0));
+    sender.enable(&secret);
+    sender.encode(&packet, &mut buf).unwrap();
+    sender.encode(&packet, &mut buf).unwrap();
+
+    assert!(!buf.windows(5).skip(7).any(|window| window == b"hello"));
+
+    // the encrypted packets were received together with the plain one
+    assert_eq!(
+        receiver.decode(&mut buf).unwrap().unwrap().data,
+        packet.data
+    );
+    receiver.inner_mut().set_threshold(Some(0));
+    receiver.enable(&secret);
+
+    let mut partial = buf.split_to(buf.len() - 1);
+    assert_eq!(
+        receiver.decode(&mut partial).unwrap().unwrap().data,
+        packet.data
+    );
+    assert!(receiver.decode(&mut partial).unwrap().is_none());
+
+    partial.unsplit(buf);
+    assert_eq!(
+        receiver.decode(&mut partial).unwrap().unwrap().data,
+        packet.data
+    );
+    assert!(partial.is_empty());
+}
+
 // Get derive macros to work within crate
+#[allow(unused_imports)]
 mod netherite {
     pub use crate::*;
 }
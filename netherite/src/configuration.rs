@@ -0,0 +1,540 @@
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{
+    codec::CodecError,
+    connection::Connection,
+    encoding::{identifier::Identifier, packetid::PacketId, varint::VarInt},
+    nbt::{Compound, ListTag, Nbt, Tag},
+    packet::{
+        configuration::{
+            AcknowledgeFinishConfiguration, ClientboundKnownPacks, FeatureFlags,
+            FinishConfiguration, KnownPack, RegistryCodec, RegistryData, RegistryEntry,
+            RegistryTags, ServerboundKnownPacks, TagEntries, UpdateTags,
+        },
+        RawPacket,
+    },
+    protocol::{ProtocolVersion, State},
+    Serialize,
+};
+
+/// loading registries from data pack files
+#[cfg(feature = "serde_json")]
+mod load;
+#[cfg(test)]
+mod test;
+
+#[cfg(feature = "serde_json")]
+pub use load::LoadError;
+
+/// Error during the configuration
+#[derive(Debug, Error)]
+pub enum ConfigurationError {
+    /// Error reading or writing packets
+    #[error("codec: {0}")]
+    Codec(#[from] CodecError),
+
+    /// Connection closed before the end of the configuration
+    #[error("connection closed")]
+    Closed,
+
+    /// Connection isn't in the configuration state
+    #[error("connection is in state {0:?}")]
+    State(State),
+
+    /// Protocol version has no configuration state (before 1.20.2)
+    #[error("unsupported protocol version {0}")]
+    Version(ProtocolVersion),
+
+    /// Client sent more than [`MAX_RECEIVED`] other packets
+    #[error("too many packets received")]
+    TooManyPackets,
+}
+
+/// Maximum number of packets kept by [`Registries::configure`]
+pub const MAX_RECEIVED: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    id: Identifier,
+    data: Option<Tag>,
+    pack: Option<KnownPack>,
+}
+
+/// Entries and tags of a registry, like `minecraft:dimension_type`.
+///
+/// The entries of synchronized registries are sent to the client, while
+/// the ones of [`Self::builtin`] registries only give their tags network ids
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registry {
+    id: Identifier,
+    builtin: bool,
+    entries: Vec<Entry>,
+    tags: Vec<(Identifier, Vec<Identifier>)>,
+}
+
+impl Registry {
+    /// creates the empty synchronized registry `id`
+    pub fn new(id: Identifier) -> Self {
+        Self {
+            id,
+            builtin: false,
+            entries: vec![],
+            tags: vec![],
+        }
+    }
+
+    /// Registry built into the game, like `minecraft:block`, of which only
+    /// the tags are sent. `entries` must be in the network id order of the
+    /// client, listed by the `registries.json` report of the game
+    pub fn builtin(id: Identifier, entries: Vec<Identifier>) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|id| Entry {
+                id,
+                data: None,
+                pack: None,
+            })
+            .collect();
+
+        Self {
+            builtin: true,
+            entries,
+            ..Self::new(id)
+        }
+    }
+
+    /// id of the registry
+    pub fn id(&self) -> &Identifier {
+        &self.id
+    }
+
+    /// whether the entries are built into the game, see [`Self::builtin`]
+    pub fn is_builtin(&self) -> bool {
+        self.builtin
+    }
+
+    fn insert_entry(&mut self, id: Identifier, data: Tag, pack: Option<KnownPack>) {
+        let entry = Entry {
+            id,
+            data: Some(data),
+            pack,
+        };
+
+        match self.entries.iter_mut().find(|old| old.id == entry.id) {
+            Some(old) => *old = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Adds the entry `id`, or replaces it keeping its network id.
+    /// Its data is always sent, the client can't know it
+    pub fn insert(&mut self, id: Identifier, data: impl Into<Tag>) {
+        self.insert_entry(id, data.into(), None);
+    }
+
+    /// Builder-style [`Self::insert`]
+    pub fn with(mut self, id: Identifier, data: impl Into<Tag>) -> Self {
+        self.insert(id, data);
+        self
+    }
+
+    /// Adds the entry `id` of `pack` like [`Self::insert`],
+    /// clients knowing the pack don't receive its data
+    pub fn insert_from(&mut self, pack: &KnownPack, id: Identifier, data: impl Into<Tag>) {
+        self.insert_entry(id, data.into(), Some(pack.clone()));
+    }
+
+    /// data of the entry `id`
+    pub fn get(&self, id: &Identifier) -> Option<&Tag> {
+        self.entry(id)?.data.as_ref()
+    }
+
+    /// pack the entry `id` comes from, `None` for custom entries
+    pub fn pack(&self, id: &Identifier) -> Option<&KnownPack> {
+        self.entry(id)?.pack.as_ref()
+    }
+
+    fn entry(&self, id: &Identifier) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.id == *id)
+    }
+
+    /// network id of the entry `id`, its position in the registry
+    pub fn network_id(&self, id: &Identifier) -> Option<i32> {
+        let position = self.entries.iter().position(|entry| entry.id == *id)?;
+        position.try_into().ok()
+    }
+
+    /// ids of the entries, in network id order
+    pub fn entries(&self) -> impl Iterator<Item = &Identifier> {
+        self.entries.iter().map(|entry| &entry.id)
+    }
+
+    /// number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// true if the registry has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sets the tag `name` to the entries `entries`, replacing it if
+    /// present. Entries missing from the registry aren't sent
+    pub fn insert_tag(&mut self, name: Identifier, entries: Vec<Identifier>) {
+        match self.tags.iter_mut().find(|(tag, _)| *tag == name) {
+            Some((_, old)) => *old = entries,
+            None => self.tags.push((name, entries)),
+        }
+    }
+
+    /// entries of the tag `name`
+    pub fn tag(&self, name: &Identifier) -> Option<&[Identifier]> {
+        let (_, entries) = self.tags.iter().find(|(tag, _)| tag == name)?;
+        Some(entries)
+    }
+
+    /// Registry data packet of the registry. Entries of the `known`
+    /// packs, which the client already has, don't carry their data
+    pub fn data(&self, known: &[KnownPack]) -> RegistryData {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| RegistryEntry {
+                id: entry.id.clone(),
+                data: match &entry.pack {
+                    Some(pack) if known.contains(pack) => None,
+                    _ => entry.data.clone().map(|data| Nbt(Some(data))),
+                },
+            })
+            .collect();
+
+        RegistryData {
+            registry: self.id.clone(),
+            entries,
+        }
+    }
+
+    /// Registry in the codec sent before 1.20.5, with the data of every entry
+    pub fn codec(&self) -> Compound {
+        let entries = self.entries.iter().enumerate().map(|(id, entry)| {
+            let element = entry.data.clone().unwrap_or(Tag::Compound(Compound::new()));
+            let entry = Compound::new()
+                .with("name", entry.id.to_string())
+                .with("id", id as i32)
+                .with("element", element);
+
+            Tag::from(entry)
+        });
+        let entries = ListTag::new(entries.collect()).expect("entries are compounds");
+
+        Compound::new()
+            .with("type", self.id.to_string())
+            .with("value", entries)
+    }
+
+    /// tags of the registry, with the network ids of their entries
+    pub fn tags(&self) -> RegistryTags {
+        let tags = self
+            .tags
+            .iter()
+            .map(|(name, entries)| TagEntries {
+                name: name.clone(),
+                entries: entries
+                    .iter()
+                    .filter_map(|entry| self.network_id(entry))
+                    .map(VarInt)
+                    .collect(),
+            })
+            .collect();
+
+        RegistryTags {
+            registry: self.id.clone(),
+            tags,
+        }
+    }
+}
+
+/// Ids of the packets sent by [`Registries::configure`], which
+/// moved with the resource packs of 1.20.3 and the cookies of 1.20.5
+struct Ids {
+    flags: i32,
+    tags: i32,
+    finish: i32,
+    acknowledge: i32,
+}
+
+impl Ids {
+    fn new(version: ProtocolVersion) -> Self {
+        match version {
+            v if v >= ProtocolVersion::V1_20_5 => Self {
+                flags: FeatureFlags::ID,
+                tags: UpdateTags::ID,
+                finish: FinishConfiguration::ID,
+                acknowledge: AcknowledgeFinishConfiguration::ID,
+            },
+            v if v >= ProtocolVersion::V1_20_3 => Self {
+                flags: 0x08,
+                tags: 0x09,
+                finish: 0x02,
+                acknowledge: 0x02,
+            },
+            _ => Self {
+                flags: 0x07,
+                tags: 0x08,
+                finish: 0x02,
+                acknowledge: 0x02,
+            },
+        }
+    }
+}
+
+/// Registries of the client during the configuration (1.20.2+), along
+/// with the enabled features.
+///
+/// [`Self::configure`] gets a client from the configuration to the play
+/// state. Vanilla clients need every [synchronized](Self::synchronized)
+/// registry of their version, which can be loaded from the game data with
+/// `Registries::load` (`serde_json` feature), along with the tags of the
+/// [builtin](Registry::builtin) registries
+#[derive(Debug, Clone)]
+pub struct Registries {
+    registries: Vec<Registry>,
+    packs: Vec<KnownPack>,
+    features: Vec<Identifier>,
+}
+
+impl Default for Registries {
+    fn default() -> Self {
+        Self {
+            registries: vec![],
+            packs: vec![],
+            features: vec![Identifier::from_static("minecraft:vanilla")],
+        }
+    }
+}
+
+impl Registries {
+    /// no registries, with the `minecraft:vanilla` feature
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ids of the registries synchronized with the clients of `version`,
+    /// which need all of them. Empty before 1.20.2
+    pub fn synchronized(version: ProtocolVersion) -> Vec<Identifier> {
+        let mut ids = vec![];
+
+        if version >= ProtocolVersion::V1_20_2 {
+            ids.extend([
+                "minecraft:worldgen/biome",
+                "minecraft:chat_type",
+                "minecraft:trim_pattern",
+                "minecraft:trim_material",
+                "minecraft:dimension_type",
+                "minecraft:damage_type",
+            ]);
+        }
+        if version >= ProtocolVersion::V1_20_5 {
+            ids.extend(["minecraft:wolf_variant", "minecraft:banner_pattern"]);
+        }
+        if version >= ProtocolVersion::V1_21 {
+            ids.extend([
+                "minecraft:enchantment",
+                "minecraft:jukebox_song",
+                "minecraft:painting_variant",
+            ]);
+        }
+        if version >= ProtocolVersion::V1_21_2 {
+            ids.push("minecraft:instrument");
+        }
+        if version >= ProtocolVersion::V1_21_5 {
+            ids.extend([
+                "minecraft:wolf_sound_variant",
+                "minecraft:pig_variant",
+                "minecraft:frog_variant",
+                "minecraft:cat_variant",
+                "minecraft:cow_variant",
+                "minecraft:chicken_variant",
+                "minecraft:test_environment",
+                "minecraft:test_instance",
+            ]);
+        }
+
+        ids.into_iter().map(Identifier::from_static).collect()
+    }
+
+    /// Adds `registry`, replacing the one with the same id
+    pub fn insert(&mut self, registry: Registry) {
+        match self.registries.iter_mut().find(|r| r.id == registry.id) {
+            Some(old) => *old = registry,
+            None => self.registries.push(registry),
+        }
+    }
+
+    /// Builder-style [`Self::insert`]
+    pub fn with(mut self, registry: Registry) -> Self {
+        self.insert(registry);
+        self
+    }
+
+    /// registry `id`
+    pub fn get(&self, id: &Identifier) -> Option<&Registry> {
+        self.registries.iter().find(|registry| registry.id == *id)
+    }
+
+    /// registry `id`
+    pub fn get_mut(&mut self, id: &Identifier) -> Option<&mut Registry> {
+        self.registries
+            .iter_mut()
+            .find(|registry| registry.id == *id)
+    }
+
+    /// registries in the order they are sent
+    pub fn iter(&self) -> impl Iterator<Item = &Registry> {
+        self.registries.iter()
+    }
+
+    /// Declares a pack entries come from, like [`KnownPack::core`], see
+    /// [`Registry::insert_from`]. Ignored before 1.20.5
+    pub fn known_pack(mut self, pack: KnownPack) -> Self {
+        self.packs.push(pack);
+        self
+    }
+
+    /// enabled features, `minecraft:vanilla` by default
+    pub fn features(self, features: Vec<Identifier>) -> Self {
+        Self { features, ..self }
+    }
+
+    /// Registry codec of the synchronized registries, sent before 1.20.5
+    pub fn codec(&self) -> RegistryCodec {
+        let mut codec = Compound::new();
+        for registry in self.registries.iter().filter(|r| !r.builtin) {
+            codec.insert(registry.id.to_string(), registry.codec());
+        }
+
+        RegistryCodec {
+            codec: Nbt::from(codec),
+        }
+    }
+
+    /// Synchronizes the registries and sends the tags of every registry to
+    /// the client of `connection`, then finishes the configuration and
+    /// switches the connection to the play state. Returns the other packets
+    /// received meanwhile, like the client information and the brand, in order,
+    /// failing after [`MAX_RECEIVED`] of them
+    pub async fn configure<S, C>(
+        &self,
+        connection: &mut Connection<S, C>,
+    ) -> Result<Vec<RawPacket>, ConfigurationError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+        C: Decoder<Item = RawPacket, Error = CodecError>
+            + for<'a> Encoder<&'a RawPacket, Error = CodecError>,
+    {
+        if connection.state() != State::Configuration {
+            return Err(ConfigurationError::State(connection.state()));
+        }
+        let version = connection.version();
+        if version < ProtocolVersion::V1_20_2 {
+            return Err(ConfigurationError::Version(version));
+        }
+
+        let ids = Ids::new(version);
+        let mut received = vec![];
+
+        let flags = FeatureFlags {
+            flags: self.features.clone(),
+        };
+        send(connection, ids.flags, flags).await?;
+
+        if version >= ProtocolVersion::V1_20_5 {
+            let packs = ClientboundKnownPacks {
+                packs: self.packs.clone(),
+            };
+            send(connection, ClientboundKnownPacks::ID, packs).await?;
+
+            let id = ServerboundKnownPacks::ID;
+            let packet = wait(connection, id, &mut received).await?;
+            let ServerboundKnownPacks { packs } =
+                packet.deserialize_unchecked().map_err(CodecError::from)?;
+
+            // packs of the server the client also has
+            let known: Vec<KnownPack> = packs
+                .into_iter()
+                .filter(|pack| self.packs.contains(pack))
+                .collect();
+            for registry in self.registries.iter().filter(|r| !r.builtin) {
+                let data = registry.data(&known);
+                send(connection, RegistryData::ID, data).await?;
+            }
+        } else {
+            send(connection, RegistryCodec::ID, self.codec()).await?;
+        }
+
+        let registries: Vec<RegistryTags> = self
+            .registries
+            .iter()
+            .filter(|registry| !registry.tags.is_empty())
+            .map(Registry::tags)
+            .collect();
+        if !registries.is_empty() {
+            send(connection, ids.tags, UpdateTags { registries }).await?;
+        }
+
+        send(connection, ids.finish, FinishConfiguration {}).await?;
+        wait(connection, ids.acknowledge, &mut received).await?;
+        connection.set_state(State::Play);
+
+        Ok(received)
+    }
+}
+
+/// sends `packet` with the id `packet_id` of the version
+async fn send<S, C, T>(
+    connection: &mut Connection<S, C>,
+    packet_id: i32,
+    packet: T,
+) -> Result<(), CodecError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: Decoder<Item = RawPacket, Error = CodecError>
+        + for<'a> Encoder<&'a RawPacket, Error = CodecError>,
+    T: Serialize + PacketId,
+{
+    let packet = RawPacket {
+        packet_id,
+        ..RawPacket::from(packet)
+    };
+    connection.send_raw(&packet).await
+}
+
+/// Receives packets until the one with `packet_id`, keeping the other ones in `received`
+async fn wait<S, C>(
+    connection: &mut Connection<S, C>,
+    packet_id: i32,
+    received: &mut Vec<RawPacket>,
+) -> Result<RawPacket, ConfigurationError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: Decoder<Item = RawPacket, Error = CodecError>
+        + for<'a> Encoder<&'a RawPacket, Error = CodecError>,
+{
+    loop {
+        let packet = connection
+            .receive()
+            .await?
+            .ok_or(ConfigurationError::Closed)?;
+
+        if packet.packet_id == packet_id {
+            return Ok(packet);
+        }
+        if received.len() >= MAX_RECEIVED {
+            return Err(ConfigurationError::TooManyPackets);
+        }
+        received.push(packet);
+    }
+}
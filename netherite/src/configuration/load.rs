@@ -0,0 +1,288 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{Registries, Registry};
+use crate::{encoding::identifier::Identifier, nbt::Tag, packet::configuration::KnownPack};
+
+/// Error loading registries from a data pack
+#[derive(Debug, Error)]
+pub enum LoadError {
+    /// File or directory couldn't be read
+    #[error("{}: {source}", path.display())]
+    Io {
+        /// path of the file
+        path: PathBuf,
+        /// io error
+        source: io::Error,
+    },
+
+    /// File isn't valid JSON
+    #[error("{}: {source}", path.display())]
+    Json {
+        /// path of the file
+        path: PathBuf,
+        /// parsing error
+        source: serde_json::Error,
+    },
+
+    /// File name or tag value isn't a valid identifier
+    #[error("{}: invalid identifier {identifier:?}", path.display())]
+    Identifier {
+        /// path of the file
+        path: PathBuf,
+        /// invalid identifier
+        identifier: String,
+    },
+}
+
+fn io(path: &Path) -> impl FnOnce(io::Error) -> LoadError + '_ {
+    |source| LoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+fn identifier(path: &Path, namespace: &str, name: &str) -> Result<Identifier, LoadError> {
+    let identifier = match name.contains(':') {
+        true => Identifier::parse(name),
+        false => Identifier::new(namespace, name),
+    };
+
+    identifier.ok_or_else(|| LoadError::Identifier {
+        path: path.to_path_buf(),
+        identifier: name.to_string(),
+    })
+}
+
+fn read_json(path: &Path) -> Result<Value, LoadError> {
+    let data = fs::read(path).map_err(io(path))?;
+
+    serde_json::from_slice(&data).map_err(|source| LoadError::Json {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// `.json` files under `dir` with their name relative to it,
+/// without the extension. Missing directories have no files
+fn files(dir: &Path) -> Result<Vec<(String, PathBuf)>, LoadError> {
+    let mut files = vec![];
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+
+    let mut pending = vec![(String::new(), dir.to_path_buf())];
+    while let Some((prefix, dir)) = pending.pop() {
+        for entry in fs::read_dir(&dir).map_err(io(&dir))? {
+            let path = entry.map_err(io(&dir))?.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+
+            if path.is_dir() {
+                pending.push((format!("{prefix}{name}/"), path));
+            } else if let Some(name) = name.strip_suffix(".json") {
+                files.push((format!("{prefix}{name}"), path));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// directory of a registry in a namespace, vanilla registries don't repeat their namespace
+fn directory(registry: &Identifier) -> String {
+    match registry.namespace() {
+        "minecraft" => registry.path().to_string(),
+        namespace => format!("{namespace}/{}", registry.path()),
+    }
+}
+
+/// Tag directories of a registry, builtin registries had plural names before 1.21
+fn tag_directories(registry: &Identifier) -> Vec<String> {
+    let directory = directory(registry);
+    let plural = ["block", "item", "fluid", "entity_type", "game_event"];
+
+    match plural.contains(&directory.as_str()) {
+        true => vec![format!("{directory}s"), directory],
+        false => vec![directory],
+    }
+}
+
+/// Values of a tag file: entries, and other tags prefixed with `#`
+fn tag_values(path: &Path, namespace: &str) -> Result<Vec<(bool, Identifier)>, LoadError> {
+    let json = read_json(path)?;
+    let values = json["values"].as_array().cloned().unwrap_or_default();
+
+    values
+        .iter()
+        .filter_map(|value| match value {
+            Value::String(value) => Some(value.as_str()),
+            value => value["id"].as_str(),
+        })
+        .map(|value| match value.strip_prefix('#') {
+            Some(tag) => Ok((true, identifier(path, namespace, tag)?)),
+            None => Ok((false, identifier(path, namespace, value)?)),
+        })
+        .collect()
+}
+
+/// entries of tag `name`, following the nested tags once
+fn resolve(
+    name: &Identifier,
+    tags: &HashMap<Identifier, Vec<(bool, Identifier)>>,
+    visited: &mut HashSet<Identifier>,
+    entries: &mut Vec<Identifier>,
+) {
+    if !visited.insert(name.clone()) {
+        return;
+    }
+
+    for (nested, value) in tags.get(name).into_iter().flatten() {
+        match nested {
+            true => resolve(value, tags, visited, entries),
+            false if !entries.contains(value) => entries.push(value.clone()),
+            false => {}
+        }
+    }
+}
+
+/// Reads the tags of `registry` from every namespace, following the nested tags
+fn load_tags(data: &Path, namespaces: &[String], registry: &mut Registry) -> Result<(), LoadError> {
+    let mut tags = HashMap::new();
+    for namespace in namespaces {
+        for directory in tag_directories(&registry.id) {
+            for (name, path) in files(&data.join(namespace).join("tags").join(directory))? {
+                let tag = identifier(&path, namespace, &name)?;
+                tags.insert(tag, tag_values(&path, namespace)?);
+            }
+        }
+    }
+
+    let mut names: Vec<&Identifier> = tags.keys().collect();
+    names.sort_by(|a, b| str::cmp(a, b));
+    for name in names {
+        let mut entries = vec![];
+        resolve(name, &tags, &mut HashSet::new(), &mut entries);
+        registry.insert_tag(name.clone(), entries);
+    }
+
+    Ok(())
+}
+
+fn load_registry(
+    data: &Path,
+    namespaces: &[String],
+    id: &Identifier,
+    pack: Option<&KnownPack>,
+) -> Result<Registry, LoadError> {
+    let mut registry = Registry::new(id.clone());
+    let directory = directory(id);
+
+    let mut entries = vec![];
+    for namespace in namespaces {
+        for (name, path) in files(&data.join(namespace).join(&directory))? {
+            let entry = identifier(&path, namespace, &name)?;
+            let data = Tag::from_json(&read_json(&path)?);
+            entries.extend(data.map(|data| (entry, data)));
+        }
+    }
+
+    entries.sort_by(|(a, _), (b, _)| str::cmp(a, b));
+    for (entry, data) in entries {
+        match pack {
+            Some(pack) if entry.namespace() == "minecraft" => {
+                registry.insert_from(pack, entry, data)
+            }
+            _ => registry.insert(entry, data),
+        }
+    }
+
+    load_tags(data, namespaces, &mut registry)?;
+    Ok(registry)
+}
+
+/// Builtin registries of the `registries.json` report, with their entries
+/// in network id order. No registries if the report is missing
+fn load_builtin(report: &Path) -> Result<Vec<Registry>, LoadError> {
+    if !report.is_file() {
+        return Ok(vec![]);
+    }
+
+    let json = read_json(report)?;
+    let Some(report_registries) = json.as_object() else {
+        return Ok(vec![]);
+    };
+
+    let mut registries = vec![];
+    for (id, registry) in report_registries {
+        let id = identifier(report, "minecraft", id)?;
+
+        let mut entries = vec![];
+        for (entry, value) in registry["entries"].as_object().into_iter().flatten() {
+            let entry = identifier(report, "minecraft", entry)?;
+            entries.push((value["protocol_id"].as_i64().unwrap_or(i64::MAX), entry));
+        }
+        entries.sort_by_key(|(protocol_id, _)| *protocol_id);
+
+        let entries = entries.into_iter().map(|(_, entry)| entry).collect();
+        registries.push(Registry::builtin(id, entries));
+    }
+
+    Ok(registries)
+}
+
+impl Registries {
+    /// Loads `registries`, like the [synchronized](Self::synchronized) ones,
+    /// from the data generated by the game at `root`. Entries are read from
+    /// the JSON files of `data/<namespace>/<registry path>` and tags from the
+    /// ones of `data/<namespace>/tags/<registry path>`, then sorted by id.
+    ///
+    /// The entries of the `minecraft` namespace come from `pack`, like
+    /// [`KnownPack::core`], which is declared: clients that have it don't
+    /// receive their data. Leave it out if they were modified.
+    ///
+    /// The tags of the builtin registries listed by `reports/registries.json`,
+    /// like `minecraft:block`, are loaded too when the report is present
+    pub fn load(
+        root: impl AsRef<Path>,
+        registries: &[Identifier],
+        pack: Option<&KnownPack>,
+    ) -> Result<Self, LoadError> {
+        let root = root.as_ref();
+        let data = root.join("data");
+
+        let mut namespaces = vec![];
+        for entry in fs::read_dir(&data).map_err(io(&data))? {
+            let path = entry.map_err(io(&data))?.path();
+            if let (true, Some(name)) = (path.is_dir(), path.file_name().and_then(|n| n.to_str())) {
+                namespaces.push(name.to_string());
+            }
+        }
+        namespaces.sort();
+
+        let mut loaded = Self::new();
+        if let Some(pack) = pack {
+            loaded = loaded.known_pack(pack.clone());
+        }
+
+        for registry in registries {
+            loaded.insert(load_registry(&data, &namespaces, registry, pack)?);
+        }
+
+        for mut builtin in load_builtin(&root.join("reports").join("registries.json"))? {
+            load_tags(&data, &namespaces, &mut builtin)?;
+            if !builtin.tags.is_empty() && loaded.get(&builtin.id).is_none() {
+                loaded.insert(builtin);
+            }
+        }
+
+        Ok(loaded)
+    }
+}
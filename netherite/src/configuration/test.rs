@@ -0,0 +1,384 @@
+use bytes::Bytes;
+use tokio::io::{duplex, DuplexStream};
+
+use super::{ConfigurationError, Registries, Registry, MAX_RECEIVED};
+use crate::{
+    connection::Connection,
+    encoding::{identifier::Identifier, varint::VarInt},
+    login::{ClientLogin, ServerLogin},
+    nbt::{Compound, Nbt, Tag},
+    packet::{
+        configuration::{
+            AcknowledgeFinishConfiguration, ClientboundKnownPacks, FeatureFlags,
+            FinishConfiguration, KnownPack, RegistryCodec, RegistryData, ServerboundKnownPacks,
+            UpdateTags,
+        },
+        RawPacket,
+    },
+    protocol::{ProtocolVersion as V, State},
+};
+
+fn id(identifier: &'static str) -> Identifier {
+    Identifier::from_static(identifier)
+}
+
+fn dimension(height: i32) -> Compound {
+    Compound::new().with("height", height)
+}
+
+fn registries() -> Registries {
+    let core = KnownPack::core("1.21.5");
+    let mut dimensions = Registry::new(id("minecraft:dimension_type"));
+    dimensions.insert_from(&core, id("minecraft:overworld"), dimension(384));
+    dimensions.insert_from(&core, id("minecraft:the_end"), dimension(256));
+    dimensions.insert(id("custom:mines"), dimension(64));
+    dimensions.insert_tag(
+        id("minecraft:tall"),
+        vec![id("minecraft:overworld"), id("minecraft:missing")],
+    );
+
+    let blocks = vec![id("minecraft:air"), id("minecraft:stone")];
+    let mut blocks = Registry::builtin(id("minecraft:block"), blocks);
+    blocks.insert_tag(id("minecraft:mineable"), vec![id("minecraft:stone")]);
+
+    Registries::new()
+        .with(dimensions)
+        .with(blocks)
+        .known_pack(core)
+}
+
+async fn connect(version: V) -> (Connection<DuplexStream>, Connection<DuplexStream>) {
+    let (client, server) = duplex(4096);
+
+    let client = ClientLogin::new("Notch", version).login(client);
+    let server = ServerLogin::new().accept(server);
+
+    let (client, server) = futures::join!(client, server);
+    (client.unwrap(), server.unwrap())
+}
+
+async fn receive(client: &mut Connection<DuplexStream>) -> RawPacket {
+    client.receive().await.unwrap().unwrap()
+}
+
+/// Client side of the configuration, knowing `packs`.
+/// Returns the registries and tags received
+async fn client(
+    client: &mut Connection<DuplexStream>,
+    packs: Vec<KnownPack>,
+) -> (Vec<RegistryData>, Vec<UpdateTags>) {
+    // client information, sent right after the login
+    let information = RawPacket {
+        packet_id: 0x00,
+        data: Bytes::from_static(b"info"),
+    };
+    client.send_raw(&information).await.unwrap();
+
+    let flags: FeatureFlags = receive(client).await.deserialize().unwrap().unwrap();
+    assert_eq!(flags.flags, [id("minecraft:vanilla")]);
+
+    let packet = receive(client).await;
+    let server_packs: ClientboundKnownPacks = packet.deserialize().unwrap().unwrap();
+    assert_eq!(server_packs.packs, [KnownPack::core("1.21.5")]);
+    client.send(ServerboundKnownPacks { packs }).await.unwrap();
+
+    let (mut registries, mut tags) = (vec![], vec![]);
+    loop {
+        let packet = receive(client).await;
+
+        if let Some(data) = packet.deserialize::<RegistryData>() {
+            registries.push(data.unwrap());
+        } else if let Some(update) = packet.deserialize::<UpdateTags>() {
+            tags.push(update.unwrap());
+        } else {
+            assert!(packet.is::<FinishConfiguration>());
+            break;
+        }
+    }
+
+    client
+        .send(AcknowledgeFinishConfiguration {})
+        .await
+        .unwrap();
+    (registries, tags)
+}
+
+#[test]
+fn registry() {
+    let registries = registries();
+    let dimensions = registries.get(&id("minecraft:dimension_type")).unwrap();
+
+    assert_eq!(dimensions.network_id(&id("minecraft:the_end")), Some(1));
+    assert_eq!(
+        dimensions.get(&id("minecraft:overworld")),
+        Some(&Tag::from(dimension(384)))
+    );
+
+    let core = KnownPack::core("1.21.5");
+    assert_eq!(dimensions.pack(&id("minecraft:the_end")), Some(&core));
+    assert_eq!(dimensions.pack(&id("custom:mines")), None);
+
+    let mut replaced = dimensions.clone();
+    replaced.insert(id("minecraft:overworld"), dimension(256));
+    assert_eq!(replaced.network_id(&id("minecraft:overworld")), Some(0));
+    assert_eq!(replaced.pack(&id("minecraft:overworld")), None);
+    assert_eq!(replaced.len(), 3);
+
+    let tags = dimensions.tags();
+    assert_eq!(tags.tags[0].entries, [VarInt(0)]);
+
+    // custom entries keep their data even for clients knowing the pack
+    let data = dimensions.data(&[core]);
+    assert_eq!(data.entries[0].data, None);
+    assert_eq!(data.entries[1].data, None);
+    assert_eq!(data.entries[2].data, Some(Nbt::from(dimension(64))));
+    let data = dimensions.data(&[]);
+    assert_eq!(data.entries[1].data, Some(Nbt::from(dimension(256))));
+
+    let blocks = registries.get(&id("minecraft:block")).unwrap();
+    assert!(blocks.is_builtin());
+    assert_eq!(blocks.get(&id("minecraft:stone")), None);
+    assert_eq!(blocks.tags().tags[0].entries, [VarInt(1)]);
+}
+
+#[test]
+fn codec() {
+    let codec = registries().codec().codec.0.unwrap();
+    let codec = codec.as_compound().unwrap();
+
+    // builtin registries aren't synchronized
+    assert_eq!(codec.len(), 1);
+    let dimensions = codec.get("minecraft:dimension_type").unwrap();
+    let dimensions = dimensions.as_compound().unwrap();
+    assert_eq!(
+        dimensions.get("type").unwrap().as_str(),
+        Some("minecraft:dimension_type")
+    );
+
+    let entries = dimensions.get("value").unwrap().as_list().unwrap().items();
+    let expected = Compound::new()
+        .with("name", "custom:mines")
+        .with("id", 2)
+        .with("element", dimension(64));
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[2], Tag::from(expected));
+}
+
+#[test]
+fn synchronized() {
+    let synchronized = Registries::synchronized;
+    assert!(synchronized(V::V1_20).is_empty());
+    assert_eq!(synchronized(V::V1_20_3).len(), 6);
+    assert_eq!(synchronized(V::V1_21).len(), 11);
+    assert!(synchronized(V::V1_21_5).contains(&id("minecraft:pig_variant")));
+    assert!(!synchronized(V::V1_21_4).contains(&id("minecraft:pig_variant")));
+}
+
+#[tokio::test]
+async fn configure() {
+    let registries = registries();
+
+    for packs in [vec![KnownPack::core("1.21.5")], vec![]] {
+        let (mut client_connection, mut server) = connect(V::V1_21_5).await;
+
+        let known = !packs.is_empty();
+        let client = client(&mut client_connection, packs);
+        let configured = registries.configure(&mut server);
+
+        let ((data, tags), received) = futures::join!(client, configured);
+        let received = received.unwrap();
+
+        assert_eq!(server.state(), State::Play);
+        assert_eq!(received.len(), 1);
+        assert_eq!(&received[0].data[..], b"info");
+
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].registry, id("minecraft:dimension_type"));
+        assert_eq!(data[0].entries.len(), 3);
+        assert_eq!(data[0].entries[0].data.is_none(), known);
+        assert!(data[0].entries[2].data.is_some());
+
+        // tags of the builtin registries too
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].registries.len(), 2);
+        assert_eq!(tags[0].registries[0].tags[0].name, id("minecraft:tall"));
+        assert_eq!(tags[0].registries[1].registry, id("minecraft:block"));
+    }
+}
+
+#[tokio::test]
+async fn configure_codec() {
+    let registries = registries();
+
+    for (version, flags, tags) in [(V::V1_20_3, 0x08, 0x09), (V::V1_20_2, 0x07, 0x08)] {
+        let (mut client, mut server) = connect(version).await;
+
+        let client = async {
+            // before 1.20.5, the packs aren't negotiated
+            assert_eq!(receive(&mut client).await.packet_id, flags);
+
+            let codec = receive(&mut client).await;
+            let codec: RegistryCodec = codec.deserialize().unwrap().unwrap();
+            assert_eq!(codec, registries.codec());
+
+            let packet = receive(&mut client).await;
+            assert_eq!(packet.packet_id, tags);
+            let update: UpdateTags = packet.deserialize_unchecked().unwrap();
+            assert_eq!(update.registries.len(), 2);
+
+            assert_eq!(receive(&mut client).await.packet_id, 0x02);
+            let acknowledge = RawPacket {
+                packet_id: 0x02,
+                data: Bytes::new(),
+            };
+            client.send_raw(&acknowledge).await.unwrap();
+        };
+
+        let ((), received) = futures::join!(client, registries.configure(&mut server));
+        assert!(received.unwrap().is_empty());
+        assert_eq!(server.state(), State::Play);
+    }
+}
+
+#[tokio::test]
+async fn unsupported() {
+    let (_client, mut server) = connect(V::V1_20).await;
+
+    server.set_state(State::Configuration);
+    let res = registries().configure(&mut server).await;
+    assert!(matches!(res, Err(ConfigurationError::Version(V::V1_20))));
+
+    server.set_state(State::Play);
+    let res = registries().configure(&mut server).await;
+    assert!(matches!(res, Err(ConfigurationError::State(State::Play))));
+}
+
+#[tokio::test]
+async fn too_many_packets() {
+    let registries = registries();
+    let (mut client, mut server) = connect(V::V1_21_5).await;
+
+    let client = async {
+        let brand = RawPacket {
+            packet_id: 0x02,
+            data: Bytes::from_static(b"brand"),
+        };
+        for _ in 0..=MAX_RECEIVED {
+            client.send_raw(&brand).await.unwrap();
+        }
+    };
+
+    let ((), res) = futures::join!(client, registries.configure(&mut server));
+    assert!(matches!(res, Err(ConfigurationError::TooManyPackets)));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn load() {
+    use std::fs;
+
+    let root = std::env::temp_dir().join(format!("netherite-registries-{}", std::process::id()));
+    let files = [
+        (
+            "minecraft/dimension_type/the_end.json",
+            r#"{"height": 256}"#,
+        ),
+        (
+            "minecraft/dimension_type/overworld.json",
+            r#"{"height": 384}"#,
+        ),
+        (
+            "minecraft/worldgen/biome/plains.json",
+            r#"{"downfall": 0.4}"#,
+        ),
+        (
+            "custom/worldgen/biome/caves/deep.json",
+            r#"{"downfall": 0}"#,
+        ),
+        (
+            "minecraft/tags/worldgen/biome/is_overworld.json",
+            r##"{"values": ["plains", "#custom:underground", {"id": "missing", "required": false}]}"##,
+        ),
+        (
+            "custom/tags/worldgen/biome/underground.json",
+            r#"{"values": ["custom:caves/deep"]}"#,
+        ),
+        (
+            "minecraft/tags/block/mineable.json",
+            r#"{"values": ["stone"]}"#,
+        ),
+        ("minecraft/tags/blocks/old.json", r#"{"values": ["air"]}"#),
+    ];
+    let report = r#"{
+        "minecraft:block": {"entries": {
+            "minecraft:stone": {"protocol_id": 1},
+            "minecraft:air": {"protocol_id": 0}
+        }},
+        "minecraft:item": {"entries": {}}
+    }"#;
+
+    for (path, content) in files {
+        let path = root.join("data").join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    fs::create_dir_all(root.join("reports")).unwrap();
+    fs::write(root.join("reports/registries.json"), report).unwrap();
+
+    let registries = [
+        id("minecraft:dimension_type"),
+        id("minecraft:worldgen/biome"),
+    ];
+    let core = KnownPack::core("1.21.5");
+    let loaded = Registries::load(&root, &registries, Some(&core));
+    fs::remove_dir_all(&root).unwrap();
+    let loaded = loaded.unwrap();
+
+    let dimensions = loaded.get(&registries[0]).unwrap();
+    let entries: Vec<_> = dimensions.entries().map(|id| id.to_string()).collect();
+    assert_eq!(entries, ["minecraft:overworld", "minecraft:the_end"]);
+    assert_eq!(
+        dimensions.get(&id("minecraft:the_end")),
+        Some(&Tag::from(dimension(256)))
+    );
+
+    let biomes = loaded.get(&registries[1]).unwrap();
+    let entries: Vec<_> = biomes.entries().map(|id| id.to_string()).collect();
+    assert_eq!(entries, ["custom:caves/deep", "minecraft:plains"]);
+    assert_eq!(
+        biomes.tag(&id("minecraft:is_overworld")).unwrap(),
+        [
+            id("minecraft:plains"),
+            id("custom:caves/deep"),
+            id("minecraft:missing")
+        ]
+    );
+
+    let tags = biomes.tags();
+    let overworld = tags
+        .tags
+        .iter()
+        .find(|tag| tag.name == id("minecraft:is_overworld"));
+    assert_eq!(overworld.unwrap().entries, [VarInt(1), VarInt(0)]);
+
+    // only vanilla entries come from the pack
+    assert_eq!(biomes.pack(&id("minecraft:plains")), Some(&core));
+    assert_eq!(biomes.pack(&id("custom:caves/deep")), None);
+
+    // builtin registries without tags are left out
+    let blocks = loaded.get(&id("minecraft:block")).unwrap();
+    assert!(blocks.is_builtin());
+    assert_eq!(blocks.network_id(&id("minecraft:stone")), Some(1));
+    assert_eq!(
+        blocks.tag(&id("minecraft:mineable")).unwrap(),
+        [id("minecraft:stone")]
+    );
+    assert_eq!(
+        blocks.tag(&id("minecraft:old")).unwrap(),
+        [id("minecraft:air")]
+    );
+    assert!(loaded.get(&id("minecraft:item")).is_none());
+
+    let res = Registries::load(root.join("missing"), &registries, None);
+    assert!(matches!(res, Err(super::LoadError::Io { .. })));
+}
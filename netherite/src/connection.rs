@@ -0,0 +1,269 @@
+use std::{collections::HashMap, net::IpAddr};
+
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+#[cfg(feature = "crypto")]
+use crate::codec::encrypted::Encrypted;
+use crate::{
+    codec::{dual::SwitchCodec, CodecError},
+    encoding::{identifier::Identifier, packetid::PacketId, str::Str, varint::VarInt},
+    packet::{
+        configuration::Transfer,
+        login::{LoginSuccess, Property},
+        RawPacket,
+    },
+    protocol::{Direction, ProtocolVersion, State},
+    registry::{Key, PacketRegistry},
+    Serialize,
+};
+
+/// split halves writing from a background task
+mod split;
+
+pub use split::{PacketReceiver, PacketSender, PacketWriter, SendError};
+
+/// Player the connection belongs to, as known by the session server
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameProfile {
+    /// uuid of the player
+    pub uuid: u128,
+    /// name of the player
+    pub username: Str,
+    /// profile properties, like the skin textures
+    pub properties: Vec<Property>,
+}
+
+impl From<&GameProfile> for LoginSuccess {
+    fn from(profile: &GameProfile) -> Self {
+        Self {
+            uuid: profile.uuid,
+            username: profile.username.clone(),
+            properties: profile.properties.clone(),
+            strict_error_handling: false,
+        }
+    }
+}
+
+/// Error transferring the player to another server
+#[derive(Debug, Error)]
+pub enum TransferError {
+    /// Transfers need 1.20.5+
+    #[error("transfers are not supported by {0}")]
+    Version(ProtocolVersion),
+
+    /// Id of the transfer packet isn't known in the state
+    #[error("no transfer packet in state {0:?}")]
+    State(State),
+
+    /// Error writing the packet
+    #[error("codec: {0}")]
+    Codec(#[from] CodecError),
+}
+
+/// Connection of a player that completed the login, in the
+/// [`State::Configuration`] (1.20.2+) or [`State::Play`] state.
+///
+/// Compression is already set up, so packets can be sent and
+/// received without caring about the framing. Online mode
+/// connections also have an encryption layer in their codec
+pub struct Connection<S, C = SwitchCodec> {
+    framed: Framed<S, C>,
+    version: ProtocolVersion,
+    state: State,
+    profile: GameProfile,
+    address: Option<IpAddr>,
+    transferred: bool,
+    cookies: HashMap<Identifier, Bytes>,
+}
+
+impl<S, C> Connection<S, C>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: Decoder<Item = RawPacket, Error = CodecError>
+        + for<'a> Encoder<&'a RawPacket, Error = CodecError>,
+{
+    /// wraps a connection which completed the login
+    pub fn new(
+        framed: Framed<S, C>,
+        version: ProtocolVersion,
+        state: State,
+        profile: GameProfile,
+    ) -> Self {
+        Self {
+            framed,
+            version,
+            state,
+            profile,
+            address: None,
+            transferred: false,
+            cookies: HashMap::new(),
+        }
+    }
+
+    /// address of the player forwarded by a proxy
+    pub fn address(self, address: Option<IpAddr>) -> Self {
+        Self { address, ..self }
+    }
+
+    /// whether the player was transferred from another server (1.20.5+)
+    pub fn transferred(self, transferred: bool) -> Self {
+        Self {
+            transferred,
+            ..self
+        }
+    }
+
+    /// cookies sent by the player during the login
+    pub fn cookies(self, cookies: HashMap<Identifier, Bytes>) -> Self {
+        Self { cookies, ..self }
+    }
+
+    /// protocol version of the connection
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// current state of the connection
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Sets the state of the connection, after exchanging
+    /// the packets that switch it
+    pub fn set_state(&mut self, state: State) {
+        self.state = state
+    }
+
+    /// player of the connection
+    pub fn profile(&self) -> &GameProfile {
+        &self.profile
+    }
+
+    /// Address the player connected from, as forwarded by the proxy
+    /// they came through. `None` without forwarding: the address of
+    /// the stream is the one of the player
+    pub fn get_address(&self) -> Option<IpAddr> {
+        self.address
+    }
+
+    /// Whether the player connected with the transfer intent,
+    /// after being transferred by another server
+    pub fn is_transferred(&self) -> bool {
+        self.transferred
+    }
+
+    /// Cookie the player had stored under `key`, if the
+    /// server requested it during the login
+    pub fn get_cookie(&self, key: &Identifier) -> Option<&Bytes> {
+        self.cookies.get(key)
+    }
+
+    /// underlying framed stream
+    pub fn framed(&mut self) -> &mut Framed<S, C> {
+        &mut self.framed
+    }
+
+    /// returns the underlying framed stream
+    pub fn into_framed(self) -> Framed<S, C> {
+        self.framed
+    }
+
+    /// sends `packet` and flushes the stream
+    pub async fn send<T>(&mut self, packet: T) -> Result<(), CodecError>
+    where
+        T: Serialize + PacketId,
+        C: Encoder<T, Error = CodecError>,
+    {
+        self.framed.send(packet).await
+    }
+
+    /// sends a raw `packet` and flushes the stream
+    pub async fn send_raw(&mut self, packet: &RawPacket) -> Result<(), CodecError> {
+        self.framed.send(packet).await
+    }
+
+    /// receives the next packet, `None` if the stream ended
+    pub async fn receive(&mut self) -> Result<Option<RawPacket>, CodecError> {
+        self.framed.next().await.transpose()
+    }
+
+    /// Transfers the player to the server at `host:port`, which it joins with
+    /// the [`Intent::Transfer`](crate::packet::handshake::Intent::Transfer)
+    /// and its cookies (1.20.5+). In the play state, where the id of the
+    /// packet depends on the version, use [`Self::transfer_with`]
+    pub async fn transfer(&mut self, host: &str, port: u16) -> Result<(), TransferError> {
+        self.send_transfer(None, host, port).await
+    }
+
+    /// Transfers the player like [`Self::transfer`], looking up the id of
+    /// the play packet as `transfer` in `registry`
+    pub async fn transfer_with(
+        &mut self,
+        registry: &PacketRegistry,
+        host: &str,
+        port: u16,
+    ) -> Result<(), TransferError> {
+        self.send_transfer(Some(registry), host, port).await
+    }
+
+    async fn send_transfer(
+        &mut self,
+        registry: Option<&PacketRegistry>,
+        host: &str,
+        port: u16,
+    ) -> Result<(), TransferError> {
+        if self.version < ProtocolVersion::V1_20_5 {
+            return Err(TransferError::Version(self.version));
+        }
+
+        let key = Key::new(self.version, self.state, Direction::Clientbound);
+        let packet_id = match self.state {
+            State::Configuration => Some(Transfer::ID),
+            _ => registry.and_then(|registry| registry.id(key, "transfer")),
+        };
+        let Some(packet_id) = packet_id else {
+            return Err(TransferError::State(self.state));
+        };
+
+        let transfer = Transfer {
+            host: Str::from(host.to_string()),
+            port: VarInt(port as i32),
+        };
+        let packet = RawPacket {
+            packet_id,
+            ..RawPacket::from(transfer)
+        };
+
+        Ok(self.send_raw(&packet).await?)
+    }
+
+    /// Splits the connection, so that packets can be sent from several tasks
+    /// while another one receives. Senders wait while `capacity` packets (at
+    /// least one) are queued, whatever the number of clones; the
+    /// [`PacketWriter`] has to be spawned for them to be written
+    pub fn split(self, capacity: usize) -> (PacketSender, PacketReceiver<S, C>, PacketWriter<S, C>)
+    where
+        C: Unpin,
+    {
+        split::split(self.framed, capacity)
+    }
+}
+
+impl<S> Connection<S> {
+    /// compression threshold, if enabled
+    pub fn compression(&self) -> Option<usize> {
+        self.framed.codec().threshold()
+    }
+}
+
+#[cfg(feature = "crypto")]
+impl<S> Connection<S, Encrypted<SwitchCodec>> {
+    /// compression threshold, if enabled
+    pub fn compression(&self) -> Option<usize> {
+        self.framed.codec().inner().threshold()
+    }
+}
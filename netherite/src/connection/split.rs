@@ -0,0 +1,248 @@
+use std::{
+    pin::{pin, Pin},
+    task::{Context, Poll},
+};
+
+use futures::{
+    channel::oneshot,
+    future::{select, Either},
+    stream::{SplitSink, SplitStream},
+    Sink, SinkExt, Stream, StreamExt,
+};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::mpsc,
+};
+use tokio_util::{
+    codec::{Decoder, Encoder, Framed},
+    sync::CancellationToken,
+};
+
+use crate::{codec::CodecError, encoding::packetid::PacketId, packet::RawPacket, Serialize};
+
+#[cfg(test)]
+mod test;
+
+/// The writer stopped, because the connection was closed or failed
+#[derive(Debug, Error)]
+#[error("connection closed")]
+pub struct SendError;
+
+/// [`Framed`] sinking owned packets, so that it can be split
+struct Frames<S, C>(Framed<S, C>);
+
+impl<S, C> Stream for Frames<S, C>
+where
+    S: AsyncRead + Unpin,
+    C: Decoder<Item = RawPacket, Error = CodecError> + Unpin,
+{
+    type Item = Result<RawPacket, CodecError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+impl<S, C> Sink<RawPacket> for Frames<S, C>
+where
+    S: AsyncWrite + Unpin,
+    C: for<'a> Encoder<&'a RawPacket, Error = CodecError> + Unpin,
+{
+    type Error = CodecError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), CodecError>> {
+        Sink::<&RawPacket>::poll_ready(Pin::new(&mut self.0), cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, packet: RawPacket) -> Result<(), CodecError> {
+        Pin::new(&mut self.0).start_send(&packet)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), CodecError>> {
+        Sink::<&RawPacket>::poll_flush(Pin::new(&mut self.0), cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), CodecError>> {
+        Sink::<&RawPacket>::poll_close(Pin::new(&mut self.0), cx)
+    }
+}
+
+pub(super) fn split<S, C>(
+    framed: Framed<S, C>,
+    capacity: usize,
+) -> (PacketSender, PacketReceiver<S, C>, PacketWriter<S, C>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: Decoder<Item = RawPacket, Error = CodecError>
+        + for<'a> Encoder<&'a RawPacket, Error = CodecError>
+        + Unpin,
+{
+    let (sink, stream) = Frames(framed).split();
+    let (sender, packets) = mpsc::channel(capacity.max(1));
+    let (disconnect, disconnected) = oneshot::channel();
+    let closed = CancellationToken::new();
+
+    let receiver = PacketReceiver {
+        stream,
+        disconnect: Some(disconnect),
+    };
+    let writer = PacketWriter {
+        sink,
+        packets,
+        closed: closed.clone(),
+        disconnected: Some(disconnected),
+    };
+
+    (PacketSender { sender, closed }, receiver, writer)
+}
+
+/// Handle queueing packets for the [`PacketWriter`] of a split connection.
+///
+/// Clones write to the same connection, so every task can own one.
+/// Sending waits while the queue is full
+#[derive(Debug, Clone)]
+pub struct PacketSender {
+    sender: mpsc::Sender<RawPacket>,
+    closed: CancellationToken,
+}
+
+impl PacketSender {
+    /// queues `packet`, waiting for room in the queue
+    pub async fn send<T: Serialize + PacketId>(&mut self, packet: T) -> Result<(), SendError> {
+        self.send_raw(packet.into()).await
+    }
+
+    /// queues a raw `packet`, waiting for room in the queue
+    pub async fn send_raw(&mut self, packet: RawPacket) -> Result<(), SendError> {
+        if self.closed.is_cancelled() {
+            return Err(SendError);
+        }
+
+        self.sender.send(packet).await.map_err(|_| SendError)
+    }
+
+    /// whether the writer stopped, or was closed
+    pub fn is_closed(&self) -> bool {
+        self.closed.is_cancelled() || self.sender.is_closed()
+    }
+
+    /// Stops the writer for every clone, once the
+    /// packets already queued are written
+    pub fn close(&mut self) {
+        self.closed.cancel()
+    }
+}
+
+/// Read half of a split connection, a stream of the received packets.
+///
+/// The end of the stream or an error stops the [`PacketWriter`]
+pub struct PacketReceiver<S, C> {
+    stream: SplitStream<Frames<S, C>>,
+    disconnect: Option<oneshot::Sender<()>>,
+}
+
+impl<S, C> PacketReceiver<S, C>
+where
+    S: AsyncRead + Unpin,
+    C: Decoder<Item = RawPacket, Error = CodecError> + Unpin,
+{
+    /// receives the next packet, `None` if the stream ended
+    pub async fn receive(&mut self) -> Result<Option<RawPacket>, CodecError> {
+        self.next().await.transpose()
+    }
+}
+
+impl<S, C> Stream for PacketReceiver<S, C>
+where
+    S: AsyncRead + Unpin,
+    C: Decoder<Item = RawPacket, Error = CodecError> + Unpin,
+{
+    type Item = Result<RawPacket, CodecError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = futures::ready!(self.stream.poll_next_unpin(cx));
+
+        if !matches!(item, Some(Ok(_))) {
+            if let Some(disconnect) = self.disconnect.take() {
+                let _ = disconnect.send(());
+            }
+        }
+
+        Poll::Ready(item)
+    }
+}
+
+/// Write half of a split connection, writing the queued packets
+/// until the connection closes. It must be polled for packets to be
+/// sent, usually by spawning [`Self::run`].
+///
+/// Packets queued together are written with a single flush
+pub struct PacketWriter<S, C> {
+    sink: SplitSink<Frames<S, C>, RawPacket>,
+    packets: mpsc::Receiver<RawPacket>,
+    closed: CancellationToken,
+    disconnected: Option<oneshot::Receiver<()>>,
+}
+
+impl<S, C> PacketWriter<S, C>
+where
+    S: AsyncWrite + Unpin,
+    C: for<'a> Encoder<&'a RawPacket, Error = CodecError> + Unpin,
+{
+    /// Writes the queued packets until every [`PacketSender`] is dropped or
+    /// closed, or the [`PacketReceiver`] reaches the end of the stream.
+    /// The write half is then shut down
+    pub async fn run(mut self) -> Result<(), CodecError> {
+        let res = self.write().await;
+        self.packets.close();
+
+        match res {
+            Ok(()) => self.sink.close().await,
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn write(&mut self) -> Result<(), CodecError> {
+        loop {
+            let next = next(&mut self.packets, &self.closed);
+            let packet = match &mut self.disconnected {
+                Some(disconnected) => match select(pin!(next), disconnected).await {
+                    Either::Left((packet, _)) => packet,
+                    Either::Right((Ok(()), _)) => return Ok(()),
+                    // the receiver was dropped without disconnecting
+                    Either::Right((Err(_), _)) => {
+                        self.disconnected = None;
+                        continue;
+                    }
+                },
+                None => next.await,
+            };
+
+            let Some(packet) = packet else {
+                return Ok(());
+            };
+            self.sink.feed(packet).await?;
+
+            while let Ok(packet) = self.packets.try_recv() {
+                self.sink.feed(packet).await?;
+            }
+            self.sink.flush().await?;
+        }
+    }
+}
+
+/// Next queued packet, `None` once the senders are dropped,
+/// or closed and the packets already queued are taken
+async fn next(
+    packets: &mut mpsc::Receiver<RawPacket>,
+    closed: &CancellationToken,
+) -> Option<RawPacket> {
+    let cancelled = pin!(closed.cancelled());
+    if let Either::Left((packet, _)) = select(pin!(packets.recv()), cancelled).await {
+        return packet;
+    }
+
+    packets.close();
+    packets.recv().await
+}
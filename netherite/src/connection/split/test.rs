@@ -0,0 +1,120 @@
+use bytes::Bytes;
+use futures::{FutureExt, StreamExt};
+use tokio::io::{duplex, DuplexStream};
+use tokio_util::codec::Framed;
+
+use crate::{
+    connection::{Connection, GameProfile},
+    encoding::str::Str,
+    packet::{status::PingRequest, RawPacket},
+    protocol::{ProtocolVersion as V, State},
+    UncompressedCodec,
+};
+
+fn connection(stream: DuplexStream) -> Connection<DuplexStream, UncompressedCodec> {
+    let profile = GameProfile {
+        uuid: 0,
+        username: Str::from_static("Notch"),
+        properties: vec![],
+    };
+    let framed = Framed::new(stream, UncompressedCodec::default());
+
+    Connection::new(framed, V::V1_21_5, State::Play, profile)
+}
+
+fn raw(packet_id: i32) -> RawPacket {
+    RawPacket {
+        packet_id,
+        data: Bytes::from_static(b"data"),
+    }
+}
+
+#[tokio::test]
+async fn send() {
+    let (server, client) = duplex(4096);
+    let (sender, _receiver, writer) = connection(server).split(4);
+    let mut client = connection(client);
+
+    let senders = (0..3).map(|id| {
+        let mut sender = sender.clone();
+        async move {
+            sender.send_raw(raw(id)).await.unwrap();
+            sender.send(PingRequest { payload: 7 }).await.unwrap();
+        }
+    });
+    let senders = futures::future::join_all(senders).map(move |_| drop(sender));
+
+    let ((), written) = futures::join!(senders, writer.run());
+    written.unwrap();
+
+    let mut received = vec![];
+    while let Some(packet) = client.receive().await.unwrap() {
+        received.push(packet.packet_id);
+    }
+
+    assert_eq!(received.len(), 6);
+    for id in 0..3 {
+        assert!(received.contains(&id));
+    }
+    let pings = received.iter().filter(|&&id| id == 0x01).count();
+    assert_eq!(pings, 4);
+}
+
+#[test]
+fn backpressure() {
+    let (server, _client) = duplex(4096);
+    let (mut sender, _receiver, writer) = connection(server).split(1);
+
+    // the second packet is queued, but the sender waits for room
+    assert!(sender.send_raw(raw(0)).now_or_never().is_some());
+    assert!(sender.send_raw(raw(1)).now_or_never().is_none());
+
+    // clones share the same room
+    let mut clone = sender.clone();
+    assert!(clone.send_raw(raw(2)).now_or_never().is_none());
+
+    drop(writer);
+    assert!(sender.is_closed());
+    let res = sender.send_raw(raw(3)).now_or_never();
+    assert!(matches!(res, Some(Err(_))));
+}
+
+#[tokio::test]
+async fn close() {
+    let (server, client) = duplex(4096);
+    let (mut sender, _receiver, writer) = connection(server).split(4);
+    let mut client = connection(client);
+
+    sender.send_raw(raw(0)).await.unwrap();
+    sender.send_raw(raw(1)).await.unwrap();
+
+    // every clone stops, but the queued packets are still written
+    let mut clone = sender.clone();
+    sender.close();
+    assert!(clone.is_closed());
+    assert!(clone.send_raw(raw(2)).await.is_err());
+
+    writer.run().await.unwrap();
+    assert_eq!(client.receive().await.unwrap().unwrap().packet_id, 0);
+    assert_eq!(client.receive().await.unwrap().unwrap().packet_id, 1);
+    assert!(client.receive().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn disconnect() {
+    let (server, client) = duplex(4096);
+    let (mut sender, mut receiver, writer) = connection(server).split(4);
+    let mut client = connection(client);
+
+    client.send_raw(&raw(5)).await.unwrap();
+    drop(client);
+
+    let receive = async {
+        assert_eq!(receiver.next().await.unwrap().unwrap().packet_id, 5);
+        assert!(receiver.receive().await.unwrap().is_none());
+    };
+
+    let ((), written) = futures::join!(receive, writer.run());
+    written.unwrap();
+    assert!(sender.send_raw(raw(0)).await.is_err());
+}
@@ -0,0 +1,140 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+
+use crate::{
+    encoding::{identifier::Identifier, packetid::PacketId},
+    login::sha256,
+    packet::{
+        configuration::{CookieRequest, CookieResponse, StoreCookie},
+        RawPacket,
+    },
+    DeError,
+};
+
+#[cfg(test)]
+mod test;
+
+/// maximum size of the payload of a cookie
+pub const MAX_SIZE: usize = 5120;
+
+/// size of the HMAC-SHA256 signature leading signed cookies
+const SIGNATURE: usize = 32;
+
+/// Cookies stored on the client by the servers it joined.
+///
+/// Clones share the same cookies, so the jar can be given to the
+/// [`ClientLogin`](crate::login::ClientLogin) of every server the
+/// player is transferred to. Configuration packets are handled
+/// with [`Self::handle`]; in the play state, where their ids depend
+/// on the version, with [`Self::store`] and [`Self::respond`]
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Arc<Mutex<HashMap<Identifier, Bytes>>>,
+}
+
+impl CookieJar {
+    /// creates an empty jar
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `payload` under `key`, replacing the previous cookie.
+    /// Returns false if the payload is bigger than [`MAX_SIZE`]
+    pub fn insert(&self, key: Identifier, payload: Bytes) -> bool {
+        if payload.len() > MAX_SIZE {
+            return false;
+        }
+
+        self.cookies.lock().unwrap().insert(key, payload);
+        true
+    }
+
+    /// payload of the cookie stored under `key`
+    pub fn get(&self, key: &Identifier) -> Option<Bytes> {
+        self.cookies.lock().unwrap().get(key).cloned()
+    }
+
+    /// removes the cookie stored under `key`, returning its payload
+    pub fn remove(&self, key: &Identifier) -> Option<Bytes> {
+        self.cookies.lock().unwrap().remove(key)
+    }
+
+    /// Stores the cookie sent by the server, see [`Self::insert`]
+    pub fn store(&self, cookie: StoreCookie) -> bool {
+        self.insert(cookie.key, cookie.payload)
+    }
+
+    /// answer to a cookie `request` of the server
+    pub fn respond(&self, request: CookieRequest) -> CookieResponse {
+        CookieResponse {
+            payload: self.get(&request.key),
+            key: request.key,
+        }
+    }
+
+    /// Handles the cookie packets received in the configuration state,
+    /// returning the response to send. Cookies bigger than
+    /// [`MAX_SIZE`] are rejected as invalid data
+    pub fn handle(&self, packet: &RawPacket) -> Result<Option<CookieResponse>, DeError> {
+        match packet.packet_id {
+            StoreCookie::ID => match self.store(packet.deserialize_unchecked()?) {
+                true => Ok(None),
+                false => Err(DeError::InvalidData),
+            },
+            CookieRequest::ID => Ok(Some(self.respond(packet.deserialize_unchecked()?))),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Signs cookies with HMAC-SHA256, so that a server can trust the state
+/// it stored on the client when the player comes back, or when it is
+/// transferred to another server sharing the secret.
+///
+/// The signature covers the key as well, so a cookie can't
+/// be replayed under a different key
+#[derive(Clone)]
+pub struct CookieSigner {
+    secret: Bytes,
+}
+
+fn signed(key: &Identifier, payload: &[u8]) -> Vec<u8> {
+    [key.as_bytes(), &[0], payload].concat()
+}
+
+impl CookieSigner {
+    /// signer using `secret`, which must be shared by the servers reading the cookies
+    pub fn new(secret: impl Into<Bytes>) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    /// Cookie storing `payload` under `key` with its signature. The signature
+    /// takes 32 bytes, leaving `MAX_SIZE - 32` bytes to the payload
+    pub fn sign(&self, key: Identifier, payload: &[u8]) -> StoreCookie {
+        let signature = sha256::hmac(&self.secret, &signed(&key, payload));
+
+        StoreCookie {
+            payload: [&signature[..], payload].concat().into(),
+            key,
+        }
+    }
+
+    /// Payload of the cookie stored under `key`,
+    /// `None` if it wasn't signed with this secret
+    pub fn verify(&self, key: &Identifier, cookie: &Bytes) -> Option<Bytes> {
+        if cookie.len() < SIGNATURE {
+            return None;
+        }
+
+        let payload = cookie.slice(SIGNATURE..);
+        let signature = &cookie[..SIGNATURE];
+
+        sha256::verify(&self.secret, &signed(key, &payload), signature).then_some(payload)
+    }
+}
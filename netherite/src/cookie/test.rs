@@ -0,0 +1,196 @@
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use tokio::io::duplex;
+use tokio_util::codec::Framed;
+
+use super::{CookieJar, CookieSigner, MAX_SIZE};
+use crate::{
+    assert_serialization,
+    codec::dual::SwitchCodec,
+    connection::TransferError,
+    encoding::{identifier::Identifier, str::Str, varint::VarInt, versioned::Versioned},
+    login::{ClientLogin, LoginError, ServerLogin},
+    packet::{
+        configuration::{CookieRequest, CookieResponse, StoreCookie, Transfer},
+        handshake::{Handshake, Intent},
+        login, RawPacket,
+    },
+    protocol::{Direction, ProtocolVersion as V, State},
+    registry::{Key, PacketRegistry},
+    DeError,
+};
+
+fn key() -> Identifier {
+    Identifier::from_static("lobby:session")
+}
+
+#[test]
+fn packets() {
+    let transfer = Transfer {
+        host: Str::from_static("mc"),
+        port: VarInt(25565),
+    };
+    assert_serialization!(transfer => b"\x02mc\xdd\xc7\x01");
+
+    let response = CookieResponse {
+        key: Identifier::from_static("a:b"),
+        payload: None,
+    };
+    assert_serialization!(response => b"\x03a:b\x00");
+}
+
+#[test]
+fn jar() {
+    let jar = CookieJar::new();
+    let shared = jar.clone();
+
+    let store = StoreCookie {
+        key: key(),
+        payload: Bytes::from_static(b"state"),
+    };
+    let response = jar.handle(&RawPacket::from(store)).unwrap();
+    assert_eq!(response, None);
+
+    let request = RawPacket::from(CookieRequest { key: key() });
+    let response = shared.handle(&request).unwrap().unwrap();
+    assert_eq!(response.payload.as_deref(), Some(&b"state"[..]));
+
+    let missing = jar.respond(CookieRequest {
+        key: Identifier::from_static("lobby:other"),
+    });
+    assert_eq!(missing.payload, None);
+
+    let big = StoreCookie {
+        key: key(),
+        payload: vec![0; MAX_SIZE + 1].into(),
+    };
+    let res = jar.handle(&RawPacket::from(big));
+    assert!(matches!(res, Err(DeError::InvalidData)));
+    assert_eq!(jar.remove(&key()).as_deref(), Some(&b"state"[..]));
+}
+
+#[test]
+fn signer() {
+    let signer = CookieSigner::new(&b"shared secret"[..]);
+    let cookie = signer.sign(key(), b"state");
+    assert_eq!(cookie.payload.len(), 32 + 5);
+
+    assert_eq!(
+        signer.verify(&key(), &cookie.payload).as_deref(),
+        Some(&b"state"[..])
+    );
+
+    // wrong key, secret or payload
+    let other = Identifier::from_static("lobby:other");
+    assert_eq!(signer.verify(&other, &cookie.payload), None);
+    let forger = CookieSigner::new(&b"guessed secret"[..]);
+    assert_eq!(forger.verify(&key(), &cookie.payload), None);
+
+    let mut forged = cookie.payload.to_vec();
+    *forged.last_mut().unwrap() ^= 1;
+    assert_eq!(signer.verify(&key(), &forged.into()), None);
+    assert_eq!(signer.verify(&key(), &Bytes::from_static(b"short")), None);
+}
+
+#[tokio::test]
+async fn transfer() {
+    let signer = CookieSigner::new(&b"shared secret"[..]);
+    let jar = CookieJar::new();
+
+    // stored by the lobby before transferring the player
+    jar.store(signer.sign(key(), b"state"));
+
+    for (version, cookie) in [(V::V1_21_5, Some(&b"state"[..])), (V::V1_20_3, None)] {
+        let (client, server) = duplex(4096);
+
+        let client = ClientLogin::new("Notch", version)
+            .transferred(true)
+            .cookies(jar.clone())
+            .login(client);
+        let server = ServerLogin::new()
+            .cookie(key())
+            .cookie(Identifier::from_static("lobby:missing"))
+            .accept(server);
+
+        let (client, server) = futures::join!(client, server);
+        let (mut client, mut server) = (client.unwrap(), server.unwrap());
+
+        assert!(server.is_transferred());
+        let payload = server
+            .get_cookie(&key())
+            .and_then(|payload| signer.verify(&key(), payload));
+        assert_eq!(payload.as_deref(), cookie);
+        assert_eq!(
+            server.get_cookie(&Identifier::from_static("lobby:missing")),
+            None
+        );
+
+        let result = server.transfer("mc", 25565).await;
+        if cookie.is_none() {
+            assert!(matches!(result, Err(TransferError::Version(_))));
+            continue;
+        }
+        result.unwrap();
+
+        // the play id comes from the registry
+        server.set_state(State::Play);
+        let result = server.transfer("mc", 25565).await;
+        assert!(matches!(result, Err(TransferError::State(State::Play))));
+
+        let mut registry = PacketRegistry::new();
+        let key = Key::new(version, State::Play, Direction::Clientbound);
+        registry.insert(key, 0x7a, "transfer");
+        server.transfer_with(&registry, "mc", 25566).await.unwrap();
+
+        let transfer = client.receive().await.unwrap().unwrap();
+        assert_eq!(
+            transfer.deserialize::<Transfer>().unwrap().unwrap(),
+            Transfer {
+                host: Str::from_static("mc"),
+                port: VarInt(25565),
+            }
+        );
+        let transfer = client.receive().await.unwrap().unwrap();
+        assert_eq!(transfer.packet_id, 0x7a);
+    }
+}
+
+#[tokio::test]
+async fn oversized_response() {
+    let (client, server) = duplex(16384);
+
+    let client = async {
+        let mut framed = Framed::new(client, SwitchCodec::default());
+        let handshake = Handshake {
+            protocol_version: V::V1_21_5,
+            server_address: Str::from_static("localhost"),
+            server_port: 25565,
+            intent: Intent::Transfer,
+        };
+        let start = login::LoginStart {
+            username: Str::from_static("Notch"),
+            key: None,
+            uuid: Some(0),
+        };
+        framed.send(handshake).await.unwrap();
+        framed
+            .send(Versioned::new(start, V::V1_21_5))
+            .await
+            .unwrap();
+
+        let request = framed.next().await.unwrap().unwrap();
+        assert!(request.is::<login::CookieRequest>());
+
+        // the client jar refuses such cookies, but the server can't trust it
+        let response = login::CookieResponse {
+            key: key(),
+            payload: Some(vec![0; MAX_SIZE + 1].into()),
+        };
+        framed.send(response).await.unwrap();
+        framed
+    };
+    let server = ServerLogin::new().cookie(key()).accept(server);
+
+    let (_client, result) = futures::join!(client, server);
+    assert!(matches!(result, Err(LoginError::CookieSize(size)) if size == MAX_SIZE + 1));
+}
@@ -0,0 +1,104 @@
+use aes::Aes128;
+use cfb8::cipher::{inout::InOutBuf, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use sha1::{Digest, Sha1};
+use thiserror::Error;
+
+mod key;
+#[cfg(test)]
+mod test;
+
+pub use key::{KeyPair, PublicKey};
+
+/// Error of the cryptographic operations of the login
+#[derive(Debug, Error)]
+pub enum CryptoError {
+    /// Random bytes couldn't be read from the operating system
+    #[error("random source: {0}")]
+    Random(#[from] getrandom::Error),
+
+    /// DER encoded public key is malformed or not an RSA key
+    #[error("invalid public key")]
+    InvalidKey,
+
+    /// Data is too long to be encrypted with the key
+    #[error("message too long for the key")]
+    MessageTooLong,
+
+    /// Key pair couldn't be generated
+    #[error("rsa: {0}")]
+    Rsa(#[from] rsa::Error),
+}
+
+/// Fills `buffer` with random bytes from the operating system
+pub fn random_bytes(buffer: &mut [u8]) -> Result<(), CryptoError> {
+    getrandom::getrandom(buffer)?;
+    Ok(())
+}
+
+/// Server id hash sent to the session server by both sides: the SHA-1
+/// digest of the server id, the shared secret and the DER public key,
+/// printed as a signed hexadecimal number like Java's `BigInteger`
+pub fn server_hash(server_id: &str, shared_secret: &[u8], public_key: &[u8]) -> String {
+    let mut digest: [u8; 20] = Sha1::new()
+        .chain_update(server_id)
+        .chain_update(shared_secret)
+        .chain_update(public_key)
+        .finalize()
+        .into();
+
+    let negative = digest[0] & 0x80 != 0;
+    if negative {
+        // two's complement
+        let mut carry = true;
+        for byte in digest.iter_mut().rev() {
+            (*byte, carry) = (!*byte).overflowing_add(carry as u8);
+        }
+    }
+
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    let hex = match hex.trim_start_matches('0') {
+        "" => "0",
+        hex => hex,
+    };
+
+    match negative {
+        true => format!("-{hex}"),
+        false => hex.to_string(),
+    }
+}
+
+/// AES-128 in 8 bit cipher feedback mode, encrypting the connection
+/// after the login. Minecraft uses the shared secret as key and IV
+#[derive(Clone)]
+pub struct Cfb8 {
+    encryptor: cfb8::Encryptor<Aes128>,
+    decryptor: cfb8::Decryptor<Aes128>,
+}
+
+impl Cfb8 {
+    /// cipher with a custom `iv`
+    pub fn new(key: &[u8; 16], iv: &[u8; 16]) -> Self {
+        Self {
+            encryptor: cfb8::Encryptor::new(key.into(), iv.into()),
+            decryptor: cfb8::Decryptor::new(key.into(), iv.into()),
+        }
+    }
+
+    /// cipher of a connection using `shared_secret`
+    pub fn from_secret(shared_secret: &[u8; 16]) -> Self {
+        Self::new(shared_secret, shared_secret)
+    }
+
+    /// encrypts `data` in place, continuing the stream
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        // blocks are a single byte long, there's never a tail
+        let (blocks, _) = InOutBuf::from(data).into_chunks();
+        self.encryptor.encrypt_blocks_inout_mut(blocks);
+    }
+
+    /// decrypts `data` in place, continuing the stream
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        let (blocks, _) = InOutBuf::from(data).into_chunks();
+        self.decryptor.decrypt_blocks_inout_mut(blocks);
+    }
+}
@@ -0,0 +1,118 @@
+use bytes::Bytes;
+use rand_core::OsRng;
+use rsa::{
+    pkcs8::{DecodePublicKey, EncodePublicKey},
+    traits::PublicKeyParts,
+    Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
+};
+
+use super::{random_bytes, CryptoError};
+
+/// RSA public key, as sent in the Encryption Request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicKey {
+    key: RsaPublicKey,
+    der: Bytes,
+}
+
+impl PublicKey {
+    fn new(key: RsaPublicKey) -> Result<Self, CryptoError> {
+        let der = key
+            .to_public_key_der()
+            .map_err(|_| CryptoError::InvalidKey)?;
+
+        Ok(Self {
+            key,
+            der: Bytes::copy_from_slice(der.as_bytes()),
+        })
+    }
+
+    /// parses a DER encoded SubjectPublicKeyInfo
+    pub fn from_der(der: &[u8]) -> Result<Self, CryptoError> {
+        let key = RsaPublicKey::from_public_key_der(der).map_err(|_| CryptoError::InvalidKey)?;
+        Self::new(key)
+    }
+
+    /// DER encoded SubjectPublicKeyInfo of the key
+    pub fn to_der(&self) -> Bytes {
+        self.der.clone()
+    }
+
+    /// size of the modulus in bytes
+    pub fn size(&self) -> usize {
+        self.key.size()
+    }
+
+    /// Encrypts `data` with PKCS#1 v1.5 padding, as the client
+    /// does with the shared secret and the verify token
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match self.key.encrypt(&mut OsRng, Pkcs1v15Encrypt, data) {
+            Err(rsa::Error::MessageTooLong) => Err(CryptoError::MessageTooLong),
+            result => Ok(result?),
+        }
+    }
+}
+
+/// RSA key pair of a server
+pub struct KeyPair {
+    private: RsaPrivateKey,
+    public: PublicKey,
+}
+
+impl std::fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyPair {
+    /// Generates a 1024 bit key pair, the size used by vanilla servers
+    pub fn generate() -> Result<Self, CryptoError> {
+        Self::generate_bits(1024)
+    }
+
+    /// Generates a key pair with a modulus of `bits`
+    pub fn generate_bits(bits: usize) -> Result<Self, CryptoError> {
+        let private = RsaPrivateKey::new(&mut OsRng, bits)?;
+        let public = PublicKey::new(private.to_public_key())?;
+
+        Ok(Self { private, public })
+    }
+
+    /// public half of the pair
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public
+    }
+
+    /// DER encoded public key, sent in the Encryption Request
+    pub fn public_key_der(&self) -> &Bytes {
+        &self.public.der
+    }
+
+    /// Decrypts `data` into a message of `N` bytes, encrypted by the client
+    /// with PKCS#1 v1.5 padding like the shared secret and the verify token.
+    ///
+    /// Data with the wrong size or padding, or holding a message of another
+    /// size, decrypts to random bytes instead of failing: clients can't tell
+    /// it apart from a wrong verify token, which would let them use the
+    /// server as a padding oracle (Bleichenbacher's attack).
+    /// The decryption is blinded against timing attacks
+    pub fn decrypt<const N: usize>(&self, data: &[u8]) -> Result<[u8; N], CryptoError> {
+        let mut message = [0; N];
+        random_bytes(&mut message)?;
+
+        let decrypted = self
+            .private
+            .decrypt_blinded(&mut OsRng, Pkcs1v15Encrypt, data);
+
+        if let Ok(decrypted) = decrypted {
+            if decrypted.len() == N {
+                message.copy_from_slice(&decrypted);
+            }
+        }
+
+        Ok(message)
+    }
+}
@@ -0,0 +1,87 @@
+use super::{server_hash, Cfb8, CryptoError, KeyPair, PublicKey};
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn hash() {
+    assert_eq!(
+        server_hash("Notch", &[], &[]),
+        "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48"
+    );
+    assert_eq!(
+        server_hash("jeb_", &[], &[]),
+        "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1"
+    );
+    assert_eq!(
+        server_hash("simon", &[], &[]),
+        "88e16a1019277b15d58faf0541e11910eb756f6"
+    );
+}
+
+#[test]
+fn cfb8() {
+    // NIST SP 800-38A, F.3.7
+    let key = unhex("2b7e151628aed2a6abf7158809cf4f3c")
+        .try_into()
+        .unwrap();
+    let iv = unhex("000102030405060708090a0b0c0d0e0f")
+        .try_into()
+        .unwrap();
+    let plaintext = unhex("6bc1bee22e409f96e93d7e117393172aae2d");
+    let ciphertext = "3b79424c9c0dd436bace9e0ed4586a4f32b9";
+
+    let mut data = plaintext.clone();
+    let mut encryptor = Cfb8::new(&key, &iv);
+    encryptor.encrypt(&mut data[..5]);
+    encryptor.encrypt(&mut data[5..]);
+    assert_eq!(hex(&data), ciphertext);
+
+    Cfb8::new(&key, &iv).decrypt(&mut data);
+    assert_eq!(data, plaintext);
+}
+
+#[test]
+fn rsa() {
+    let pair = KeyPair::generate().unwrap();
+    assert_eq!(pair.public_key().size(), 128);
+
+    let der = pair.public_key_der();
+    let public = PublicKey::from_der(der).unwrap();
+    assert_eq!(&public, pair.public_key());
+    assert_eq!(&public.to_der(), der);
+
+    let secret = b"0123456789abcdef";
+    let encrypted = public.encrypt(secret).unwrap();
+    assert_eq!(encrypted.len(), 128);
+    assert_eq!(&pair.decrypt::<16>(&encrypted).unwrap(), secret);
+
+    assert!(matches!(
+        public.encrypt(&[0; 118]),
+        Err(CryptoError::MessageTooLong)
+    ));
+
+    // invalid data or sizes can't be told apart from another message
+    assert_ne!(&pair.decrypt::<16>(&encrypted[..127]).unwrap(), secret);
+    assert_ne!(pair.decrypt::<4>(&encrypted).unwrap(), secret[..4]);
+}
+
+#[test]
+fn invalid_key() {
+    assert!(matches!(
+        PublicKey::from_der(&[0x30, 0x03, 0x02, 0x01, 0x01]),
+        Err(CryptoError::InvalidKey)
+    ));
+    assert!(matches!(
+        PublicKey::from_der(&[0x30, 0x81]),
+        Err(CryptoError::InvalidKey)
+    ));
+}
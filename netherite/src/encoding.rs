@@ -7,8 +7,17 @@ use self::{
 
 /// traits and implementations for deserialization
 pub mod de;
+/// namespaced identifiers, like `minecraft:brand`
+pub mod identifier;
+/// JSON values encoded into a Minecraft string
+#[cfg(feature = "serde_json")]
+pub mod json;
 /// defines a trait that binds a packet_id to a deserializable type
 pub mod packetid;
+/// packed block position type
+pub mod position;
+/// bytes taking up the rest of a packet
+pub mod remaining;
 /// traits and implementations for serialization
 pub mod ser;
 /// cheaply deserializable and clonable string type
@@ -16,10 +25,12 @@ pub mod str;
 /// wrapper type around an [`i32`] for serializing and deserializing
 /// a Varint
 pub mod varint;
+/// traits for types whose encoding depends on the protocol version
+pub mod versioned;
 
 mod macros;
 #[cfg(test)]
-pub mod test;
+mod test;
 
 /// Serializes `data` into Bytes, preallocating a buffer
 /// with size `data.size` (for more information read [`Serialize::size`])
@@ -80,6 +80,21 @@ impl<T: Deserialize> Deserialize for Option<T> {
     }
 }
 
+impl<T: Deserialize> Deserialize for Vec<T> {
+    fn deserialize(mut buffer: impl Buf) -> Result<Self, DeError> {
+        let (_, len) = read_varint(&mut buffer)?;
+        let len: usize = len.try_into()?;
+
+        // don't trust the length for preallocation
+        let mut items = Vec::with_capacity(len.min(buffer.remaining()));
+        for _ in 0..len {
+            items.push(T::deserialize(&mut buffer)?);
+        }
+
+        Ok(items)
+    }
+}
+
 impl Deserialize for () {
     fn deserialize(_: impl Buf) -> Result<Self, DeError> {
         Ok(())
@@ -106,3 +121,6 @@ impl_int!(u32, get_u32);
 impl_int!(i32, get_i32);
 impl_int!(u64, get_u64);
 impl_int!(i64, get_i64);
+impl_int!(u128, get_u128);
+impl_int!(f32, get_f32);
+impl_int!(f64, get_f64);
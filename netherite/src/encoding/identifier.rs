@@ -0,0 +1,143 @@
+use std::{
+    fmt::{Debug, Display},
+    ops::Deref,
+    str::FromStr,
+};
+
+use bytes::{Buf, BufMut};
+
+use super::str::Str;
+use crate::{DeError, Deserialize, Serialize};
+
+/// namespace of identifiers written without one
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+/// A namespaced identifier (resource location), like `minecraft:brand`.
+///
+/// Identifiers are always stored with their namespace, so
+/// `brand` and `minecraft:brand` are the same identifier
+#[derive(Clone, Hash, PartialEq, Eq)]
+pub struct Identifier {
+    inner: Str,
+}
+
+fn valid_namespace(namespace: &str) -> bool {
+    !namespace.is_empty()
+        && namespace
+            .bytes()
+            .all(|c| matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.'))
+}
+
+fn valid_path(path: &str) -> bool {
+    !path.is_empty()
+        && path
+            .bytes()
+            .all(|c| matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'/'))
+}
+
+impl Identifier {
+    /// Creates an identifier from its parts, `None` if they contain invalid characters
+    pub fn new(namespace: &str, path: &str) -> Option<Self> {
+        (valid_namespace(namespace) && valid_path(path)).then(|| Self {
+            inner: format!("{namespace}:{path}").into(),
+        })
+    }
+
+    /// identifier in the `minecraft` namespace
+    pub fn minecraft(path: &str) -> Option<Self> {
+        Self::new(DEFAULT_NAMESPACE, path)
+    }
+
+    /// Creates an identifier from a &'static str, without allocating
+    ///
+    /// Panic:
+    /// panics if `identifier` is invalid or doesn't have a namespace
+    pub fn from_static(identifier: &'static str) -> Self {
+        match identifier.split_once(':') {
+            Some((namespace, path)) if valid_namespace(namespace) && valid_path(path) => Self {
+                inner: Str::from_static(identifier),
+            },
+            _ => panic!("invalid identifier {identifier:?}"),
+        }
+    }
+
+    /// Parses `namespace:path` or `path`, taking the `minecraft` namespace
+    pub fn parse(identifier: &str) -> Option<Self> {
+        match identifier.split_once(':') {
+            Some((namespace, path)) => Self::new(namespace, path),
+            None => Self::minecraft(identifier),
+        }
+    }
+
+    /// namespace, before the colon
+    pub fn namespace(&self) -> &str {
+        self.inner.split_once(':').unwrap_or_default().0
+    }
+
+    /// path, after the colon
+    pub fn path(&self) -> &str {
+        self.inner.split_once(':').unwrap_or_default().1
+    }
+}
+
+impl FromStr for Identifier {
+    type Err = DeError;
+
+    fn from_str(identifier: &str) -> Result<Self, Self::Err> {
+        Self::parse(identifier).ok_or(DeError::InvalidData)
+    }
+}
+
+impl Deref for Identifier {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl Debug for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&*self.inner, f)
+    }
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.inner, f)
+    }
+}
+
+impl PartialEq<str> for Identifier {
+    fn eq(&self, other: &str) -> bool {
+        *self.inner == *other
+    }
+}
+
+impl Serialize for Identifier {
+    fn serialize(&self, buf: impl BufMut) {
+        self.inner.serialize(buf)
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+}
+
+impl Deserialize for Identifier {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        let identifier = Str::deserialize(buffer)?;
+
+        match identifier.contains(':') {
+            // keeps sharing the packet buffer
+            true => identifier
+                .split_once(':')
+                .filter(|(namespace, path)| valid_namespace(namespace) && valid_path(path))
+                .map(|_| Self {
+                    inner: identifier.clone(),
+                })
+                .ok_or(DeError::InvalidData),
+            false => identifier.parse(),
+        }
+    }
+}
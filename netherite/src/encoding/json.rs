@@ -0,0 +1,87 @@
+use std::{fmt::Debug, ops::Deref};
+
+use bytes::{Buf, BufMut};
+use serde::{de::DeserializeOwned, Serialize as SerdeSerialize};
+
+use crate::{encoding::str::Str, DeError, Deserialize, Serialize};
+
+/// Default maximum length of a [`Json`] string, which is
+/// the max length of a chat component in the vanilla protocol
+pub const MAX_JSON_LENGTH: usize = 262144;
+
+/// A `T` value serialized as JSON into a Minecraft string,
+/// like the Status Response or (pre 1.20.3) chat components.
+///
+/// The JSON encoding is computed once when constructing the value
+/// and cached alongside it, so that [`Serialize::size`] is exact
+/// and serializing doesn't need to re-encode `T`.
+///
+/// `MAX` is the maximum length (in UTF-16 code units, like the
+/// vanilla implementation) accepted when deserializing
+#[derive(Clone)]
+pub struct Json<T, const MAX: usize = MAX_JSON_LENGTH> {
+    value: T,
+    encoded: Str,
+}
+
+impl<T: SerdeSerialize, const MAX: usize> Json<T, MAX> {
+    /// Encodes `value` as JSON, caching the result
+    pub fn new(value: T) -> Result<Self, serde_json::Error> {
+        let encoded = serde_json::to_string(&value)?.into();
+        Ok(Self { value, encoded })
+    }
+}
+
+impl<T, const MAX: usize> Json<T, MAX> {
+    /// JSON encoding of the inner value
+    pub fn as_str(&self) -> &str {
+        &self.encoded
+    }
+
+    /// Returns the inner value, discarding the encoded string
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, const MAX: usize> Deref for Json<T, MAX> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Debug, const MAX: usize> Debug for Json<T, MAX> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Json").field(&self.value).finish()
+    }
+}
+
+impl<T: PartialEq, const MAX: usize> PartialEq for Json<T, MAX> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T, const MAX: usize> Serialize for Json<T, MAX> {
+    fn serialize(&self, buf: impl BufMut) {
+        self.encoded.serialize(buf)
+    }
+
+    fn size(&self) -> usize {
+        self.encoded.size()
+    }
+}
+
+impl<T: DeserializeOwned, const MAX: usize> Deserialize for Json<T, MAX> {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        let encoded = Str::deserialize(buffer)?;
+        if encoded.encode_utf16().count() > MAX {
+            return Err(DeError::InvalidData);
+        }
+
+        let value = serde_json::from_str(&encoded).map_err(|_| DeError::InvalidData)?;
+        Ok(Self { value, encoded })
+    }
+}
@@ -0,0 +1,55 @@
+use bytes::{Buf, BufMut};
+
+use super::{de::DeError, de::Deserialize, ser::Serialize};
+
+/// Block position packed into a 64 bit integer, with
+/// the layout used since 1.14: x (26 bits), z (26 bits), y (12 bits)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// x coordinate, in `-33554432..33554432`
+    pub x: i32,
+    /// y coordinate, in `-2048..2048`
+    pub y: i32,
+    /// z coordinate, in `-33554432..33554432`
+    pub z: i32,
+}
+
+impl Position {
+    /// creates a position from its coordinates
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Packs the coordinates into a single integer,
+    /// truncating the ones out of range
+    pub const fn pack(&self) -> i64 {
+        ((self.x as i64 & 0x3FFFFFF) << 38)
+            | ((self.z as i64 & 0x3FFFFFF) << 12)
+            | (self.y as i64 & 0xFFF)
+    }
+
+    /// unpacks a position from an integer
+    pub const fn unpack(packed: i64) -> Self {
+        Self {
+            x: (packed >> 38) as i32,
+            y: (packed << 52 >> 52) as i32,
+            z: (packed << 26 >> 38) as i32,
+        }
+    }
+}
+
+impl Serialize for Position {
+    fn serialize(&self, buf: impl BufMut) {
+        self.pack().serialize(buf)
+    }
+
+    fn size(&self) -> usize {
+        8
+    }
+}
+
+impl Deserialize for Position {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        i64::deserialize(buffer).map(Self::unpack)
+    }
+}
@@ -0,0 +1,40 @@
+use std::ops::Deref;
+
+use bytes::{Buf, BufMut, Bytes};
+
+use super::{de::DeError, de::Deserialize, ser::Serialize};
+
+/// Bytes without a length prefix, which take up everything
+/// left in the packet. Must be the last field of a packet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Remaining(pub Bytes);
+
+impl Deref for Remaining {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Bytes> for Remaining {
+    fn from(value: Bytes) -> Self {
+        Self(value)
+    }
+}
+
+impl Serialize for Remaining {
+    fn serialize(&self, mut buf: impl BufMut) {
+        buf.put_slice(&self.0)
+    }
+
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl Deserialize for Remaining {
+    fn deserialize(mut buffer: impl Buf) -> Result<Self, DeError> {
+        Ok(Self(buffer.copy_to_bytes(buffer.remaining())))
+    }
+}
@@ -1,4 +1,4 @@
-use bytes::BufMut;
+use bytes::{BufMut, Bytes};
 
 use crate::varint;
 
@@ -56,6 +56,16 @@ impl Serialize for &[u8] {
     }
 }
 
+impl Serialize for Bytes {
+    fn serialize(&self, buf: impl BufMut) {
+        self.as_ref().serialize(buf)
+    }
+
+    fn size(&self) -> usize {
+        self.as_ref().size()
+    }
+}
+
 impl Serialize for &str {
     fn serialize(&self, buf: impl BufMut) {
         self.as_bytes().serialize(buf)
@@ -89,6 +99,20 @@ impl<T: Serialize> Serialize for Option<T> {
     }
 }
 
+impl<T: Serialize> Serialize for Vec<T> {
+    fn serialize(&self, mut buf: impl BufMut) {
+        let len = self.len().try_into().unwrap_or(i32::MAX);
+        varint::write(&mut buf, len);
+
+        self.iter().for_each(|item| item.serialize(&mut buf));
+    }
+
+    fn size(&self) -> usize {
+        let len = self.len().try_into().unwrap_or(i32::MAX);
+        varint::size(len) + self.iter().map(Serialize::size).sum::<usize>()
+    }
+}
+
 impl Serialize for () {
     fn serialize(&self, _: impl BufMut) {}
 
@@ -119,4 +143,7 @@ impl_int!(u32, put_u32);
 impl_int!(i32, put_i32);
 impl_int!(u64, put_u64);
 impl_int!(i64, put_i64);
+impl_int!(u128, put_u128);
+impl_int!(f32, put_f32);
+impl_int!(f64, put_f64);
 impl_int!(bool, put_u8);
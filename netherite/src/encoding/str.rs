@@ -31,6 +31,13 @@ impl Str {
     }
 }
 
+impl From<String> for Str {
+    fn from(value: String) -> Self {
+        let inner = Bytes::from(value);
+        Self { inner }
+    }
+}
+
 impl Deserialize for Str {
     fn deserialize(buffer: impl bytes::Buf) -> Result<Self, crate::DeError> {
         let inner = Bytes::deserialize(buffer)?;
@@ -75,6 +82,6 @@ impl Display for Str {
 
 impl PartialEq<str> for Str {
     fn eq(&self, other: &str) -> bool {
-        self == other
+        self.deref() == other
     }
 }
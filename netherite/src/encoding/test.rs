@@ -1,5 +1,14 @@
 mod ser {
-    use crate::{assert_serialization, encoding::varint::VarInt};
+    use bytes::Bytes;
+
+    use crate::{
+        assert_serialization,
+        encoding::{
+            position::Position,
+            remaining::Remaining,
+            varint::{VarInt, VarLong},
+        },
+    };
 
     #[test]
     fn serialize_str() {
@@ -12,10 +21,34 @@ mod ser {
         assert_serialization!(Some(10u32) => &[0x01, 0x00, 0x00, 0x00, 0x0A]);
     }
 
+    #[test]
+    fn serialize_vec() {
+        assert_serialization!(vec![1u8, 2] => &[0x02, 0x01, 0x02]);
+        assert_serialization!(vec![0.5f32] => &[0x01, 0x3f, 0x00, 0x00, 0x00]);
+    }
+
     #[test]
     fn serialize_varint() {
         assert_serialization!(VarInt(-1) => &[0xff, 0xff, 0xff, 0xff, 0x0f]);
     }
+
+    #[test]
+    fn serialize_bytes() {
+        assert_serialization!(Bytes::from_static(b"ab") => b"\x02ab");
+        assert_serialization!(Remaining(Bytes::from_static(b"ab")) => b"ab");
+    }
+
+    #[test]
+    fn serialize_varlong() {
+        assert_serialization!(VarLong(2147483648) => &[0x80, 0x80, 0x80, 0x80, 0x08]);
+        assert_serialization!(VarLong(-1) => &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+    }
+
+    #[test]
+    fn serialize_position() {
+        let position = Position::new(18357644, 831, -20882616);
+        assert_serialization!(&position => &[0x46, 0x07, 0x63, 0x2c, 0x15, 0xb4, 0x83, 0x3f]);
+    }
 }
 
 mod de {
@@ -23,7 +56,13 @@ mod de {
 
     use crate::{
         assert_deserialization,
-        encoding::{str::Str, varint::VarInt},
+        encoding::{
+            position::Position,
+            remaining::Remaining,
+            str::Str,
+            varint::{VarInt, VarLong},
+        },
+        Deserialize,
     };
 
     #[test]
@@ -36,19 +75,135 @@ mod de {
         assert_deserialization!(b"\x04ciao_extradata" => "ciao", Str);
     }
 
+    #[test]
+    fn str_eq() {
+        let str = Str::from_static("ciao");
+        assert!(str == *"ciao");
+        assert!(str != *"hello");
+    }
+
     #[test]
     fn deserialize_borrowed_bytes() {
         assert_deserialization!(b"\x04aaaa" => b"aaaa", Bytes);
     }
 
+    #[test]
+    fn deserialize_vec() {
+        assert_deserialization!(&[0x02, 0x00, 0x01, 0x00, 0x02] => vec![1u16, 2]);
+    }
+
     #[test]
     fn deserialize_varint() {
         assert_deserialization!(b"\xff\x01" => VarInt(255));
     }
 
+    #[test]
+    fn deserialize_remaining() {
+        assert_deserialization!(b"abc" => Remaining(Bytes::from_static(b"abc")));
+    }
+
+    #[test]
+    fn deserialize_varlong() {
+        assert_deserialization!(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f] => VarLong(i64::MAX));
+        assert!(VarLong::deserialize(&[0xff; 11][..]).is_err());
+    }
+
+    #[test]
+    fn deserialize_position() {
+        let packed = [0x46, 0x07, 0x63, 0x2c, 0x15, 0xb4, 0x83, 0x3f];
+        assert_deserialization!(&packed => Position::new(18357644, 831, -20882616));
+        assert_eq!(Position::unpack(-1), Position::new(-1, -1, -1));
+    }
+
     #[test]
     fn deserialize_option() {
         assert_deserialization!(&[0x01, 0x01] => Some(1u8));
         assert_deserialization!(&[0x00] => Option::<()>::None);
     }
 }
+
+#[cfg(feature = "serde_json")]
+mod json {
+    use bytes::BytesMut;
+
+    use crate::{
+        assert_serialization,
+        encoding::{deserialize_bytes, json::Json},
+        DeError, Serialize,
+    };
+
+    #[test]
+    fn serialize_cached() {
+        let json: Json<Vec<u32>> = Json::new(vec![1, 2]).unwrap();
+
+        assert_eq!(json.as_str(), "[1,2]");
+        assert_serialization!(json => b"\x05[1,2]");
+    }
+
+    #[test]
+    fn deserialize_roundtrip() {
+        let json: Json<Vec<u32>> = Json::new(vec![1, 2, 3]).unwrap();
+
+        let mut buf = BytesMut::new();
+        json.serialize(&mut buf);
+
+        let res: Json<Vec<u32>> = deserialize_bytes(buf).unwrap();
+        assert_eq!(*res, [1, 2, 3]);
+    }
+
+    #[test]
+    fn deserialize_max_length() {
+        let res = deserialize_bytes::<Json<Vec<u32>, 4>>(&b"\x05[1,2]"[..]);
+        assert!(matches!(res, Err(DeError::InvalidData)));
+
+        let res = deserialize_bytes::<Json<Vec<u32>, 5>>(&b"\x05[1,2]"[..]);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let res = deserialize_bytes::<Json<Vec<u32>>>(&b"\x03[1,"[..]);
+        assert!(matches!(res, Err(DeError::InvalidData)));
+    }
+}
+
+mod identifier {
+    use crate::{
+        assert_serialization,
+        encoding::{deserialize_bytes, identifier::Identifier},
+        DeError,
+    };
+
+    #[test]
+    fn parse() {
+        let brand = Identifier::parse("brand").unwrap();
+        assert_eq!(brand, Identifier::from_static("minecraft:brand"));
+        assert_eq!((brand.namespace(), brand.path()), ("minecraft", "brand"));
+
+        let channel = Identifier::parse("netherite:some/path.v2").unwrap();
+        assert_eq!(channel.namespace(), "netherite");
+        assert_eq!(channel.path(), "some/path.v2");
+
+        for invalid in [
+            "",
+            "MC|Brand",
+            ":brand",
+            "minecraft:",
+            "name/space:path",
+            "a:b:c",
+        ] {
+            assert_eq!(Identifier::parse(invalid), None, "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        assert_serialization!(Identifier::parse("brand").unwrap() => b"\x0fminecraft:brand");
+
+        let res: Identifier = deserialize_bytes(&b"\x05brand"[..]).unwrap();
+        assert_eq!(&*res, "minecraft:brand");
+
+        let res = deserialize_bytes::<Identifier>(&b"\x08MC|Brand"[..]);
+        assert!(matches!(res, Err(DeError::InvalidData)));
+    }
+}
@@ -26,3 +26,43 @@ impl Serialize for VarInt {
         varint::size(self.0)
     }
 }
+
+/// newtype wrapper that defines
+/// a varint-encoded i64 (VarLong)
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub struct VarLong(pub i64);
+
+impl Deserialize for VarLong {
+    fn deserialize(mut buffer: impl Buf) -> Result<Self, super::de::DeError> {
+        let mut value: u64 = 0;
+
+        for i in 0..10 {
+            let byte = u8::deserialize(&mut buffer)?;
+            value |= ((byte & 0x7f) as u64) << (i * 7);
+
+            if byte & 0x80 == 0 {
+                return Ok(Self(value as i64));
+            }
+        }
+
+        Err(super::de::DeError::InvalidData)
+    }
+}
+
+impl Serialize for VarLong {
+    fn serialize(&self, mut buf: impl bytes::BufMut) {
+        let mut value = self.0 as u64;
+
+        while value >= 0x80 {
+            buf.put_u8(value as u8 | 0x80);
+            value >>= 7;
+        }
+
+        buf.put_u8(value as u8);
+    }
+
+    fn size(&self) -> usize {
+        let bits = u64::BITS - (self.0 as u64).leading_zeros();
+        (bits as usize).div_ceil(7).max(1)
+    }
+}
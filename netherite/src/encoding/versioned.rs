@@ -0,0 +1,102 @@
+use bytes::{Buf, BufMut};
+
+use crate::{encoding::packetid::PacketId, protocol::ProtocolVersion, DeError, Serialize};
+
+/// Like [`Serialize`], for types whose wire
+/// format depends on the protocol version
+pub trait VersionedSerialize {
+    /// serializes &self into an impl BufMut, using
+    /// the format of protocol `version`
+    fn serialize_versioned(&self, buf: impl BufMut, version: ProtocolVersion);
+
+    /// **EXACT** size in bytes of the serialization with protocol `version`.
+    /// Read [`Serialize::size`] for more information
+    fn size_versioned(&self, version: ProtocolVersion) -> usize;
+}
+
+/// Like [`crate::Deserialize`], for types whose
+/// wire format depends on the protocol version
+pub trait VersionedDeserialize: Sized {
+    /// Instantiate a `Self` from a buffer `buffer`
+    /// encoded with protocol `version`
+    fn deserialize_versioned(buffer: impl Buf, version: ProtocolVersion) -> Result<Self, DeError>;
+}
+
+impl<T: VersionedSerialize> VersionedSerialize for &T {
+    fn serialize_versioned(&self, buf: impl BufMut, version: ProtocolVersion) {
+        (*self).serialize_versioned(buf, version)
+    }
+
+    fn size_versioned(&self, version: ProtocolVersion) -> usize {
+        (*self).size_versioned(version)
+    }
+}
+
+impl<T: VersionedSerialize> VersionedSerialize for Vec<T> {
+    fn serialize_versioned(&self, mut buf: impl BufMut, version: ProtocolVersion) {
+        let len = self.len().try_into().unwrap_or(i32::MAX);
+        crate::varint::write(&mut buf, len);
+
+        self.iter()
+            .for_each(|item| item.serialize_versioned(&mut buf, version));
+    }
+
+    fn size_versioned(&self, version: ProtocolVersion) -> usize {
+        let len = self.len().try_into().unwrap_or(i32::MAX);
+        crate::varint::size(len)
+            + self
+                .iter()
+                .map(|item| item.size_versioned(version))
+                .sum::<usize>()
+    }
+}
+
+impl<T: VersionedDeserialize> VersionedDeserialize for Vec<T> {
+    fn deserialize_versioned(
+        mut buffer: impl Buf,
+        version: ProtocolVersion,
+    ) -> Result<Self, DeError> {
+        let (_, len) = crate::varint::read_varint(&mut buffer)?;
+        let len: usize = len.try_into()?;
+
+        // don't trust the length for preallocation
+        let mut items = Vec::with_capacity(len.min(buffer.remaining()));
+        for _ in 0..len {
+            items.push(T::deserialize_versioned(&mut buffer, version)?);
+        }
+
+        Ok(items)
+    }
+}
+
+/// Binds a [`VersionedSerialize`] value to a protocol version,
+/// making it usable wherever a [`Serialize`] is expected
+/// (for example with the codecs)
+#[derive(Debug, Clone, Copy)]
+pub struct Versioned<T> {
+    /// wrapped value
+    pub value: T,
+    /// protocol version used for serialization
+    pub version: ProtocolVersion,
+}
+
+impl<T> Versioned<T> {
+    /// binds `value` to `version`
+    pub fn new(value: T, version: ProtocolVersion) -> Self {
+        Self { value, version }
+    }
+}
+
+impl<T: VersionedSerialize> Serialize for Versioned<T> {
+    fn serialize(&self, buf: impl BufMut) {
+        self.value.serialize_versioned(buf, self.version)
+    }
+
+    fn size(&self) -> usize {
+        self.value.size_versioned(self.version)
+    }
+}
+
+impl<T: PacketId> PacketId for Versioned<T> {
+    const ID: i32 = T::ID;
+}
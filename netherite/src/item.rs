@@ -0,0 +1,335 @@
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::{
+    encoding::{
+        varint::VarInt,
+        versioned::{VersionedDeserialize, VersionedSerialize},
+    },
+    nbt::Nbt,
+    protocol::ProtocolVersion,
+    record::Recorder,
+    varint, DeError, Deserialize, Serialize,
+};
+
+/// data component registries and layouts
+pub mod component;
+
+#[cfg(test)]
+mod test;
+
+use component::{ComponentRegistry, SkipError};
+
+/// Maximum nesting of items within the components of other items,
+/// like containers and bundles, and of components within components
+pub const MAX_DEPTH: usize = 64;
+
+/// A data component of an item, kept in its raw wire format.
+///
+/// Items relayed by a proxy are forwarded unchanged,
+/// even if their components can't be decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawComponent {
+    /// component type id
+    pub id: i32,
+    /// component payload, not including the type id
+    pub data: Bytes,
+}
+
+/// Data components added to or removed from
+/// the prototype of an item (1.20.5+)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataComponents {
+    /// components added or overridden
+    pub added: Vec<RawComponent>,
+    /// type ids of the components removed from the item prototype
+    pub removed: Vec<i32>,
+}
+
+impl DataComponents {
+    /// raw payload of the added component `id`
+    pub fn get(&self, id: i32) -> Option<&Bytes> {
+        self.added
+            .iter()
+            .find(|component| component.id == id)
+            .map(|component| &component.data)
+    }
+
+    /// Adds (or replaces) component `id` with `value`
+    pub fn insert(&mut self, id: i32, value: impl Serialize) {
+        let data = crate::encoding::serialize_bytes(value);
+        self.removed.retain(|&removed| removed != id);
+
+        match self.added.iter_mut().find(|component| component.id == id) {
+            Some(component) => component.data = data,
+            None => self.added.push(RawComponent { id, data }),
+        }
+    }
+
+    fn read(
+        buffer: &mut dyn Buf,
+        registry: &ComponentRegistry,
+        depth: usize,
+    ) -> Result<Self, SkipError> {
+        let added = VarInt::deserialize(&mut *buffer)?.0;
+        let removed = VarInt::deserialize(&mut *buffer)?.0;
+        let added: usize = added.try_into().map_err(DeError::from)?;
+        let removed: usize = removed.try_into().map_err(DeError::from)?;
+
+        let mut components = Self::default();
+
+        for _ in 0..added {
+            let VarInt(id) = VarInt::deserialize(&mut *buffer)?;
+            let layout = registry.layout(id).ok_or(SkipError::Unknown)?;
+
+            let mut recorder = Recorder::new(&mut *buffer);
+            layout.skip(&mut recorder, registry, depth)?;
+
+            let data = recorder.finish();
+            components.added.push(RawComponent { id, data });
+        }
+
+        for _ in 0..removed {
+            let VarInt(id) = VarInt::deserialize(&mut *buffer)?;
+            components.removed.push(id);
+        }
+
+        Ok(components)
+    }
+
+    fn write(&self, mut buf: impl BufMut) {
+        VarInt(self.added.len() as i32).serialize(&mut buf);
+        VarInt(self.removed.len() as i32).serialize(&mut buf);
+
+        for component in &self.added {
+            VarInt(component.id).serialize(&mut buf);
+            buf.put_slice(&component.data);
+        }
+
+        for &id in &self.removed {
+            VarInt(id).serialize(&mut buf);
+        }
+    }
+
+    fn size(&self) -> usize {
+        let added = self
+            .added
+            .iter()
+            .map(|component| varint::size(component.id) + component.data.len());
+        let removed = self.removed.iter().copied().map(varint::size);
+
+        varint::size(self.added.len() as i32)
+            + varint::size(self.removed.len() as i32)
+            + added.sum::<usize>()
+            + removed.sum::<usize>()
+    }
+}
+
+/// Extra data attached to an item, whose
+/// format depends on the protocol version
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemData {
+    /// NBT tag of the item, before 1.20.5
+    Nbt(Nbt),
+    /// data components, since 1.20.5
+    Components(DataComponents),
+    /// Data components including one without a known layout (1.20.5+).
+    ///
+    /// Since its end can't be found, the components are kept with
+    /// the rest of the buffer, starting from the number of added
+    /// components, and serialized back unchanged
+    Raw(Bytes),
+}
+
+/// A non empty stack of items
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    /// item id in the item registry
+    pub item: i32,
+    /// number of items in the stack
+    pub count: i32,
+    /// item data, which must match the protocol version used to serialize
+    pub data: ItemData,
+}
+
+impl ItemStack {
+    /// Creates a stack of `count` `item`s without any
+    /// extra data, in the format used by `version`
+    pub fn new(item: i32, count: i32, version: ProtocolVersion) -> Self {
+        let data = match uses_components(version) {
+            true => ItemData::Components(Default::default()),
+            false => ItemData::Nbt(Default::default()),
+        };
+
+        Self { item, count, data }
+    }
+
+    /// reads a slot in the data components format, nested `depth` items deep
+    pub(crate) fn read_components(
+        buffer: &mut dyn Buf,
+        registry: &ComponentRegistry,
+        depth: usize,
+    ) -> Result<Option<Self>, SkipError> {
+        let VarInt(count) = VarInt::deserialize(&mut *buffer)?;
+        if count <= 0 {
+            return Ok(None);
+        }
+
+        let VarInt(item) = VarInt::deserialize(&mut *buffer)?;
+        let data = ItemData::Components(DataComponents::read(buffer, registry, depth)?);
+
+        Ok(Some(Self { item, count, data }))
+    }
+
+    /// Reads a slot in the data components format, keeping the rest
+    /// of the buffer as [`ItemData::Raw`] at the first unknown component
+    fn read_slot(
+        buffer: &mut dyn Buf,
+        registry: &ComponentRegistry,
+    ) -> Result<Option<Self>, DeError> {
+        let VarInt(count) = VarInt::deserialize(&mut *buffer)?;
+        if count <= 0 {
+            return Ok(None);
+        }
+
+        let VarInt(item) = VarInt::deserialize(&mut *buffer)?;
+
+        let mut recorder = Recorder::new(&mut *buffer);
+        let data = match DataComponents::read(&mut recorder, registry, 0) {
+            Ok(components) => ItemData::Components(components),
+            Err(SkipError::Unknown) => {
+                recorder.advance(recorder.remaining());
+                ItemData::Raw(recorder.finish())
+            }
+            Err(SkipError::De(err)) => return Err(err),
+        };
+
+        Ok(Some(Self { item, count, data }))
+    }
+
+    /// Reads a slot in the data components format using a custom `registry`,
+    /// for protocol versions whose registry isn't bundled with netherite.
+    /// Returns `None` for an empty slot
+    pub fn deserialize_with(
+        mut buffer: impl Buf,
+        registry: &ComponentRegistry,
+    ) -> Result<Option<Self>, DeError> {
+        Self::read_slot(&mut buffer, registry)
+    }
+}
+
+fn uses_components(version: ProtocolVersion) -> bool {
+    version >= ProtocolVersion::V1_20_5
+}
+
+/// An inventory slot, which may be empty.
+///
+/// Its encoding depends on the protocol version:
+/// - from 1.13.2 to 1.20.4 it's a present flag, followed by
+///   the item id, the count and the item NBT
+/// - since 1.20.5 it's the item count (0 for empty slots), followed
+///   by the item id and the data components
+///
+/// When serializing an [`ItemStack`] whose [`ItemData`] doesn't match
+/// the format of the protocol version, its data is dropped.
+///
+/// Data components are decoded through [`ComponentRegistry::vanilla`]; when
+/// it doesn't cover the protocol version use [`ItemStack::deserialize_with`].
+/// Components without a layout don't fail the slot, see [`ItemData::Raw`],
+/// but items nested deeper than [`MAX_DEPTH`] do
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Slot(pub Option<ItemStack>);
+
+impl Slot {
+    /// An empty slot
+    pub const EMPTY: Self = Self(None);
+
+    /// true if the slot contains no items
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl From<ItemStack> for Slot {
+    fn from(value: ItemStack) -> Self {
+        Self(Some(value))
+    }
+}
+
+impl VersionedSerialize for Slot {
+    fn serialize_versioned(&self, mut buf: impl BufMut, version: ProtocolVersion) {
+        match (&self.0, uses_components(version)) {
+            (None, true) => VarInt(0).serialize(buf),
+            (None, false) => false.serialize(buf),
+            (Some(stack), true) => {
+                VarInt(stack.count).serialize(&mut buf);
+                VarInt(stack.item).serialize(&mut buf);
+
+                match &stack.data {
+                    ItemData::Components(components) => components.write(buf),
+                    ItemData::Raw(data) => buf.put_slice(data),
+                    ItemData::Nbt(_) => DataComponents::default().write(buf),
+                }
+            }
+            (Some(stack), false) => {
+                true.serialize(&mut buf);
+                VarInt(stack.item).serialize(&mut buf);
+                (stack.count as i8).serialize(&mut buf);
+
+                match &stack.data {
+                    ItemData::Nbt(nbt) => nbt.serialize_versioned(buf, version),
+                    ItemData::Components(_) | ItemData::Raw(_) => {
+                        Nbt(None).serialize_versioned(buf, version)
+                    }
+                }
+            }
+        }
+    }
+
+    fn size_versioned(&self, version: ProtocolVersion) -> usize {
+        match (&self.0, uses_components(version)) {
+            (None, _) => 1,
+            (Some(stack), true) => {
+                let data = match &stack.data {
+                    ItemData::Components(components) => components.size(),
+                    ItemData::Raw(data) => data.len(),
+                    ItemData::Nbt(_) => DataComponents::default().size(),
+                };
+
+                varint::size(stack.count) + varint::size(stack.item) + data
+            }
+            (Some(stack), false) => {
+                let data = match &stack.data {
+                    ItemData::Nbt(nbt) => nbt.size_versioned(version),
+                    ItemData::Components(_) | ItemData::Raw(_) => Nbt(None).size_versioned(version),
+                };
+
+                1 + varint::size(stack.item) + 1 + data
+            }
+        }
+    }
+}
+
+impl VersionedDeserialize for Slot {
+    fn deserialize_versioned(
+        mut buffer: impl Buf,
+        version: ProtocolVersion,
+    ) -> Result<Self, DeError> {
+        if uses_components(version) {
+            let empty = ComponentRegistry::default();
+            let registry = ComponentRegistry::vanilla(version).unwrap_or(&empty);
+
+            return ItemStack::read_slot(&mut buffer, registry).map(Slot);
+        }
+
+        if !bool::deserialize(&mut buffer)? {
+            return Ok(Slot::EMPTY);
+        }
+
+        let VarInt(item) = VarInt::deserialize(&mut buffer)?;
+        let count = i8::deserialize(&mut buffer)?.into();
+        let nbt = Nbt::deserialize_versioned(buffer, version)?;
+
+        let data = ItemData::Nbt(nbt);
+        Ok(Slot(Some(ItemStack { item, count, data })))
+    }
+}
@@ -0,0 +1,804 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use bytes::Buf;
+
+use crate::{encoding::str::Str, nbt, protocol::ProtocolVersion, DeError, Deserialize};
+
+use super::{ItemStack, MAX_DEPTH};
+
+/// Error skipping a payload
+#[derive(Debug)]
+pub(crate) enum SkipError {
+    /// a component without a layout was found, so the end of the payload is unknown
+    Unknown,
+    /// the payload doesn't match its layout
+    De(DeError),
+}
+
+impl From<DeError> for SkipError {
+    fn from(value: DeError) -> Self {
+        Self::De(value)
+    }
+}
+
+/// Wire shape of a data component payload.
+///
+/// Payloads are kept as raw bytes, a layout only
+/// describes how to find where one ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Layout {
+    /// no payload
+    Empty,
+    Bool,
+    Byte,
+    Int,
+    Long,
+    Float,
+    Double,
+    VarInt,
+    /// varint length prefixed string, also used for identifiers
+    String,
+    /// nameless network NBT, also used for text components
+    Nbt,
+    /// nested [`super::Slot`] in the data component format
+    Slot,
+    /// boolean prefixed optional value
+    Option(Box<Layout>),
+    /// varint length prefixed array
+    Array(Box<Layout>),
+    /// fields following each other
+    Seq(Vec<Layout>),
+    /// boolean, followed by the first layout if true or the second one if false
+    Either(Box<Layout>, Box<Layout>),
+    /// varint discriminant, followed by the layout at that index
+    Tagged(Vec<Layout>),
+    /// Registry entry: a varint id plus one, or 0 followed by the entry inline
+    Holder(Box<Layout>),
+    /// Registry entries: a varint count plus one followed by
+    /// the ids, or 0 followed by the identifier of a tag
+    HolderSet,
+    /// status effect instance, with its hidden effects
+    Effect,
+    /// data component: a varint type id followed by its payload
+    Component,
+}
+
+impl Layout {
+    /// shorthand for [`Layout::Option`]
+    pub fn option(layout: Layout) -> Self {
+        Self::Option(Box::new(layout))
+    }
+
+    /// shorthand for [`Layout::Array`]
+    pub fn array(layout: Layout) -> Self {
+        Self::Array(Box::new(layout))
+    }
+
+    /// shorthand for [`Layout::Either`]
+    pub fn either(left: Layout, right: Layout) -> Self {
+        Self::Either(Box::new(left), Box::new(right))
+    }
+
+    /// shorthand for [`Layout::Holder`]
+    pub fn holder(layout: Layout) -> Self {
+        Self::Holder(Box::new(layout))
+    }
+
+    /// Consumes a value with this layout from `buffer`, within `depth`
+    /// nested items and components, up to [`MAX_DEPTH`]
+    pub(crate) fn skip(
+        &self,
+        buffer: &mut dyn Buf,
+        registry: &ComponentRegistry,
+        depth: usize,
+    ) -> Result<(), SkipError> {
+        fn advance(buffer: &mut dyn Buf, cnt: usize) -> Result<(), DeError> {
+            (buffer.remaining() >= cnt)
+                .then(|| buffer.advance(cnt))
+                .ok_or(DeError::Eof)
+        }
+
+        fn varint(buffer: &mut dyn Buf) -> Result<i32, DeError> {
+            Ok(crate::varint::read_varint(buffer)?.1)
+        }
+
+        if matches!(self, Layout::Slot | Layout::Component) && depth >= MAX_DEPTH {
+            return Err(DeError::InvalidData.into());
+        }
+
+        match self {
+            Layout::Empty => {}
+            Layout::Bool => drop(bool::deserialize(buffer)?),
+            Layout::Byte => advance(buffer, 1)?,
+            Layout::Int | Layout::Float => advance(buffer, 4)?,
+            Layout::Long | Layout::Double => advance(buffer, 8)?,
+            Layout::VarInt => drop(varint(buffer)?),
+            Layout::String => drop(Str::deserialize(buffer)?),
+            Layout::Nbt => drop(nbt::Nbt::deserialize(buffer)?),
+            Layout::Slot => drop(ItemStack::read_components(buffer, registry, depth + 1)?),
+            Layout::Option(layout) => {
+                if bool::deserialize(&mut *buffer)? {
+                    layout.skip(buffer, registry, depth)?;
+                }
+            }
+            Layout::Array(layout) => {
+                let len: usize = varint(buffer)?.try_into().map_err(DeError::from)?;
+
+                for _ in 0..len {
+                    layout.skip(buffer, registry, depth)?;
+                }
+            }
+            Layout::Seq(layouts) => {
+                for layout in layouts {
+                    layout.skip(buffer, registry, depth)?;
+                }
+            }
+            Layout::Either(left, right) => match bool::deserialize(&mut *buffer)? {
+                true => left.skip(buffer, registry, depth)?,
+                false => right.skip(buffer, registry, depth)?,
+            },
+            Layout::Tagged(layouts) => {
+                let tag: usize = varint(buffer)?.try_into().map_err(DeError::from)?;
+                let layout = layouts.get(tag).ok_or(DeError::InvalidData)?;
+
+                layout.skip(buffer, registry, depth)?;
+            }
+            Layout::Holder(layout) => {
+                if varint(buffer)? == 0 {
+                    layout.skip(buffer, registry, depth)?;
+                }
+            }
+            Layout::HolderSet => match varint(buffer)? {
+                0 => drop(Str::deserialize(buffer)?),
+                len => {
+                    for _ in 1..len {
+                        varint(buffer)?;
+                    }
+                }
+            },
+            Layout::Effect => {
+                varint(buffer)?;
+
+                // amplifier, duration, ambient, particles, icon and hidden effect
+                loop {
+                    varint(buffer)?;
+                    varint(buffer)?;
+                    advance(buffer, 3)?;
+
+                    if !bool::deserialize(&mut *buffer)? {
+                        break;
+                    }
+                }
+            }
+            Layout::Component => {
+                let id = varint(buffer)?;
+                let layout = registry.layout(id).ok_or(SkipError::Unknown)?;
+
+                layout.skip(buffer, registry, depth + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps data component type ids to their name
+/// and [`Layout`] for a protocol version.
+///
+/// Components without a layout can still be removed from or
+/// forwarded within an item, but not decoded from the wire
+#[derive(Debug, Clone, Default)]
+pub struct ComponentRegistry {
+    names: HashMap<i32, String>,
+    layouts: HashMap<i32, Layout>,
+}
+
+impl ComponentRegistry {
+    /// creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// registers component `id` as `name`, with an optional `layout`
+    pub fn register(&mut self, id: i32, name: impl Into<String>, layout: Option<Layout>) {
+        self.names.insert(id, name.into());
+
+        match layout {
+            Some(layout) => self.layouts.insert(id, layout),
+            None => self.layouts.remove(&id),
+        };
+    }
+
+    /// layout of component `id`, if known
+    pub fn layout(&self, id: i32) -> Option<&Layout> {
+        self.layouts.get(&id)
+    }
+
+    /// name of component `id`, if known
+    pub fn name(&self, id: i32) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+
+    /// id of the component called `name`, if known
+    pub fn id(&self, name: &str) -> Option<i32> {
+        self.names
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(&id, _)| id)
+    }
+
+    /// Registry of the vanilla components for `version`.
+    ///
+    /// Registries go from 1.20.5 up to 1.21.5: use
+    /// [`Self::register`] to build the table of other versions
+    pub fn vanilla(version: ProtocolVersion) -> Option<&'static ComponentRegistry> {
+        use ProtocolVersion as V;
+
+        static REGISTRIES: [OnceLock<ComponentRegistry>; 5] = [
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+        ];
+
+        let (index, version, names) = match version {
+            v if v < V::V1_20_5 => return None,
+            v if v < V::V1_21 => (0, V::V1_20_5, tables::V1_20_5),
+            v if v < V::V1_21_2 => (1, V::V1_21, tables::V1_21),
+            v if v < V::V1_21_4 => (2, V::V1_21_2, tables::V1_21_2),
+            v if v < V::V1_21_5 => (3, V::V1_21_4, tables::V1_21_2),
+            v if v == V::V1_21_5 => (4, V::V1_21_5, tables::V1_21_5),
+            _ => return None,
+        };
+
+        Some(REGISTRIES[index].get_or_init(|| vanilla(version, names)))
+    }
+}
+
+fn vanilla(version: ProtocolVersion, names: &[&str]) -> ComponentRegistry {
+    let mut registry = ComponentRegistry::new();
+    for (id, name) in names.iter().enumerate() {
+        registry.register(id as i32, *name, layout(name, version));
+    }
+
+    registry
+}
+
+/// layout of the vanilla component `name` in `version`
+fn layout(name: &str, version: ProtocolVersion) -> Option<Layout> {
+    use Layout::*;
+    use ProtocolVersion as V;
+
+    let array = Layout::array;
+    let option = Layout::option;
+    let holder = Layout::holder;
+    let since = |since| version >= since;
+
+    // the "show in tooltip" flags were replaced by `tooltip_display` in 1.21.5
+    let tooltip = |layout| match since(V::V1_21_5) {
+        true => layout,
+        false => Seq(vec![layout, Bool]),
+    };
+
+    let sound = || holder(Seq(vec![String, option(Float)]));
+    let filterable = |layout: Layout| Seq(vec![layout.clone(), option(layout)]);
+    let enchantments = || tooltip(array(Seq(vec![VarInt, VarInt])));
+    let consume_effect = || {
+        Tagged(vec![
+            Seq(vec![array(Effect), Float]),
+            HolderSet,
+            Empty,
+            Float,
+            sound(),
+        ])
+    };
+    let firework_explosion = || Seq(vec![VarInt, array(Int), array(Int), Bool, Bool]);
+
+    let block_predicate = || {
+        let property = Seq(vec![
+            String,
+            Layout::either(String, Seq(vec![option(String), option(String)])),
+        ]);
+        let mut predicate = vec![option(HolderSet), option(array(property)), option(Nbt)];
+        if since(V::V1_21_5) {
+            // exact and partial data component predicates
+            predicate.extend([array(Component), array(Seq(vec![VarInt, Nbt]))]);
+        }
+
+        tooltip(array(Seq(predicate)))
+    };
+
+    let trim_material = || {
+        let mut material = vec![String];
+        if !since(V::V1_21_5) {
+            material.push(VarInt);
+        }
+        if !since(V::V1_21_4) {
+            material.push(Float);
+        }
+        match since(V::V1_21_2) {
+            true => material.push(array(Seq(vec![String, String]))),
+            false => material.push(array(Seq(vec![VarInt, String]))),
+        }
+        material.push(Nbt);
+
+        holder(Seq(material))
+    };
+    let trim_pattern = || match since(V::V1_21_5) {
+        true => holder(Seq(vec![String, Nbt, Bool])),
+        false => holder(Seq(vec![String, VarInt, Nbt, Bool])),
+    };
+    let jukebox_song = || holder(Seq(vec![sound(), Nbt, Float, VarInt]));
+    let instrument = || match since(V::V1_21_2) {
+        true => holder(Seq(vec![sound(), Float, Float, Nbt])),
+        false => holder(Seq(vec![sound(), VarInt, Float])),
+    };
+
+    let layout = match name {
+        "custom_data"
+        | "custom_name"
+        | "item_name"
+        | "intangible_projectile"
+        | "map_decorations"
+        | "debug_stick_state"
+        | "entity_data"
+        | "bucket_entity_data"
+        | "block_entity_data"
+        | "recipes"
+        | "lock"
+        | "container_loot" => Nbt,
+
+        "max_stack_size"
+        | "max_damage"
+        | "damage"
+        | "rarity"
+        | "repair_cost"
+        | "enchantable"
+        | "map_id"
+        | "map_post_processing"
+        | "ominous_bottle_amplifier"
+        | "base_color" => VarInt,
+
+        "item_model"
+        | "damage_resistant"
+        | "tooltip_style"
+        | "note_block_sound"
+        | "provides_banner_patterns" => String,
+
+        "hide_additional_tooltip"
+        | "hide_tooltip"
+        | "creative_slot_lock"
+        | "fire_resistant"
+        | "glider" => Empty,
+
+        "charged_projectiles" | "bundle_contents" | "container" => array(Slot),
+
+        "unbreakable" => match since(V::V1_21_5) {
+            true => Empty,
+            false => Bool,
+        },
+        "lore" => array(Nbt),
+        "enchantments" | "stored_enchantments" => enchantments(),
+        "can_place_on" | "can_break" => block_predicate(),
+        "attribute_modifiers" => {
+            let modifier = match since(V::V1_21) {
+                true => Seq(vec![VarInt, String, Double, VarInt, VarInt]),
+                false => Seq(vec![
+                    VarInt,
+                    Seq(vec![Long, Long]),
+                    String,
+                    Double,
+                    VarInt,
+                    VarInt,
+                ]),
+            };
+
+            tooltip(array(modifier))
+        }
+        "custom_model_data" => match since(V::V1_21_4) {
+            true => Seq(vec![array(Float), array(Bool), array(String), array(Int)]),
+            false => VarInt,
+        },
+        "tooltip_display" => Seq(vec![Bool, array(VarInt)]),
+        "enchantment_glint_override" => Bool,
+        "food" => {
+            let effects = array(Seq(vec![Effect, Float]));
+
+            match version {
+                v if v >= V::V1_21_2 => Seq(vec![VarInt, Float, Bool]),
+                v if v >= V::V1_21 => Seq(vec![VarInt, Float, Bool, Float, option(Slot), effects]),
+                _ => Seq(vec![VarInt, Float, Bool, Float, effects]),
+            }
+        }
+        "consumable" => Seq(vec![Float, VarInt, sound(), Bool, array(consume_effect())]),
+        "use_remainder" => Slot,
+        "use_cooldown" => Seq(vec![Float, option(String)]),
+        "tool" => {
+            let rule = Seq(vec![HolderSet, option(Float), option(Bool)]);
+            let mut tool = vec![array(rule), Float, VarInt];
+            if since(V::V1_21_5) {
+                tool.push(Bool);
+            }
+
+            Seq(tool)
+        }
+        "weapon" => Seq(vec![VarInt, Float]),
+        "equippable" => {
+            let mut equippable = vec![
+                VarInt,
+                sound(),
+                option(String),
+                option(String),
+                option(HolderSet),
+                Bool,
+                Bool,
+                Bool,
+            ];
+            if since(V::V1_21_5) {
+                equippable.push(Bool);
+            }
+
+            Seq(equippable)
+        }
+        "repairable" => HolderSet,
+        "death_protection" => array(consume_effect()),
+        "blocks_attacks" => Seq(vec![
+            Float,
+            Float,
+            array(Seq(vec![Float, option(HolderSet), Float, Float])),
+            Seq(vec![Float, Float, Float]),
+            option(String),
+            option(sound()),
+            option(sound()),
+        ]),
+        "dyed_color" => tooltip(Int),
+        "map_color" => Int,
+        "potion_contents" => {
+            let mut potion = vec![option(VarInt), option(Int), array(Effect)];
+            if since(V::V1_21_2) {
+                potion.push(option(String));
+            }
+
+            Seq(potion)
+        }
+        "potion_duration_scale" => Float,
+        "suspicious_stew_effects" => array(Seq(vec![VarInt, VarInt])),
+        "writable_book_content" => array(filterable(String)),
+        "written_book_content" => Seq(vec![
+            filterable(String),
+            String,
+            VarInt,
+            array(filterable(Nbt)),
+            Bool,
+        ]),
+        "trim" => tooltip(Seq(vec![trim_material(), trim_pattern()])),
+        "instrument" => match since(V::V1_21_5) {
+            true => Layout::either(instrument(), String),
+            false => instrument(),
+        },
+        "provides_trim_material" => Layout::either(trim_material(), String),
+        "jukebox_playable" => tooltip(Layout::either(jukebox_song(), String)),
+        "lodestone_tracker" => Seq(vec![option(Seq(vec![String, Long])), Bool]),
+        "firework_explosion" => firework_explosion(),
+        "fireworks" => Seq(vec![VarInt, array(firework_explosion())]),
+        "profile" => Seq(vec![
+            option(String),
+            option(Seq(vec![Long, Long])),
+            array(Seq(vec![String, String, option(String)])),
+        ]),
+        "banner_patterns" => array(Seq(vec![holder(Seq(vec![String, String])), VarInt])),
+        "pot_decorations" => array(VarInt),
+        "block_state" => array(Seq(vec![String, String])),
+        "bees" => array(Seq(vec![Nbt, VarInt, VarInt])),
+        "break_sound" => sound(),
+        "painting/variant" => holder(Seq(vec![VarInt, VarInt, String, option(Nbt), option(Nbt)])),
+        "chicken/variant" => Layout::either(VarInt, String),
+        // data driven variants are registry ids, the other ones enum ordinals
+        name if name.ends_with("/variant")
+            || name.ends_with("/sound_variant")
+            || name.ends_with("/collar")
+            || name.ends_with("/color")
+            || name.starts_with("tropical_fish/")
+            || name == "salmon/size" =>
+        {
+            VarInt
+        }
+        _ => return None,
+    };
+
+    Some(layout)
+}
+
+/// Component names of each version, in id order
+mod tables {
+    pub const V1_20_5: &[&str] = &[
+        "custom_data",
+        "max_stack_size",
+        "max_damage",
+        "damage",
+        "unbreakable",
+        "custom_name",
+        "item_name",
+        "lore",
+        "rarity",
+        "enchantments",
+        "can_place_on",
+        "can_break",
+        "attribute_modifiers",
+        "custom_model_data",
+        "hide_additional_tooltip",
+        "hide_tooltip",
+        "repair_cost",
+        "creative_slot_lock",
+        "enchantment_glint_override",
+        "intangible_projectile",
+        "food",
+        "fire_resistant",
+        "tool",
+        "stored_enchantments",
+        "dyed_color",
+        "map_color",
+        "map_id",
+        "map_decorations",
+        "map_post_processing",
+        "charged_projectiles",
+        "bundle_contents",
+        "potion_contents",
+        "suspicious_stew_effects",
+        "writable_book_content",
+        "written_book_content",
+        "trim",
+        "debug_stick_state",
+        "entity_data",
+        "bucket_entity_data",
+        "block_entity_data",
+        "instrument",
+        "ominous_bottle_amplifier",
+        "recipes",
+        "lodestone_tracker",
+        "firework_explosion",
+        "fireworks",
+        "profile",
+        "note_block_sound",
+        "banner_patterns",
+        "base_color",
+        "pot_decorations",
+        "container",
+        "block_state",
+        "bees",
+        "lock",
+        "container_loot",
+    ];
+
+    pub const V1_21: &[&str] = &[
+        "custom_data",
+        "max_stack_size",
+        "max_damage",
+        "damage",
+        "unbreakable",
+        "custom_name",
+        "item_name",
+        "lore",
+        "rarity",
+        "enchantments",
+        "can_place_on",
+        "can_break",
+        "attribute_modifiers",
+        "custom_model_data",
+        "hide_additional_tooltip",
+        "hide_tooltip",
+        "repair_cost",
+        "creative_slot_lock",
+        "enchantment_glint_override",
+        "intangible_projectile",
+        "food",
+        "fire_resistant",
+        "tool",
+        "stored_enchantments",
+        "dyed_color",
+        "map_color",
+        "map_id",
+        "map_decorations",
+        "map_post_processing",
+        "charged_projectiles",
+        "bundle_contents",
+        "potion_contents",
+        "suspicious_stew_effects",
+        "writable_book_content",
+        "written_book_content",
+        "trim",
+        "debug_stick_state",
+        "entity_data",
+        "bucket_entity_data",
+        "block_entity_data",
+        "instrument",
+        "ominous_bottle_amplifier",
+        "jukebox_playable",
+        "recipes",
+        "lodestone_tracker",
+        "firework_explosion",
+        "fireworks",
+        "profile",
+        "note_block_sound",
+        "banner_patterns",
+        "base_color",
+        "pot_decorations",
+        "container",
+        "block_state",
+        "bees",
+        "lock",
+        "container_loot",
+    ];
+
+    /// also used by 1.21.4
+    pub const V1_21_2: &[&str] = &[
+        "custom_data",
+        "max_stack_size",
+        "max_damage",
+        "damage",
+        "unbreakable",
+        "custom_name",
+        "item_name",
+        "item_model",
+        "lore",
+        "rarity",
+        "enchantments",
+        "can_place_on",
+        "can_break",
+        "attribute_modifiers",
+        "custom_model_data",
+        "hide_additional_tooltip",
+        "hide_tooltip",
+        "repair_cost",
+        "creative_slot_lock",
+        "enchantment_glint_override",
+        "intangible_projectile",
+        "food",
+        "consumable",
+        "use_remainder",
+        "use_cooldown",
+        "damage_resistant",
+        "tool",
+        "enchantable",
+        "equippable",
+        "repairable",
+        "glider",
+        "tooltip_style",
+        "death_protection",
+        "stored_enchantments",
+        "dyed_color",
+        "map_color",
+        "map_id",
+        "map_decorations",
+        "map_post_processing",
+        "charged_projectiles",
+        "bundle_contents",
+        "potion_contents",
+        "suspicious_stew_effects",
+        "writable_book_content",
+        "written_book_content",
+        "trim",
+        "debug_stick_state",
+        "entity_data",
+        "bucket_entity_data",
+        "block_entity_data",
+        "instrument",
+        "ominous_bottle_amplifier",
+        "jukebox_playable",
+        "recipes",
+        "lodestone_tracker",
+        "firework_explosion",
+        "fireworks",
+        "profile",
+        "note_block_sound",
+        "banner_patterns",
+        "base_color",
+        "pot_decorations",
+        "container",
+        "block_state",
+        "bees",
+        "lock",
+        "container_loot",
+    ];
+
+    pub const V1_21_5: &[&str] = &[
+        "custom_data",
+        "max_stack_size",
+        "max_damage",
+        "damage",
+        "unbreakable",
+        "custom_name",
+        "item_name",
+        "item_model",
+        "lore",
+        "rarity",
+        "enchantments",
+        "can_place_on",
+        "can_break",
+        "attribute_modifiers",
+        "custom_model_data",
+        "tooltip_display",
+        "repair_cost",
+        "creative_slot_lock",
+        "enchantment_glint_override",
+        "intangible_projectile",
+        "food",
+        "consumable",
+        "use_remainder",
+        "use_cooldown",
+        "damage_resistant",
+        "tool",
+        "weapon",
+        "enchantable",
+        "equippable",
+        "repairable",
+        "glider",
+        "tooltip_style",
+        "death_protection",
+        "blocks_attacks",
+        "stored_enchantments",
+        "dyed_color",
+        "map_color",
+        "map_id",
+        "map_decorations",
+        "map_post_processing",
+        "charged_projectiles",
+        "bundle_contents",
+        "potion_contents",
+        "potion_duration_scale",
+        "suspicious_stew_effects",
+        "writable_book_content",
+        "written_book_content",
+        "trim",
+        "debug_stick_state",
+        "entity_data",
+        "bucket_entity_data",
+        "block_entity_data",
+        "instrument",
+        "provides_trim_material",
+        "ominous_bottle_amplifier",
+        "jukebox_playable",
+        "provides_banner_patterns",
+        "recipes",
+        "lodestone_tracker",
+        "firework_explosion",
+        "fireworks",
+        "profile",
+        "note_block_sound",
+        "banner_patterns",
+        "base_color",
+        "pot_decorations",
+        "container",
+        "block_state",
+        "bees",
+        "lock",
+        "container_loot",
+        "break_sound",
+        "villager/variant",
+        "wolf/variant",
+        "wolf/sound_variant",
+        "wolf/collar",
+        "fox/variant",
+        "salmon/size",
+        "parrot/variant",
+        "tropical_fish/pattern",
+        "tropical_fish/base_color",
+        "tropical_fish/pattern_color",
+        "mooshroom/variant",
+        "rabbit/variant",
+        "pig/variant",
+        "cow/variant",
+        "chicken/variant",
+        "frog/variant",
+        "horse/variant",
+        "painting/variant",
+        "llama/variant",
+        "axolotl/variant",
+        "cat/variant",
+        "cat/collar",
+        "sheep/color",
+        "shulker/color",
+    ];
+}
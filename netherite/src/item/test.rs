@@ -0,0 +1,238 @@
+use bytes::{Bytes, BytesMut};
+
+use super::{
+    component::{ComponentRegistry, Layout},
+    DataComponents, ItemData, ItemStack, RawComponent, Slot, MAX_DEPTH,
+};
+use crate::{
+    assert_serialization,
+    encoding::{
+        varint::VarInt,
+        versioned::{Versioned, VersionedDeserialize, VersionedSerialize},
+    },
+    nbt::{Compound, Nbt},
+    protocol::ProtocolVersion,
+    DeError,
+};
+
+const LEGACY: ProtocolVersion = ProtocolVersion::V1_20;
+const COMPONENTS: ProtocolVersion = ProtocolVersion::V1_20_5;
+
+#[test]
+fn empty() {
+    assert_serialization!(Versioned::new(Slot::EMPTY, LEGACY) => &[0x00]);
+    assert_serialization!(Versioned::new(Slot::EMPTY, COMPONENTS) => &[0x00]);
+
+    let slot = Slot::deserialize_versioned(&[0x00][..], COMPONENTS).unwrap();
+    assert!(slot.is_empty());
+}
+
+#[test]
+fn legacy_nbt() {
+    let mut stack = ItemStack::new(0x100, 64, LEGACY);
+    stack.data = ItemData::Nbt(Compound::new().with("a", 1i8).into());
+
+    let expected = [
+        0x01, 0x80, 0x02, 0x40, 0x0a, 0x00, 0x00, 0x01, 0x00, 0x01, b'a', 0x01, 0x00,
+    ];
+    let slot = Slot::from(stack);
+
+    assert_serialization!(Versioned::new(&slot, LEGACY) => &expected);
+    assert_eq!(
+        Slot::deserialize_versioned(&expected[..], LEGACY).unwrap(),
+        slot
+    );
+}
+
+#[test]
+fn components_roundtrip() {
+    let data = [
+        0x01, // count
+        0x05, // item
+        0x03, // added
+        0x01, // removed
+        0x03, 0x0a, // damage: 10
+        0x05, 0x08, 0x00, 0x02, b'h', b'i', // custom_name: "hi"
+        0x1e, 0x01, 0x01, 0x02, 0x00, 0x00, // bundle_contents: [2x item 2]
+        0x08, // removed rarity
+    ];
+
+    let slot = Slot::deserialize_versioned(&data[..], COMPONENTS).unwrap();
+    let stack = slot.0.as_ref().unwrap();
+
+    let ItemData::Components(components) = &stack.data else {
+        panic!("expected data components")
+    };
+
+    assert_eq!(components.get(3).unwrap().as_ref(), [0x0a]);
+    assert_eq!(components.removed, [0x08]);
+    assert_eq!(slot.size_versioned(COMPONENTS), data.len());
+
+    let mut buf = BytesMut::new();
+    slot.serialize_versioned(&mut buf, COMPONENTS);
+    assert_eq!(buf.as_ref(), data);
+}
+
+#[test]
+fn components_unknown_layout() {
+    // component 0x7f doesn't exist, so the rest of the buffer is kept
+    let data = [0x01, 0x05, 0x02, 0x00, 0x03, 0x0a, 0x7f, 0x01, 0x02];
+    let slot = Slot::deserialize_versioned(&data[..], COMPONENTS).unwrap();
+
+    let stack = slot.0.as_ref().unwrap();
+    assert_eq!(
+        stack.data,
+        ItemData::Raw(Bytes::copy_from_slice(&data[2..]))
+    );
+
+    let mut buf = BytesMut::new();
+    slot.serialize_versioned(&mut buf, COMPONENTS);
+    assert_eq!(buf.as_ref(), data);
+    assert_eq!(slot.size_versioned(COMPONENTS), data.len());
+
+    // bundle containing an item with an unknown component
+    let data = [
+        0x01, 0x05, 0x01, 0x00, 0x1e, 0x01, 0x01, 0x02, 0x01, 0x00, 0x7f,
+    ];
+    let res = Slot::deserialize_versioned(&data[..3], COMPONENTS);
+    assert!(matches!(res, Err(DeError::Eof)));
+
+    let slot = Slot::deserialize_versioned(&data[..], COMPONENTS).unwrap();
+    let stack = slot.0.unwrap();
+    assert_eq!(
+        stack.data,
+        ItemData::Raw(Bytes::copy_from_slice(&data[2..]))
+    );
+}
+
+#[test]
+fn vanilla_registries() {
+    use ProtocolVersion as V;
+
+    let expected = [
+        (V::V1_20_5, 56, "container_loot"),
+        (V::V1_21, 57, "container_loot"),
+        (V::V1_21_2, 67, "container_loot"),
+        (V::V1_21_4, 67, "container_loot"),
+        (V::V1_21_5, 96, "shulker/color"),
+    ];
+
+    for (version, len, last) in expected {
+        let registry = ComponentRegistry::vanilla(version).unwrap();
+        assert_eq!(registry.name(len - 1), Some(last), "{version}");
+        assert_eq!(registry.name(len), None);
+
+        for id in 0..len {
+            assert!(registry.layout(id).is_some(), "{version} {id}");
+        }
+    }
+
+    assert!(ComponentRegistry::vanilla(V::V1_20_3).is_none());
+}
+
+#[test]
+fn components_since_1_21_5() {
+    let version = ProtocolVersion::V1_21_5;
+    let registry = ComponentRegistry::vanilla(version).unwrap();
+    let id = |name| registry.id(name).unwrap() as u8;
+
+    #[rustfmt::skip]
+    let data = [
+        0x01, 0x05, 0x03, 0x00,
+        // tooltip display hiding damage
+        id("tooltip_display"), 0x00, 0x01, 0x03,
+        // eaten in 1.5s, teleporting randomly
+        id("consumable"), 0x3f, 0xc0, 0x00, 0x00, 0x01, 0x00, 0x0b, b'e', b'n', b't',
+        b'i', b't', b'y', b'.', b'e', b'a', b't', 0x00, 0x01, 0x01, 0x03, 0x41, 0x80,
+        0x00, 0x00,
+        // can be placed on #minecraft:logs, with an exact component predicate
+        id("can_place_on"), 0x01, 0x01, 0x00, 0x0e, b'm', b'i', b'n', b'e', b'c', b'r',
+        b'a', b'f', b't', b':', b'l', b'o', b'g', b's', 0x00, 0x00, 0x01, id("damage"),
+        0x01, 0x00,
+    ];
+
+    let slot = Slot::deserialize_versioned(&data[..], version).unwrap();
+    let ItemData::Components(components) = &slot.0.as_ref().unwrap().data else {
+        panic!("expected data components")
+    };
+
+    assert_eq!(components.added.len(), 3);
+    assert_eq!(slot.size_versioned(version), data.len());
+}
+
+#[test]
+fn components_custom_registry() {
+    let mut registry = ComponentRegistry::new();
+    registry.register(200, "custom:thing", Some(Layout::array(Layout::VarInt)));
+
+    let data = [0x02, 0x05, 0x01, 0x00, 0xc8, 0x01, 0x02, 0x7f, 0xff, 0x01];
+    let stack = ItemStack::deserialize_with(&data[..], &registry)
+        .unwrap()
+        .unwrap();
+
+    let expected = ItemStack {
+        item: 5,
+        count: 2,
+        data: ItemData::Components(DataComponents {
+            added: vec![RawComponent {
+                id: 200,
+                data: Bytes::from_static(&[0x02, 0x7f, 0xff, 0x01]),
+            }],
+            removed: vec![],
+        }),
+    };
+
+    assert_eq!(stack, expected);
+}
+
+/// `depth` containers each holding the next one, around an empty slot
+fn nested_containers(depth: usize) -> Vec<u8> {
+    let version = ProtocolVersion::V1_21_5;
+    let container = ComponentRegistry::vanilla(version)
+        .and_then(|registry| registry.id("container"))
+        .unwrap();
+
+    // one item, with one added component holding one slot
+    let mut level = vec![0x01, 0x01, 0x01, 0x00];
+    level.extend(crate::encoding::serialize_bytes(VarInt(container)));
+    level.push(0x01);
+
+    let mut data = level.repeat(depth);
+    data.push(0x00);
+    data
+}
+
+#[test]
+fn nesting_limit() {
+    let version = ProtocolVersion::V1_21_5;
+
+    let data = nested_containers(MAX_DEPTH);
+    let slot = Slot::deserialize_versioned(&data[..], version).unwrap();
+    assert!(matches!(slot.0.unwrap().data, ItemData::Components(_)));
+
+    let data = nested_containers(MAX_DEPTH + 1);
+    let res = Slot::deserialize_versioned(&data[..], version);
+    assert!(matches!(res, Err(DeError::InvalidData)), "{res:?}");
+
+    // deep enough to overflow the stack without a limit, but under the packet size
+    let data = nested_containers(200_000);
+    let res = Slot::deserialize_versioned(&data[..], version);
+    assert!(matches!(res, Err(DeError::InvalidData)), "{res:?}");
+}
+
+#[test]
+fn mismatched_data_is_dropped() {
+    let mut stack = ItemStack::new(1, 1, COMPONENTS);
+    if let ItemData::Components(components) = &mut stack.data {
+        components.insert(3, VarInt(5));
+    }
+
+    let slot = Versioned::new(Slot::from(stack), LEGACY);
+    assert_serialization!(slot => &[0x01, 0x01, 0x01, 0x00]);
+
+    let slot = Slot::from(ItemStack {
+        data: ItemData::Nbt(Nbt::from(Compound::new())),
+        ..ItemStack::new(1, 1, LEGACY)
+    });
+    assert_serialization!(Versioned::new(&slot, COMPONENTS) => &[0x01, 0x01, 0x00, 0x00]);
+}
@@ -0,0 +1,304 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    pin::pin,
+    time::Duration,
+};
+
+use futures::{
+    future::{select, Either},
+    SinkExt, StreamExt,
+};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    time::{sleep_until, Instant},
+};
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+use crate::{
+    codec::CodecError,
+    connection::{PacketReceiver, PacketSender, SendError},
+    encoding::{deserialize_bytes, serialize_bytes, varint::VarInt},
+    packet::RawPacket,
+    protocol::{Direction, ProtocolVersion, State},
+    registry::{Key, PacketRegistry},
+};
+
+/// exchange running in its own task over a split connection
+mod task;
+#[cfg(test)]
+mod test;
+
+pub use task::{KeepAliveReceiver, KeepAliveTask};
+
+/// Error of the keep alive exchange
+#[derive(Debug, Error)]
+pub enum KeepAliveError {
+    /// Error reading or writing packets
+    #[error("codec: {0}")]
+    Codec(#[from] CodecError),
+
+    /// The other side didn't answer, or didn't send keep alives, in time.
+    /// Servers disconnect the client with `disconnect.timeout`
+    #[error("timed out after {0:?}")]
+    Timeout(Duration),
+
+    /// The client answered with an id that wasn't sent
+    #[error("unexpected keep alive id {0}")]
+    Id(i64),
+
+    /// The writer of the split connection stopped
+    #[error("connection closed")]
+    Closed(#[from] SendError),
+}
+
+/// Ids of the keep alive packets, which depend on the version and the state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAliveIds {
+    /// id of the keep alive sent by the server
+    pub clientbound: i32,
+    /// id of the answer of the client
+    pub serverbound: i32,
+}
+
+impl KeepAliveIds {
+    /// ids of the configuration state of `version`, `None` before 1.20.2
+    pub fn configuration(version: ProtocolVersion) -> Option<Self> {
+        let id = match version {
+            v if v >= ProtocolVersion::V1_20_5 => 0x04,
+            v if v >= ProtocolVersion::V1_20_2 => 0x03,
+            _ => return None,
+        };
+
+        Some(Self {
+            clientbound: id,
+            serverbound: id,
+        })
+    }
+
+    /// ids of the `keep_alive` packets of `registry` in `state`
+    pub fn from_registry(
+        registry: &PacketRegistry,
+        version: ProtocolVersion,
+        state: State,
+    ) -> Option<Self> {
+        let id = |direction| registry.id(Key::new(version, state, direction), "keep_alive");
+
+        Some(Self {
+            clientbound: id(Direction::Clientbound)?,
+            serverbound: id(Direction::Serverbound)?,
+        })
+    }
+
+    /// id of the keep alives received by the server, or by the client
+    fn received(&self, server: bool) -> i32 {
+        match server {
+            true => self.serverbound,
+            false => self.clientbound,
+        }
+    }
+}
+
+/// Keep alive exchange of either side of a connection, in the configuration
+/// and play states.
+///
+/// The server sends a random id every interval, and times out if the client
+/// doesn't echo it before the timeout, measuring the round trip latency.
+/// The client echoes the ids, and times out if the server doesn't send any
+/// for both the interval and the timeout.
+///
+/// Keep alives are exchanged while waiting for the other packets with
+/// [`Self::receive`], which must be called often enough to answer in time.
+/// Split connections exchange them from a task instead, see [`Self::split`]
+#[derive(Debug)]
+pub struct KeepAlive {
+    server: bool,
+    version: ProtocolVersion,
+    ids: KeepAliveIds,
+    interval: Duration,
+    timeout: Duration,
+    last: Instant,
+    pending: Option<i64>,
+    latency: Option<Duration>,
+}
+
+impl KeepAlive {
+    fn new(server: bool, version: ProtocolVersion, ids: KeepAliveIds) -> Self {
+        Self {
+            server,
+            version,
+            ids,
+            interval: Duration::from_secs(15),
+            timeout: Duration::from_secs(15),
+            last: Instant::now(),
+            pending: None,
+            latency: None,
+        }
+    }
+
+    /// server side of a connection with `version`, sending keep alives
+    pub fn server(version: ProtocolVersion, ids: KeepAliveIds) -> Self {
+        Self::new(true, version, ids)
+    }
+
+    /// client side of a connection with `version`, answering keep alives
+    pub fn client(version: ProtocolVersion, ids: KeepAliveIds) -> Self {
+        Self::new(false, version, ids)
+    }
+
+    /// time between keep alives, 15 seconds by default
+    pub fn interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// time the client has to answer, 15 seconds by default
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Changes the packet ids, when the connection switches state.
+    /// A keep alive sent in the previous state can't be answered anymore
+    pub fn set_ids(&mut self, ids: KeepAliveIds) {
+        self.pending = None;
+        self.ids = ids;
+    }
+
+    /// round trip latency measured by the last keep alive, server side only
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// whether `packet` is a keep alive of the other side
+    pub fn is_keep_alive(&self, packet: &RawPacket) -> bool {
+        packet.packet_id == self.ids.received(self.server)
+    }
+
+    /// Moves the exchange to a [`KeepAliveTask`] sending with `sender`, which
+    /// must be spawned. The returned receiver replaces `receiver`, passing it
+    /// the keep alives of the other side
+    pub fn split<S, C>(
+        self,
+        sender: PacketSender,
+        receiver: PacketReceiver<S, C>,
+    ) -> (KeepAliveReceiver<S, C>, KeepAliveTask) {
+        task::split(self, sender, receiver)
+    }
+
+    /// Receives the next packet that isn't a keep alive, sending and answering
+    /// keep alives meanwhile. `None` if the stream ended
+    pub async fn receive<S, C>(
+        &mut self,
+        framed: &mut Framed<S, C>,
+    ) -> Result<Option<RawPacket>, KeepAliveError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+        C: Decoder<Item = RawPacket, Error = CodecError>
+            + for<'a> Encoder<&'a RawPacket, Error = CodecError>,
+    {
+        loop {
+            if let Some(packet) = self.expire()? {
+                framed.send(&packet).await?;
+                continue;
+            }
+
+            let sleep = pin!(sleep_until(self.deadline()));
+            let packet = match select(framed.next(), sleep).await {
+                Either::Left((packet, _)) => packet,
+                Either::Right(_) => continue,
+            };
+
+            let Some(packet) = packet.transpose()? else {
+                return Ok(None);
+            };
+
+            if !self.is_keep_alive(&packet) {
+                return Ok(Some(packet));
+            }
+
+            if let Some(answer) = self.handle(&packet)? {
+                framed.send(&answer).await?;
+            }
+        }
+    }
+
+    fn legacy(&self) -> bool {
+        self.version < ProtocolVersion::V1_12_2
+    }
+
+    /// when the next keep alive is due, or the other side times out
+    fn deadline(&self) -> Instant {
+        match (self.server, self.pending) {
+            (true, Some(_)) => self.last + self.timeout,
+            (true, None) => self.last + self.interval,
+            (false, _) => self.last + self.interval + self.timeout,
+        }
+    }
+
+    /// Keep alive to send if one is due, or the timeout error
+    fn expire(&mut self) -> Result<Option<RawPacket>, KeepAliveError> {
+        let now = Instant::now();
+        if now < self.deadline() {
+            return Ok(None);
+        }
+
+        if !self.server || self.pending.is_some() {
+            return Err(KeepAliveError::Timeout(now - self.last));
+        }
+
+        let id = match self.legacy() {
+            true => random_id() as i32 as i64,
+            false => random_id(),
+        };
+
+        self.pending = Some(id);
+        self.last = now;
+        Ok(Some(self.packet(self.ids.clientbound, id)))
+    }
+
+    /// Handles a keep alive of the other side, returning the answer to send
+    fn handle(&mut self, packet: &RawPacket) -> Result<Option<RawPacket>, KeepAliveError> {
+        let data = packet.data.clone();
+        let id = match self.legacy() {
+            true => deserialize_bytes::<VarInt>(data).map(|id| id.0 as i64),
+            false => deserialize_bytes::<i64>(data),
+        };
+        let id = id.map_err(CodecError::from)?;
+
+        if !self.server {
+            self.last = Instant::now();
+            return Ok(Some(self.packet(self.ids.serverbound, id)));
+        }
+
+        match self.pending {
+            Some(pending) if pending == id => {
+                self.pending = None;
+                self.latency = Some(self.last.elapsed());
+                Ok(None)
+            }
+            _ => Err(KeepAliveError::Id(id)),
+        }
+    }
+
+    fn packet(&self, packet_id: i32, id: i64) -> RawPacket {
+        let data = match self.legacy() {
+            true => serialize_bytes(VarInt(id as i32)),
+            false => serialize_bytes(id),
+        };
+
+        RawPacket { packet_id, data }
+    }
+}
+
+/// only needs to be unpredictable enough that a client can't answer in advance
+fn random_id() -> i64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::UNIX_EPOCH
+            .elapsed()
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+
+    hasher.finish() as i64
+}
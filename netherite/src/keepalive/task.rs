@@ -0,0 +1,151 @@
+use std::{pin::pin, time::Duration};
+
+use futures::future::{select, Either};
+use tokio::{
+    io::AsyncRead,
+    sync::{mpsc, watch},
+    time::sleep_until,
+};
+use tokio_util::codec::Decoder;
+
+use super::{KeepAlive, KeepAliveError, KeepAliveIds};
+use crate::{
+    codec::CodecError,
+    connection::{PacketReceiver, PacketSender},
+    packet::RawPacket,
+};
+
+/// keep alives of the other side waiting for the task
+const CAPACITY: usize = 4;
+
+enum Message {
+    Packet(RawPacket),
+    Ids(KeepAliveIds),
+}
+
+pub(super) fn split<S, C>(
+    keep_alive: KeepAlive,
+    sender: PacketSender,
+    receiver: PacketReceiver<S, C>,
+) -> (KeepAliveReceiver<S, C>, KeepAliveTask) {
+    let (messages, queue) = mpsc::channel(CAPACITY);
+    let (latency, latest) = watch::channel(None);
+
+    let receiver = KeepAliveReceiver {
+        receiver,
+        server: keep_alive.server,
+        ids: keep_alive.ids,
+        messages,
+        latency: latest,
+    };
+    let task = KeepAliveTask {
+        keep_alive,
+        sender,
+        messages: queue,
+        latency,
+    };
+
+    (receiver, task)
+}
+
+/// Read half of a split connection, passing the keep alives
+/// of the other side to its [`KeepAliveTask`]
+pub struct KeepAliveReceiver<S, C> {
+    receiver: PacketReceiver<S, C>,
+    server: bool,
+    ids: KeepAliveIds,
+    messages: mpsc::Sender<Message>,
+    latency: watch::Receiver<Option<Duration>>,
+}
+
+impl<S, C> KeepAliveReceiver<S, C>
+where
+    S: AsyncRead + Unpin,
+    C: Decoder<Item = RawPacket, Error = CodecError> + Unpin,
+{
+    /// Receives the next packet that isn't a keep alive, `None` if the
+    /// stream ended. Waits while the task is behind on the keep alives
+    pub async fn receive(&mut self) -> Result<Option<RawPacket>, CodecError> {
+        loop {
+            let Some(packet) = self.receiver.receive().await? else {
+                return Ok(None);
+            };
+
+            if packet.packet_id != self.ids.received(self.server) {
+                return Ok(Some(packet));
+            }
+
+            // a stopped task returns its own error
+            let _ = self.messages.send(Message::Packet(packet)).await;
+        }
+    }
+}
+
+impl<S, C> KeepAliveReceiver<S, C> {
+    /// Changes the packet ids of both halves when the
+    /// connection switches state, see [`KeepAlive::set_ids`]
+    pub async fn set_ids(&mut self, ids: KeepAliveIds) {
+        self.ids = ids;
+        let _ = self.messages.send(Message::Ids(ids)).await;
+    }
+
+    /// round trip latency measured by the task, server side only
+    pub fn latency(&self) -> Option<Duration> {
+        *self.latency.borrow()
+    }
+
+    /// returns the underlying receiver, stopping the task
+    pub fn into_inner(self) -> PacketReceiver<S, C> {
+        self.receiver
+    }
+}
+
+/// Keep alive exchange of a split connection, sending with a
+/// [`PacketSender`] the keep alives and answers, and timing out by itself.
+/// It must be polled, usually by spawning [`Self::run`]
+pub struct KeepAliveTask {
+    keep_alive: KeepAlive,
+    sender: PacketSender,
+    messages: mpsc::Receiver<Message>,
+    latency: watch::Sender<Option<Duration>>,
+}
+
+impl KeepAliveTask {
+    /// Exchanges keep alives until the [`KeepAliveReceiver`] is dropped.
+    /// On errors, like [`KeepAliveError::Timeout`], the sender is closed so
+    /// that the writer stops once the queued packets are written
+    pub async fn run(mut self) -> Result<(), KeepAliveError> {
+        let res = self.exchange().await;
+        if res.is_err() {
+            self.sender.close();
+        }
+
+        res
+    }
+
+    async fn exchange(&mut self) -> Result<(), KeepAliveError> {
+        loop {
+            if let Some(packet) = self.keep_alive.expire()? {
+                self.sender.send_raw(packet).await?;
+                continue;
+            }
+
+            let sleep = pin!(sleep_until(self.keep_alive.deadline()));
+            let message = match select(pin!(self.messages.recv()), sleep).await {
+                Either::Left((message, _)) => message,
+                Either::Right(_) => continue,
+            };
+
+            match message {
+                None => return Ok(()),
+                Some(Message::Ids(ids)) => self.keep_alive.set_ids(ids),
+                Some(Message::Packet(packet)) => {
+                    if let Some(answer) = self.keep_alive.handle(&packet)? {
+                        self.sender.send_raw(answer).await?;
+                    }
+                    self.latency.send_replace(self.keep_alive.latency());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::SinkExt;
+use tokio::io::{duplex, DuplexStream};
+use tokio_util::codec::Framed;
+
+use super::{KeepAlive, KeepAliveError, KeepAliveIds};
+use crate::{
+    connection::{Connection, GameProfile},
+    encoding::str::Str,
+    packet::RawPacket,
+    protocol::{ProtocolVersion as V, State},
+    registry::PacketRegistry,
+    UncompressedCodec,
+};
+
+const REGISTRY: &str = "
+[47 play clientbound]
+0x00 keep_alive
+
+[47 play serverbound]
+0x00 keep_alive
+";
+
+type Stream = Framed<DuplexStream, UncompressedCodec>;
+
+fn pair() -> (Stream, Stream) {
+    let (client, server) = duplex(4096);
+    let codec = UncompressedCodec::default;
+
+    (Framed::new(client, codec()), Framed::new(server, codec()))
+}
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Answers keep alives for `duration`, then sends a packet ending the exchange
+async fn client(mut keep_alive: KeepAlive, mut framed: Stream, duration: Duration) {
+    let res = tokio::time::timeout(duration, keep_alive.receive(&mut framed)).await;
+    assert!(res.is_err(), "{res:?}");
+
+    let done = RawPacket {
+        packet_id: 0x7f,
+        data: Bytes::from_static(b"done"),
+    };
+    framed.send(&done).await.unwrap();
+}
+
+#[test]
+fn ids() {
+    let configuration = KeepAliveIds::configuration;
+    assert_eq!(configuration(V::V1_21_5).unwrap().clientbound, 0x04);
+    assert_eq!(configuration(V::V1_20_2).unwrap().serverbound, 0x03);
+    assert_eq!(configuration(V::V1_20), None);
+
+    let mut registry = PacketRegistry::new();
+    registry.load(REGISTRY).unwrap();
+
+    let ids = KeepAliveIds::from_registry(&registry, V::V1_8, State::Play).unwrap();
+    assert_eq!(ids.serverbound, 0x00);
+    assert!(KeepAliveIds::from_registry(&registry, V::V1_9, State::Play).is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn exchange() {
+    let mut registry = PacketRegistry::new();
+    registry.load(REGISTRY).unwrap();
+    let legacy = KeepAliveIds::from_registry(&registry, V::V1_8, State::Play).unwrap();
+    let configuration = KeepAliveIds::configuration(V::V1_21_5).unwrap();
+
+    for (version, ids) in [(V::V1_8, legacy), (V::V1_21_5, configuration)] {
+        let (client_stream, mut server_stream) = pair();
+
+        let keep_alive = KeepAlive::client(version, ids).interval(ms(20));
+        let client = client(keep_alive, client_stream, ms(100));
+
+        let mut server = KeepAlive::server(version, ids).interval(ms(20));
+        let received = server.receive(&mut server_stream);
+
+        let ((), received) = futures::join!(client, received);
+        let received = received.unwrap().unwrap();
+
+        assert_eq!(&received.data[..], b"done");
+        assert!(server.latency().is_some());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn timeout() {
+    let ids = KeepAliveIds::configuration(V::V1_21_5).unwrap();
+
+    let (_client, mut server_stream) = pair();
+    let mut server = KeepAlive::server(V::V1_21_5, ids)
+        .interval(ms(10))
+        .timeout(ms(20));
+
+    let res = server.receive(&mut server_stream).await;
+    assert!(matches!(res, Err(KeepAliveError::Timeout(_))), "{res:?}");
+    assert_eq!(server.latency(), None);
+
+    let (mut client_stream, _server) = pair();
+    let mut client = KeepAlive::client(V::V1_21_5, ids)
+        .interval(ms(10))
+        .timeout(ms(10));
+
+    let res = client.receive(&mut client_stream).await;
+    assert!(matches!(res, Err(KeepAliveError::Timeout(_))), "{res:?}");
+}
+
+#[tokio::test]
+async fn unexpected_id() {
+    let ids = KeepAliveIds::configuration(V::V1_21_5).unwrap();
+
+    let (mut client_stream, mut server_stream) = pair();
+    let mut server = KeepAlive::server(V::V1_21_5, ids);
+
+    let answer = RawPacket {
+        packet_id: ids.serverbound,
+        data: Bytes::copy_from_slice(&42i64.to_be_bytes()),
+    };
+    client_stream.send(&answer).await.unwrap();
+
+    let res = server.receive(&mut server_stream).await;
+    assert!(matches!(res, Err(KeepAliveError::Id(42))), "{res:?}");
+
+    drop(client_stream);
+    let res = KeepAlive::client(V::V1_21_5, ids)
+        .receive(&mut server_stream)
+        .await;
+    assert!(matches!(res, Ok(None)));
+}
+
+fn connection(stream: DuplexStream) -> Connection<DuplexStream, UncompressedCodec> {
+    let profile = GameProfile {
+        uuid: 0,
+        username: Str::from_static("Notch"),
+        properties: vec![],
+    };
+    let framed = Framed::new(stream, UncompressedCodec::default());
+
+    Connection::new(framed, V::V1_21_5, State::Configuration, profile)
+}
+
+#[tokio::test(start_paused = true)]
+async fn task() {
+    let ids = KeepAliveIds::configuration(V::V1_21_5).unwrap();
+    let (client_stream, server_stream) = pair();
+
+    let keep_alive = KeepAlive::client(V::V1_21_5, ids).interval(ms(20));
+    let client = client(keep_alive, client_stream, ms(100));
+
+    let (sender, receiver, writer) = connection(server_stream.into_inner()).split(4);
+    let server = KeepAlive::server(V::V1_21_5, ids).interval(ms(20));
+    let (mut receiver, task) = server.split(sender, receiver);
+
+    let received = async move {
+        let received = receiver.receive().await.unwrap().unwrap();
+        assert_eq!(&received.data[..], b"done");
+        assert!(receiver.latency().is_some());
+    };
+
+    // dropping the receiver stops the task, and then the writer
+    let ((), (), exchanged, written) = futures::join!(client, received, task.run(), writer.run());
+    exchanged.unwrap();
+    written.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn task_timeout() {
+    let ids = KeepAliveIds::configuration(V::V1_21_5).unwrap();
+    let (_client, server_stream) = pair();
+
+    let (sender, receiver, writer) = connection(server_stream.into_inner()).split(4);
+    let server = KeepAlive::server(V::V1_21_5, ids)
+        .interval(ms(10))
+        .timeout(ms(20));
+    let (_receiver, task) = server.split(sender, receiver);
+
+    // nobody waits on the receiver, the task times out by itself and stops the writer
+    let (exchanged, written) = futures::join!(task.run(), writer.run());
+    assert!(
+        matches!(exchanged, Err(KeepAliveError::Timeout(_))),
+        "{exchanged:?}"
+    );
+    written.unwrap();
+}
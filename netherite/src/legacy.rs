@@ -0,0 +1,300 @@
+use std::{io, time::Duration};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    net::TcpStream,
+    time::{timeout_at, Instant},
+};
+
+use crate::{DeError, Deserialize, Serialize};
+
+#[cfg(test)]
+mod test;
+
+/// First byte sent by clients doing a legacy ping
+pub const PING: u8 = 0xFE;
+/// Id of the kick packet answering a legacy ping
+pub const KICK: u8 = 0xFF;
+/// Id of the plugin message carrying the 1.6 ping payload
+const PLUGIN_MESSAGE: u8 = 0xFA;
+/// Channel of the 1.6 ping payload
+const PING_HOST: &str = "MC|PingHost";
+/// Maximum size of a ping, the 1.6 payload only carries an address
+const MAX_SIZE: usize = 1024;
+
+/// Whether `stream` starts with a legacy ping, without consuming any data.
+///
+/// The first byte of a modern connection is the length of the handshake,
+/// which can't be [`PING`], so this should be checked before reading the
+/// stream with [`crate::UncompressedCodec`].
+/// [`crate::status::responder::Responder`] does this by itself
+pub async fn detect(stream: &TcpStream) -> io::Result<bool> {
+    let mut first = [0];
+    let read = stream.peek(&mut first).await?;
+
+    Ok(read == 1 && first[0] == PING)
+}
+
+/// Legacy (pre-Netty) server list ping sent by the client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LegacyPing {
+    /// Beta 1.8 to 1.3, a single `0xFE` byte
+    Beta,
+    /// 1.4 and 1.5, `0xFE 0x01`
+    V1_4,
+    /// 1.6, followed by a `MC|PingHost` plugin message
+    V1_6 {
+        /// protocol version of the client
+        protocol: u8,
+        /// address used by the client to connect
+        host: String,
+        /// port used by the client to connect
+        port: i32,
+    },
+}
+
+fn utf16_size(string: &str) -> usize {
+    2 + string.encode_utf16().count() * 2
+}
+
+/// writes a string prefixed by its length in UTF-16 code units
+fn write_utf16(string: &str, mut buf: impl BufMut) {
+    let len = string.encode_utf16().count();
+    buf.put_u16(len.try_into().unwrap_or(u16::MAX));
+
+    for unit in string.encode_utf16().take(u16::MAX as usize) {
+        buf.put_u16(unit);
+    }
+}
+
+fn read_utf16(mut buffer: impl Buf) -> Result<String, DeError> {
+    let len = u16::deserialize(&mut buffer)? as usize;
+    if buffer.remaining() < len * 2 {
+        return Err(DeError::Eof);
+    }
+
+    let units: Vec<u16> = (0..len).map(|_| buffer.get_u16()).collect();
+    String::from_utf16(&units).map_err(|_| DeError::InvalidData)
+}
+
+impl Serialize for LegacyPing {
+    fn serialize(&self, mut buf: impl BufMut) {
+        buf.put_u8(PING);
+
+        match self {
+            LegacyPing::Beta => {}
+            LegacyPing::V1_4 => buf.put_u8(0x01),
+            LegacyPing::V1_6 {
+                protocol,
+                host,
+                port,
+            } => {
+                buf.put_u8(0x01);
+                buf.put_u8(PLUGIN_MESSAGE);
+                write_utf16(PING_HOST, &mut buf);
+
+                let data_size = 1 + utf16_size(host) + 4;
+                buf.put_u16(data_size as u16);
+                buf.put_u8(*protocol);
+                write_utf16(host, &mut buf);
+                buf.put_i32(*port);
+            }
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            LegacyPing::Beta => 1,
+            LegacyPing::V1_4 => 2,
+            LegacyPing::V1_6 { host, .. } => {
+                3 + utf16_size(PING_HOST) + 2 + 1 + utf16_size(host) + 4
+            }
+        }
+    }
+}
+
+impl LegacyPing {
+    /// Parses a ping after which no more data arrives. Older clients send a
+    /// prefix of the newer pings and wait for the answer, so `0xFE` alone is
+    /// [`Self::Beta`], and `0xFE 0x01` not followed by a complete 1.6 payload
+    /// is [`Self::V1_4`]
+    pub fn deserialize_complete(mut buffer: impl Buf) -> Result<Self, DeError> {
+        let data = buffer.copy_to_bytes(buffer.remaining());
+
+        match Self::deserialize(&data[..]) {
+            Err(DeError::Eof) => match data[..] {
+                [PING] => Ok(LegacyPing::Beta),
+                [PING, 0x01, ..] => Ok(LegacyPing::V1_4),
+                _ => Err(DeError::Eof),
+            },
+            result => result,
+        }
+    }
+
+    /// Reads a ping from `stream`, after the bytes already in `buffer`.
+    ///
+    /// Segments of the ping may arrive separately, so this waits for up to
+    /// `wait` until it is complete, then parses the bytes received so far
+    /// with [`Self::deserialize_complete`]
+    pub async fn read<S>(stream: &mut S, buffer: &mut BytesMut, wait: Duration) -> io::Result<Self>
+    where
+        S: AsyncRead + Unpin,
+    {
+        let deadline = Instant::now() + wait;
+        let invalid = |error: DeError| io::Error::new(io::ErrorKind::InvalidData, error);
+
+        loop {
+            match Self::deserialize(&buffer[..]) {
+                Err(DeError::Eof) if buffer.len() < MAX_SIZE => {}
+                result => return result.map_err(invalid),
+            }
+
+            match timeout_at(deadline, stream.read_buf(buffer)).await {
+                Ok(Ok(0)) | Err(_) => break,
+                Ok(read) => read?,
+            };
+        }
+
+        Self::deserialize_complete(&buffer[..]).map_err(|error| match error {
+            DeError::Eof => io::ErrorKind::UnexpectedEof.into(),
+            error => invalid(error),
+        })
+    }
+}
+
+/// Parses a complete ping, failing with [`DeError::Eof`] while it could still
+/// be the prefix of a newer one: the variants can only be told apart by what
+/// follows `0xFE`, and older clients don't send anything else.
+/// See [`LegacyPing::deserialize_complete`] once no more data arrives
+impl Deserialize for LegacyPing {
+    fn deserialize(mut buffer: impl Buf) -> Result<Self, DeError> {
+        if u8::deserialize(&mut buffer)? != PING {
+            return Err(DeError::InvalidData);
+        }
+
+        if u8::deserialize(&mut buffer)? != 0x01 {
+            return Err(DeError::InvalidData);
+        }
+
+        // some 1.5 clients send an empty payload
+        if u8::deserialize(&mut buffer)? != PLUGIN_MESSAGE {
+            return Ok(LegacyPing::V1_4);
+        }
+
+        if read_utf16(&mut buffer)? != PING_HOST {
+            return Err(DeError::InvalidData);
+        }
+
+        let data_size = u16::deserialize(&mut buffer)? as usize;
+        if buffer.remaining() < data_size {
+            return Err(DeError::Eof);
+        }
+
+        Ok(LegacyPing::V1_6 {
+            protocol: Deserialize::deserialize(&mut buffer)?,
+            host: read_utf16(&mut buffer)?,
+            port: Deserialize::deserialize(buffer)?,
+        })
+    }
+}
+
+/// Status sent in the kick packet answering a [`LegacyPing`].
+///
+/// Serializes in the `§1` format understood since 1.4, use [`Self::response`]
+/// to answer older clients too
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyStatus {
+    /// protocol version of the server, 0 if unknown
+    pub protocol: i32,
+    /// version name of the server
+    pub version: String,
+    /// message of the day, without formatting
+    pub motd: String,
+    /// players online
+    pub online: i32,
+    /// maximum number of players
+    pub max: i32,
+}
+
+impl LegacyStatus {
+    fn text(&self, ping: &LegacyPing) -> String {
+        let Self {
+            protocol,
+            version,
+            motd,
+            online,
+            max,
+        } = self;
+
+        match ping {
+            // the separator can't be part of the motd
+            LegacyPing::Beta => format!("{}§{online}§{max}", motd.replace('§', "")),
+            _ => format!("§1\0{protocol}\0{version}\0{motd}\0{online}\0{max}"),
+        }
+    }
+
+    /// kick packet answering `ping`, in the format understood by the client
+    pub fn response(&self, ping: &LegacyPing) -> Bytes {
+        let text = self.text(ping);
+
+        let mut buf = BytesMut::with_capacity(1 + utf16_size(&text));
+        buf.put_u8(KICK);
+        write_utf16(&text, &mut buf);
+
+        buf.freeze()
+    }
+}
+
+impl Serialize for LegacyStatus {
+    fn serialize(&self, mut buf: impl BufMut) {
+        buf.put_u8(KICK);
+        write_utf16(&self.text(&LegacyPing::V1_4), buf);
+    }
+
+    fn size(&self) -> usize {
+        1 + utf16_size(&self.text(&LegacyPing::V1_4))
+    }
+}
+
+/// Accepts both the `§1` format and the one of beta servers,
+/// whose protocol and version are left empty
+impl Deserialize for LegacyStatus {
+    fn deserialize(mut buffer: impl Buf) -> Result<Self, DeError> {
+        if u8::deserialize(&mut buffer)? != KICK {
+            return Err(DeError::InvalidData);
+        }
+
+        let text = read_utf16(buffer)?;
+        let number = |field: &str| field.parse().map_err(|_| DeError::InvalidData);
+
+        if let Some(fields) = text.strip_prefix("§1\0") {
+            let fields: Vec<&str> = fields.split('\0').collect();
+            let [protocol, version, motd, online, max] = fields[..] else {
+                return Err(DeError::InvalidData);
+            };
+
+            return Ok(Self {
+                protocol: number(protocol)?,
+                version: version.to_string(),
+                motd: motd.to_string(),
+                online: number(online)?,
+                max: number(max)?,
+            });
+        }
+
+        let mut fields = text.rsplitn(3, '§');
+        let (Some(max), Some(online), Some(motd)) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(DeError::InvalidData);
+        };
+
+        Ok(Self {
+            protocol: 0,
+            version: String::new(),
+            motd: motd.to_string(),
+            online: number(online)?,
+            max: number(max)?,
+        })
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use bytes::BytesMut;
+use tokio::{
+    io::{duplex, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use super::{detect, LegacyPing, LegacyStatus};
+use crate::{
+    assert_serialization,
+    encoding::{deserialize_bytes, serialize_bytes},
+    DeError,
+};
+
+fn utf16(string: &str) -> Vec<u8> {
+    string.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+fn status() -> LegacyStatus {
+    LegacyStatus {
+        protocol: 78,
+        version: "1.6.4".into(),
+        motd: "A Minecraft Server".into(),
+        online: 1,
+        max: 20,
+    }
+}
+
+fn v1_6() -> (LegacyPing, Vec<u8>) {
+    let host = utf16("localhost");
+    let mut data = vec![0xFE, 0x01, 0xFA, 0x00, 0x0B];
+    data.extend(utf16("MC|PingHost"));
+    data.extend([0x00, (7 + host.len()) as u8, 78, 0x00, 0x09]);
+    data.extend(host);
+    data.extend(25565i32.to_be_bytes());
+
+    let ping = LegacyPing::V1_6 {
+        protocol: 78,
+        host: "localhost".into(),
+        port: 25565,
+    };
+
+    (ping, data)
+}
+
+#[test]
+fn ping_variants() {
+    // could still be followed by a newer ping
+    for data in [&[0xFE][..], &[0xFE, 0x01]] {
+        let partial = deserialize_bytes::<LegacyPing>(data);
+        assert!(matches!(partial, Err(DeError::Eof)));
+    }
+
+    assert_eq!(
+        LegacyPing::deserialize_complete(&[0xFE][..]).unwrap(),
+        LegacyPing::Beta
+    );
+    assert_eq!(
+        LegacyPing::deserialize_complete(&[0xFE, 0x01][..]).unwrap(),
+        LegacyPing::V1_4
+    );
+
+    let (ping, data) = v1_6();
+    assert_eq!(deserialize_bytes::<LegacyPing>(&data[..]).unwrap(), ping);
+    assert_serialization!(&ping => &data[..]);
+
+    // payload not received yet
+    let partial = deserialize_bytes::<LegacyPing>(&data[..data.len() - 2]);
+    assert!(matches!(partial, Err(DeError::Eof)));
+    let partial = LegacyPing::deserialize_complete(&data[..data.len() - 2]);
+    assert_eq!(partial.unwrap(), LegacyPing::V1_4);
+
+    // modern handshake
+    let modern = deserialize_bytes::<LegacyPing>(&[0x10, 0x00][..]);
+    assert!(matches!(modern, Err(DeError::InvalidData)));
+}
+
+#[test]
+fn response() {
+    let status = status();
+
+    let mut expected = vec![0xFF, 0x00, 0x23];
+    expected.extend(utf16(
+        &["§1", "78", "1.6.4", "A Minecraft Server", "1", "20"].join("\0"),
+    ));
+    assert_serialization!(&status => &expected[..]);
+    assert_eq!(status.response(&LegacyPing::V1_4).as_ref(), expected);
+    assert_eq!(
+        deserialize_bytes::<LegacyStatus>(&expected[..]).unwrap(),
+        status
+    );
+
+    let beta = status.response(&LegacyPing::Beta);
+    assert_eq!(&beta[3..], utf16("A Minecraft Server§1§20"));
+
+    let parsed: LegacyStatus = deserialize_bytes(beta).unwrap();
+    assert_eq!((parsed.protocol, parsed.version.as_str()), (0, ""));
+    assert_eq!(
+        (parsed.motd, parsed.online, parsed.max),
+        (status.motd, 1, 20)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn read() {
+    let wait = Duration::from_millis(200);
+    let (ping, data) = v1_6();
+
+    // the payload arrives in a later segment
+    let (mut client, mut server) = duplex(1024);
+    let mut buffer = BytesMut::from(&data[..1]);
+    let write = async {
+        tokio::time::sleep(wait / 2).await;
+        client.write_all(&data[1..]).await.unwrap();
+    };
+
+    let (read, ()) = futures::join!(LegacyPing::read(&mut server, &mut buffer, wait), write);
+    assert_eq!(read.unwrap(), ping);
+
+    // nothing else arrives before the deadline
+    let (mut client, mut server) = duplex(1024);
+    client.write_all(&[0xFE, 0x01]).await.unwrap();
+    let read = LegacyPing::read(&mut server, &mut BytesMut::new(), wait).await;
+    assert_eq!(read.unwrap(), LegacyPing::V1_4);
+
+    // the client closed the connection
+    drop(client);
+    let read = LegacyPing::read(&mut server, &mut BytesMut::new(), wait).await;
+    assert!(read.is_err());
+}
+
+#[tokio::test]
+async fn detection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    for (data, legacy) in [
+        (serialize_bytes(LegacyPing::V1_4), true),
+        ([0x10].as_slice().into(), false),
+    ] {
+        let mut client = TcpStream::connect(address).await.unwrap();
+        client.write_all(&data).await.unwrap();
+
+        let (server, _) = listener.accept().await.unwrap();
+        assert_eq!(detect(&server).await.unwrap(), legacy);
+    }
+}
@@ -1,16 +1,57 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+/// plugin message channels, carried by custom payload packets
+pub mod channel;
+/// chunk sections and paletted containers
+pub mod chunk;
 /// tokio_util codec for serializing and deserializing Minecraft packets
 pub mod codec;
+/// registry synchronization of the configuration state (1.20.2+)
+pub mod configuration;
+/// connection of a player after the login
+pub mod connection;
+/// cookies stored on the client across transfers (1.20.5+)
+pub mod cookie;
+/// encryption primitives of the online mode login
+#[cfg(feature = "crypto")]
+pub mod crypto;
 /// traits and types for data encoding of Minecraft packets
 pub mod encoding;
+/// item stacks and inventory slots
+pub mod item;
+/// keep alive exchange of both sides of a connection
+pub mod keepalive;
+/// legacy (pre-Netty) server list ping
+pub mod legacy;
+/// offline mode login of clients and servers
+pub mod login;
+/// entity metadata
+pub mod metadata;
+/// packet interception layers for proxies
+pub mod middleware;
+/// Named Binary Tag format, as used by the network protocol
+pub mod nbt;
 /// structs representing Minecraft packets
 pub mod packet;
+/// protocol versions
+pub mod protocol;
+/// packet id tables of multiple protocol versions
+pub mod registry;
+/// async packet handlers dispatched by packet type and state
+pub mod router;
+/// server list ping client and responder
+#[cfg(feature = "serde_json")]
+pub mod status;
+/// Minecraft text components and legacy formatting
+pub mod text;
+/// packet translation between protocol versions
+pub mod translate;
 /// Minecraft VarInt implementation
 pub mod varint;
 
 pub(crate) mod peek;
+pub(crate) mod record;
 
 pub use codec::UncompressedCodec;
 pub use encoding::{
@@ -18,5 +59,8 @@ pub use encoding::{
     ser::Serialize,
 };
 
+// lets the derive macros be used inside this crate
+extern crate self as netherite;
+
 pub use bytes as _bytes_export;
-pub use netherite_derive::{Deserialize, Serialize};
+pub use netherite_derive::{Deserialize, PacketEnum, PacketId, Serialize};
@@ -0,0 +1,618 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use md5::{Digest, Md5};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+#[cfg(feature = "crypto")]
+use crate::{codec::encrypted::Encrypted, crypto::CryptoError};
+use crate::{
+    codec::{dual::SwitchCodec, CodecError},
+    connection::{Connection, GameProfile},
+    cookie::{self, CookieJar},
+    encoding::{
+        identifier::Identifier,
+        packetid::PacketId,
+        str::Str,
+        varint::VarInt,
+        versioned::{Versioned, VersionedDeserialize},
+    },
+    packet::{
+        handshake::{Handshake, Intent},
+        login::{
+            CookieRequest, CookieResponse, Disconnect, EncryptionRequest, LoginAcknowledged,
+            LoginPluginRequest, LoginPluginResponse, LoginStart, LoginSuccess, SetCompression,
+        },
+        RawPacket,
+    },
+    protocol::{ProtocolVersion, State, DEFAULT_PORT},
+    DeError, Deserialize, Serialize,
+};
+
+/// BungeeCord legacy IP forwarding, in the handshake
+#[cfg(feature = "serde_json")]
+pub mod bungee;
+/// online mode login, with encryption and authentication
+#[cfg(feature = "crypto")]
+pub mod online;
+pub(crate) mod sha256;
+#[cfg(test)]
+mod test;
+/// Velocity modern player info forwarding
+pub mod velocity;
+
+/// Error during the login
+#[derive(Debug, Error)]
+pub enum LoginError {
+    /// Error reading or writing packets
+    #[error("codec: {0}")]
+    Codec(#[from] CodecError),
+
+    /// Connection closed before the end of the login
+    #[error("connection closed")]
+    Closed,
+
+    /// Server disconnected the client, with a JSON text component
+    #[error("disconnected: {0}")]
+    Disconnected(Str),
+
+    /// Packet not expected at this point of the login
+    #[error("unexpected packet {id:#04x} in state {state:?}")]
+    UnexpectedPacket {
+        /// state of the connection
+        state: State,
+        /// id of the packet
+        id: i32,
+    },
+
+    /// Server is in online mode and requested encryption
+    #[error("server requested encryption")]
+    Encryption,
+
+    /// Client connected to get the status, not to login
+    #[error("client requested the status")]
+    Status,
+
+    /// Client answered a cookie request with a payload bigger
+    /// than [`cookie::MAX_SIZE`]
+    #[error("cookie of {0} bytes is too large")]
+    CookieSize(usize),
+
+    /// Player info forwarded by the proxy is missing or invalid
+    #[error("forwarding: {0}")]
+    Forwarding(#[from] ForwardingError),
+
+    /// Encryption couldn't be set up
+    #[cfg(feature = "crypto")]
+    #[error("crypto: {0}")]
+    Crypto(#[from] CryptoError),
+
+    /// Client sent the wrong verify token, or a signature
+    /// the server can't check
+    #[cfg(feature = "crypto")]
+    #[error("encryption verification failed")]
+    Verification,
+
+    /// Session server couldn't be queried
+    #[cfg(feature = "crypto")]
+    #[error("authentication: {0}")]
+    Authentication(#[from] online::AuthError),
+
+    /// Player didn't join the server through the session server
+    #[cfg(feature = "crypto")]
+    #[error("player is not authenticated")]
+    Unauthenticated,
+}
+
+/// Error receiving the player info forwarded by the proxy
+#[derive(Debug, Error)]
+pub enum ForwardingError {
+    /// Player info is missing, the client didn't
+    /// connect through a proxy
+    #[error("player info wasn't forwarded")]
+    NotForwarded,
+
+    /// Signature doesn't match the data, the proxy has
+    /// a different secret or the data was forged
+    #[error("invalid forwarding signature")]
+    Signature,
+
+    /// Forwarding version isn't supported
+    #[error("unsupported forwarding version {0}")]
+    Version(i32),
+
+    /// BungeeGuard token is missing or isn't allowed
+    #[error("invalid BungeeGuard token")]
+    Token,
+
+    /// Forwarded data is malformed
+    #[error("forwarded data: {0}")]
+    Data(#[from] DeError),
+}
+
+/// Uuid of `username` on offline mode servers: a version 3
+/// uuid of `OfflinePlayer:<username>`
+pub fn offline_uuid(username: &str) -> u128 {
+    let mut hash: [u8; 16] = Md5::digest(format!("OfflinePlayer:{username}")).into();
+    hash[6] = hash[6] & 0x0f | 0x30;
+    hash[8] = hash[8] & 0x3f | 0x80;
+
+    u128::from_be_bytes(hash)
+}
+
+/// state after the login, the configuration state was added with 1.20.2
+fn next_state(version: ProtocolVersion) -> State {
+    match version >= ProtocolVersion::V1_20_2 {
+        true => State::Configuration,
+        false => State::Play,
+    }
+}
+
+/// Codecs the login can run on, with or without encryption
+trait LoginCodec:
+    Decoder<Item = RawPacket, Error = CodecError> + for<'a> Encoder<&'a RawPacket, Error = CodecError>
+{
+    fn framing(&mut self) -> &mut SwitchCodec;
+}
+
+impl LoginCodec for SwitchCodec {
+    fn framing(&mut self) -> &mut SwitchCodec {
+        self
+    }
+}
+
+#[cfg(feature = "crypto")]
+impl LoginCodec for Encrypted<SwitchCodec> {
+    fn framing(&mut self) -> &mut SwitchCodec {
+        self.inner_mut()
+    }
+}
+
+async fn receive<S, C>(framed: &mut Framed<S, C>) -> Result<RawPacket, LoginError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: LoginCodec,
+{
+    framed
+        .next()
+        .await
+        .ok_or(LoginError::Closed)?
+        .map_err(Into::into)
+}
+
+fn unexpected(state: State, packet: &RawPacket) -> LoginError {
+    LoginError::UnexpectedPacket {
+        state,
+        id: packet.packet_id,
+    }
+}
+
+/// deserializes `packet` as `T`, failing if it's a different packet
+fn expect<T>(packet: &RawPacket, version: ProtocolVersion) -> Result<T, LoginError>
+where
+    T: VersionedDeserialize + PacketId,
+{
+    let result = packet.deserialize_versioned(version);
+    let result = result.ok_or_else(|| unexpected(State::Login, packet))?;
+
+    Ok(result.map_err(CodecError::from)?)
+}
+
+async fn send<S, C, T>(framed: &mut Framed<S, C>, packet: T) -> Result<(), LoginError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: LoginCodec,
+    T: Serialize + PacketId,
+{
+    Ok(framed.send(&RawPacket::from(packet)).await?)
+}
+
+/// deserializes a packet whose id was already checked
+fn read<T: Deserialize>(packet: &RawPacket) -> Result<T, LoginError> {
+    let packet = Deserialize::deserialize(packet.data.clone());
+    Ok(packet.map_err(CodecError::from)?)
+}
+
+/// Handler of the login plugin requests received by the client
+type PluginHandler = Box<dyn FnMut(&LoginPluginRequest) -> Option<Bytes> + Send>;
+
+/// Client side of the offline mode login
+pub struct ClientLogin {
+    username: String,
+    uuid: Option<u128>,
+    version: ProtocolVersion,
+    host: String,
+    port: u16,
+    transferred: bool,
+    plugin: Option<PluginHandler>,
+    cookies: CookieJar,
+}
+
+impl ClientLogin {
+    /// login as `username` with protocol `version`
+    pub fn new(username: impl Into<String>, version: ProtocolVersion) -> Self {
+        Self {
+            username: username.into(),
+            uuid: None,
+            version,
+            host: "localhost".into(),
+            port: DEFAULT_PORT,
+            transferred: false,
+            plugin: None,
+            cookies: CookieJar::new(),
+        }
+    }
+
+    /// address sent in the handshake
+    pub fn address(self, host: impl Into<String>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            ..self
+        }
+    }
+
+    /// uuid sent in the login start (1.19.1+), the offline uuid by default
+    pub fn uuid(self, uuid: u128) -> Self {
+        Self {
+            uuid: Some(uuid),
+            ..self
+        }
+    }
+
+    /// Connects with the transfer intent, after being
+    /// transferred by another server (1.20.5+)
+    pub fn transferred(self, transferred: bool) -> Self {
+        Self {
+            transferred,
+            ..self
+        }
+    }
+
+    /// Answers the cookie requests of the server with the cookies of
+    /// `jar`, an empty jar by default. Share the jar with the next
+    /// logins to keep the cookies across transfers
+    pub fn cookies(self, jar: CookieJar) -> Self {
+        Self {
+            cookies: jar,
+            ..self
+        }
+    }
+
+    /// Answers login plugin requests with `handler`. Requests for which
+    /// it returns `None`, or every request without a handler, are
+    /// answered as not understood
+    pub fn plugin<F>(self, handler: F) -> Self
+    where
+        F: FnMut(&LoginPluginRequest) -> Option<Bytes> + Send + 'static,
+    {
+        Self {
+            plugin: Some(Box::new(handler)),
+            ..self
+        }
+    }
+
+    /// Logs into the server on the other side of `stream`
+    pub async fn login<S>(mut self, stream: S) -> Result<Connection<S>, LoginError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let version = self.version;
+        let mut framed = Framed::new(stream, SwitchCodec::default());
+
+        framed
+            .send(Handshake {
+                protocol_version: version,
+                server_address: self.host.clone().into(),
+                server_port: self.port,
+                intent: match self.transferred {
+                    true => Intent::Transfer,
+                    false => Intent::Login,
+                },
+            })
+            .await?;
+
+        let uuid = self.uuid.unwrap_or_else(|| offline_uuid(&self.username));
+        let start = LoginStart {
+            username: self.username.clone().into(),
+            key: None,
+            uuid: Some(uuid),
+        };
+        framed.send(Versioned::new(start, version)).await?;
+
+        loop {
+            let packet = receive(&mut framed).await?;
+
+            match packet.packet_id {
+                Disconnect::ID => {
+                    let Disconnect { reason } = read(&packet)?;
+                    return Err(LoginError::Disconnected(reason));
+                }
+                EncryptionRequest::ID => return Err(LoginError::Encryption),
+                SetCompression::ID => {
+                    let SetCompression { threshold } = read(&packet)?;
+                    framed
+                        .codec_mut()
+                        .set_threshold(threshold.0.try_into().ok());
+                }
+                LoginPluginRequest::ID => {
+                    let request: LoginPluginRequest = read(&packet)?;
+                    let data = self.plugin.as_mut().and_then(|plugin| plugin(&request));
+
+                    let response = LoginPluginResponse {
+                        message_id: request.message_id,
+                        data: data.map(Into::into),
+                    };
+                    framed.send(response).await?;
+                }
+                CookieRequest::ID => {
+                    let CookieRequest { key } = read(&packet)?;
+                    let payload = self.cookies.get(&key);
+
+                    framed.send(CookieResponse { key, payload }).await?;
+                }
+                LoginSuccess::ID => {
+                    let success: LoginSuccess = expect(&packet, version)?;
+                    let state = next_state(version);
+
+                    if state == State::Configuration {
+                        framed.send(LoginAcknowledged {}).await?;
+                    }
+
+                    let profile = GameProfile {
+                        uuid: success.uuid,
+                        username: success.username,
+                        properties: success.properties,
+                    };
+
+                    return Ok(Connection::new(framed, version, state, profile));
+                }
+                _ => return Err(unexpected(State::Login, &packet)),
+            }
+        }
+    }
+}
+
+/// Proxy forwarding the info of players to the server
+#[derive(Debug, Clone, Default)]
+enum Forwarding {
+    #[default]
+    None,
+    /// modern forwarding with a secret
+    Velocity(Bytes),
+    /// legacy forwarding, with the allowed BungeeGuard tokens if any
+    #[cfg(feature = "serde_json")]
+    Bungee(Vec<String>),
+}
+
+/// Maximum size of the packets received by servers by default, as vanilla:
+/// the frame length is a VarInt of at most 3 bytes
+pub const MAX_PACKET_SIZE: usize = 2 * 1024 * 1024;
+
+/// Server side of the offline mode login
+#[derive(Debug, Clone)]
+pub struct ServerLogin {
+    compression: Option<usize>,
+    max_size: usize,
+    forwarding: Forwarding,
+    cookies: Vec<Identifier>,
+}
+
+impl Default for ServerLogin {
+    fn default() -> Self {
+        Self {
+            compression: None,
+            max_size: MAX_PACKET_SIZE,
+            forwarding: Forwarding::None,
+            cookies: vec![],
+        }
+    }
+}
+
+impl ServerLogin {
+    /// login without compression
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum size of the packets received from the client, during the
+    /// login and afterwards. [`MAX_PACKET_SIZE`] by default
+    pub fn max_size(self, max_size: usize) -> Self {
+        Self { max_size, ..self }
+    }
+
+    /// compresses packets bigger than `threshold`, `None` to disable compression
+    pub fn compression(self, threshold: Option<usize>) -> Self {
+        Self {
+            compression: threshold,
+            ..self
+        }
+    }
+
+    /// Expects players to come from a Velocity proxy using modern
+    /// forwarding with `secret`, see [`velocity`]
+    pub fn velocity(self, secret: impl Into<Bytes>) -> Self {
+        Self {
+            forwarding: Forwarding::Velocity(secret.into()),
+            ..self
+        }
+    }
+
+    /// Expects players to come from a BungeeCord proxy with IP forwarding,
+    /// see [`bungee`]. If `tokens` isn't empty, the proxy must also send one
+    /// of them with BungeeGuard
+    #[cfg(feature = "serde_json")]
+    pub fn bungee<T: Into<String>>(self, tokens: impl IntoIterator<Item = T>) -> Self {
+        let tokens = tokens.into_iter().map(Into::into).collect();
+
+        Self {
+            forwarding: Forwarding::Bungee(tokens),
+            ..self
+        }
+    }
+
+    /// Requests the cookie stored under `key` before the login success
+    /// (1.20.5+), see [`Connection::get_cookie`]
+    pub fn cookie(mut self, key: Identifier) -> Self {
+        self.cookies.push(key);
+        self
+    }
+
+    /// Accepts the login of the client on the other side of `stream`,
+    /// starting from the handshake. Players get their offline uuid,
+    /// or the profile and address forwarded by the proxy
+    pub async fn accept<S>(self, stream: S) -> Result<Connection<S>, LoginError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let codec = SwitchCodec::default().max_size(self.max_size);
+        let mut framed = Framed::new(stream, codec);
+        let (handshake, start) = start(&mut framed).await?;
+        let version = handshake.protocol_version;
+
+        let forwarded = match &self.forwarding {
+            Forwarding::None => Ok(None),
+            Forwarding::Velocity(secret) => velocity::forward(&mut framed, secret)
+                .await
+                .map(|info| Some((info.profile, info.address))),
+            #[cfg(feature = "serde_json")]
+            Forwarding::Bungee(tokens) => {
+                bungee::forwarded(&handshake.server_address, start.username.clone(), tokens)
+                    .map(Some)
+            }
+        };
+
+        let (profile, address) = match forwarded {
+            Ok(Some((profile, address))) => (profile, Some(address)),
+            Ok(None) => {
+                let profile = GameProfile {
+                    uuid: offline_uuid(&start.username),
+                    username: start.username,
+                    properties: vec![],
+                };
+
+                (profile, None)
+            }
+            Err(err) => {
+                let reason = match self.forwarding {
+                    Forwarding::Velocity(_) => {
+                        "\"This server requires you to connect with Velocity.\""
+                    }
+                    _ => "\"If you wish to use IP forwarding, please enable it in your BungeeCord config as well!\"",
+                };
+
+                // the client may be gone already
+                let reason = Str::from_static(reason);
+                let _ = send(&mut framed, Disconnect { reason }).await;
+
+                return Err(err);
+            }
+        };
+
+        let cookies = request_cookies(&mut framed, version, &self.cookies).await?;
+        let connection = finish(framed, version, self.compression, profile).await?;
+
+        Ok(connection
+            .address(address)
+            .transferred(handshake.intent == Intent::Transfer)
+            .cookies(cookies))
+    }
+}
+
+/// Receives the handshake and the login start
+async fn start<S, C>(framed: &mut Framed<S, C>) -> Result<(Handshake, LoginStart), LoginError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: LoginCodec,
+{
+    let packet = receive(framed).await?;
+    let handshake = match packet.is::<Handshake>() {
+        true => read::<Handshake>(&packet)?,
+        false => return Err(unexpected(State::Handshaking, &packet)),
+    };
+
+    if handshake.intent == Intent::Status {
+        return Err(LoginError::Status);
+    }
+
+    let start = expect(&receive(framed).await?, handshake.protocol_version)?;
+
+    Ok((handshake, start))
+}
+
+/// Requests the cookies stored under `keys`, when the version supports them
+async fn request_cookies<S, C>(
+    framed: &mut Framed<S, C>,
+    version: ProtocolVersion,
+    keys: &[Identifier],
+) -> Result<HashMap<Identifier, Bytes>, LoginError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: LoginCodec,
+{
+    let mut cookies = HashMap::new();
+    if version < ProtocolVersion::V1_20_5 {
+        return Ok(cookies);
+    }
+
+    for key in keys {
+        send(framed, CookieRequest { key: key.clone() }).await?;
+    }
+
+    for _ in keys {
+        let packet = receive(framed).await?;
+        if !packet.is::<CookieResponse>() {
+            return Err(unexpected(State::Login, &packet));
+        }
+
+        let response: CookieResponse = read(&packet)?;
+        if !keys.contains(&response.key) {
+            return Err(unexpected(State::Login, &packet));
+        }
+
+        if let Some(payload) = response.payload {
+            if payload.len() > cookie::MAX_SIZE {
+                return Err(LoginError::CookieSize(payload.len()));
+            }
+
+            cookies.insert(response.key, payload);
+        }
+    }
+
+    Ok(cookies)
+}
+
+/// Enables compression, then sends the login success
+/// and waits for its acknowledgement if needed
+async fn finish<S, C>(
+    mut framed: Framed<S, C>,
+    version: ProtocolVersion,
+    compression: Option<usize>,
+    profile: GameProfile,
+) -> Result<Connection<S, C>, LoginError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: LoginCodec,
+{
+    if let Some(threshold) = compression {
+        let threshold = VarInt(threshold.try_into().unwrap_or(i32::MAX));
+        send(&mut framed, SetCompression { threshold }).await?;
+        framed.codec_mut().framing().set_threshold(compression);
+    }
+
+    let success = LoginSuccess::from(&profile);
+    send(&mut framed, Versioned::new(success, version)).await?;
+
+    let state = next_state(version);
+    if state == State::Configuration {
+        let packet = receive(&mut framed).await?;
+        if !packet.is::<LoginAcknowledged>() {
+            return Err(unexpected(State::Login, &packet));
+        }
+    }
+
+    Ok(Connection::new(framed, version, state, profile))
+}
@@ -0,0 +1,163 @@
+use std::net::IpAddr;
+
+use serde_json::{json, Value};
+
+use super::{ForwardingError, LoginError};
+use crate::{
+    connection::GameProfile,
+    encoding::str::Str,
+    packet::login::{parse_uuid, Property},
+    DeError,
+};
+
+#[cfg(test)]
+mod test;
+
+/// name of the property carrying the BungeeGuard token
+pub const TOKEN_PROPERTY: &str = "bungeeguard-token";
+
+/// Player info forwarded by BungeeCord in the server address of the handshake.
+///
+/// The proxy appends the address, the uuid and the properties of the
+/// player to the address, separated by null characters:
+/// `host\0address\0uuid[\0properties]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BungeeForwarding {
+    /// address the player used to connect to the proxy
+    pub host: String,
+    /// address the player connected from
+    pub address: IpAddr,
+    /// uuid of the player
+    pub uuid: u128,
+    /// profile properties, including the BungeeGuard token if any
+    pub properties: Vec<Property>,
+}
+
+fn parse_property(value: &Value) -> Option<Property> {
+    let signature = match value.get("signature") {
+        Some(signature) => Some(signature.as_str()?.to_string().into()),
+        None => None,
+    };
+
+    Some(Property {
+        name: value["name"].as_str()?.to_string().into(),
+        value: value["value"].as_str()?.to_string().into(),
+        signature,
+    })
+}
+
+impl BungeeForwarding {
+    /// Parses the server address of a handshake sent by the proxy,
+    /// failing with [`ForwardingError::NotForwarded`] if it isn't extended
+    pub fn parse(server_address: &str) -> Result<Self, ForwardingError> {
+        let parts: Vec<&str> = server_address.split('\0').collect();
+
+        let (host, address, uuid, properties) = match parts[..] {
+            [host, address, uuid] => (host, address, uuid, None),
+            [host, address, uuid, properties] => (host, address, uuid, Some(properties)),
+            _ => return Err(ForwardingError::NotForwarded),
+        };
+
+        let properties = match properties {
+            Some(properties) => serde_json::from_str::<Value>(properties)
+                .ok()
+                .as_ref()
+                .and_then(Value::as_array)
+                .and_then(|properties| properties.iter().map(parse_property).collect())
+                .ok_or(DeError::InvalidData)?,
+            None => vec![],
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            address: address.parse().map_err(|_| DeError::InvalidData)?,
+            uuid: parse_uuid(uuid).ok_or(DeError::InvalidData)?,
+            properties,
+        })
+    }
+
+    /// Server address to send in the handshake to the server
+    pub fn to_server_address(&self) -> String {
+        let mut address = format!("{}\0{}\0{:032x}", self.host, self.address, self.uuid);
+
+        if !self.properties.is_empty() {
+            let properties: Vec<Value> = self
+                .properties
+                .iter()
+                .map(|property| {
+                    let mut value = json!({ "name": &*property.name, "value": &*property.value });
+                    if let Some(signature) = &property.signature {
+                        value["signature"] = json!(&**signature);
+                    }
+
+                    value
+                })
+                .collect();
+
+            address.push('\0');
+            address.push_str(&Value::Array(properties).to_string());
+        }
+
+        address
+    }
+
+    /// Adds the BungeeGuard `token`, proving to the server that
+    /// the handshake comes from the proxy
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.properties.push(Property {
+            name: Str::from_static(TOKEN_PROPERTY),
+            value: token.into().into(),
+            signature: Some(Str::from_static("")),
+        });
+
+        self
+    }
+
+    /// BungeeGuard token, `None` if it's missing or sent more than once
+    pub fn get_token(&self) -> Option<&str> {
+        let mut tokens = self
+            .properties
+            .iter()
+            .filter(|property| &*property.name == TOKEN_PROPERTY);
+
+        match (tokens.next(), tokens.next()) {
+            (Some(token), None) => Some(&token.value),
+            _ => None,
+        }
+    }
+
+    /// Profile of the player named `username`, without the BungeeGuard token
+    pub fn profile(&self, username: Str) -> GameProfile {
+        let properties = self
+            .properties
+            .iter()
+            .filter(|property| &*property.name != TOKEN_PROPERTY)
+            .cloned()
+            .collect();
+
+        GameProfile {
+            uuid: self.uuid,
+            username,
+            properties,
+        }
+    }
+}
+
+/// Reads the forwarded info of the player named `username`, checking the
+/// BungeeGuard token if some `tokens` are allowed
+pub(super) fn forwarded(
+    server_address: &str,
+    username: Str,
+    tokens: &[String],
+) -> Result<(GameProfile, IpAddr), LoginError> {
+    let forwarding = BungeeForwarding::parse(server_address)?;
+
+    if !tokens.is_empty() {
+        let token = forwarding.get_token().ok_or(ForwardingError::Token)?;
+        if !tokens.iter().any(|allowed| allowed == token) {
+            return Err(ForwardingError::Token.into());
+        }
+    }
+
+    Ok((forwarding.profile(username), forwarding.address))
+}
@@ -0,0 +1,156 @@
+use tokio::io::duplex;
+
+use super::{BungeeForwarding, ForwardingError, TOKEN_PROPERTY};
+use crate::{
+    encoding::str::Str,
+    login::{ClientLogin, LoginError, ServerLogin},
+    packet::login::Property,
+    protocol::ProtocolVersion as V,
+    DeError,
+};
+
+fn forwarding() -> BungeeForwarding {
+    BungeeForwarding {
+        host: "play.example.com".into(),
+        address: "203.0.113.7".parse().unwrap(),
+        uuid: 0x069a79f444e94726a5befca90e38aaf5,
+        properties: vec![Property {
+            name: Str::from_static("textures"),
+            value: Str::from_static("e30="),
+            signature: Some(Str::from_static("c2lnbmF0dXJl")),
+        }],
+    }
+}
+
+#[test]
+fn server_address() {
+    let forwarding = forwarding();
+    let address = forwarding.to_server_address();
+    assert_eq!(
+        address,
+        [
+            "play.example.com",
+            "203.0.113.7",
+            "069a79f444e94726a5befca90e38aaf5",
+            r#"[{"name":"textures","signature":"c2lnbmF0dXJl","value":"e30="}]"#,
+        ]
+        .join("\0")
+    );
+    assert_eq!(BungeeForwarding::parse(&address).unwrap(), forwarding);
+
+    let unsigned = BungeeForwarding {
+        address: "2001:db8::1".parse().unwrap(),
+        properties: vec![],
+        ..forwarding
+    };
+    let address = unsigned.to_server_address();
+    assert_eq!(address.split('\0').count(), 3);
+    assert_eq!(BungeeForwarding::parse(&address).unwrap(), unsigned);
+
+    // uuids are also accepted with hyphens
+    let parsed = BungeeForwarding::parse(
+        &[
+            "localhost",
+            "127.0.0.1",
+            "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+            r#"[{"name":"textures","value":"e30="}]"#,
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    assert_eq!(parsed.uuid, unsigned.uuid);
+    assert_eq!(parsed.properties[0].signature, None);
+}
+
+#[test]
+fn invalid() {
+    assert!(matches!(
+        BungeeForwarding::parse("localhost"),
+        Err(ForwardingError::NotForwarded)
+    ));
+
+    let uuid = "069a79f444e94726a5befca90e38aaf5";
+    for parts in [
+        ["localhost", "not an ip", uuid].as_slice(),
+        &["localhost", "127.0.0.1", "069a79f4"],
+        &["localhost", "127.0.0.1", uuid, "{}"],
+        &["localhost", "127.0.0.1", uuid, r#"[{"name":1}]"#],
+    ] {
+        let address = parts.join("\0");
+        assert!(matches!(
+            BungeeForwarding::parse(&address),
+            Err(ForwardingError::Data(DeError::InvalidData))
+        ));
+    }
+}
+
+#[test]
+fn token() {
+    let forwarding = forwarding().token("secret");
+    assert_eq!(forwarding.get_token(), Some("secret"));
+
+    let parsed = BungeeForwarding::parse(&forwarding.to_server_address()).unwrap();
+    assert_eq!(parsed.get_token(), Some("secret"));
+
+    let profile = parsed.profile(Str::from_static("Notch"));
+    assert!(profile
+        .properties
+        .iter()
+        .all(|property| &*property.name != TOKEN_PROPERTY));
+    assert_eq!(profile.properties.len(), 1);
+
+    // a token sent twice is ambiguous
+    assert_eq!(forwarding.token("other").get_token(), None);
+}
+
+#[tokio::test]
+async fn login() {
+    for (forwarding, tokens) in [
+        (forwarding(), vec![]),
+        (forwarding().token("secret"), vec!["secret"]),
+    ] {
+        let (client, server) = duplex(4096);
+
+        let client = ClientLogin::new("Notch", V::V1_21_5)
+            .address(forwarding.to_server_address(), 25565)
+            .login(client);
+        let server = ServerLogin::new().bungee(tokens).accept(server);
+
+        let (client, server) = futures::join!(client, server);
+        let (client, server) = (client.unwrap(), server.unwrap());
+
+        assert_eq!(
+            server.profile(),
+            &forwarding.profile(Str::from_static("Notch"))
+        );
+        assert_eq!(server.get_address(), Some(forwarding.address));
+        assert_eq!(client.profile().uuid, forwarding.uuid);
+    }
+}
+
+#[tokio::test]
+async fn rejected() {
+    for (address, expected) in [
+        ("localhost".to_string(), ForwardingError::NotForwarded),
+        (forwarding().to_server_address(), ForwardingError::Token),
+        (
+            forwarding().token("wrong").to_server_address(),
+            ForwardingError::Token,
+        ),
+    ] {
+        let (client, server) = duplex(4096);
+
+        let client = ClientLogin::new("Notch", V::V1_21_5)
+            .address(address, 25565)
+            .login(client);
+        let server = ServerLogin::new().bungee(["secret"]).accept(server);
+
+        let (client, server) = futures::join!(client, server);
+
+        assert!(matches!(client, Err(LoginError::Disconnected(_))));
+        let Err(LoginError::Forwarding(err)) = server else {
+            panic!("expected a forwarding error")
+        };
+        assert_eq!(err.to_string(), expected.to_string());
+    }
+}
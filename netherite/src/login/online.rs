@@ -0,0 +1,213 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::Framed;
+
+use super::{expect, finish, receive, request_cookies, send, start, LoginError, MAX_PACKET_SIZE};
+use crate::{
+    codec::{dual::SwitchCodec, encrypted::Encrypted},
+    connection::{Connection, GameProfile},
+    crypto::{random_bytes, server_hash, KeyPair},
+    encoding::{identifier::Identifier, str::Str, versioned::Versioned},
+    packet::{
+        handshake::Intent,
+        login::{Disconnect, EncryptionRequest, EncryptionResponse, Verification},
+    },
+};
+
+/// HTTP client of the session server
+#[cfg(feature = "http")]
+pub mod session;
+#[cfg(test)]
+mod test;
+
+/// Error querying the session server
+#[derive(Debug, Error)]
+pub enum AuthError {
+    /// Connection to the session server failed
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// HTTP request to the session server failed or timed out
+    #[cfg(feature = "http")]
+    #[error("http: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// Session server answered with an unexpected HTTP status
+    #[error("session server answered with status {0}")]
+    Status(u16),
+
+    /// Answer of the session server couldn't be parsed, or is too large
+    #[error("invalid response from the session server")]
+    InvalidResponse,
+}
+
+/// Checks with the session server that players logging in online
+/// mode joined the server with their account, like the `hasJoined`
+/// endpoint of Mojang's session server
+pub trait Authenticator: Send + Sync {
+    /// Profile of `username` if the player joined the server
+    /// identified by `server_hash`, `None` otherwise
+    fn has_joined<'a>(
+        &'a self,
+        username: &'a str,
+        server_hash: &'a str,
+    ) -> BoxFuture<'a, Result<Option<GameProfile>, AuthError>>;
+}
+
+/// In-memory [`Authenticator`], for tests. Players are authenticated
+/// once they "joined" a server with [`MockAuthenticator::join`], like
+/// clients do with the real session server
+#[derive(Debug, Clone, Default)]
+pub struct MockAuthenticator {
+    sessions: Arc<Mutex<HashMap<(String, String), GameProfile>>>,
+}
+
+impl MockAuthenticator {
+    /// authenticator without any session
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Joins the server identified by `server_hash` as `profile`
+    pub fn join(&self, profile: GameProfile, server_hash: impl Into<String>) {
+        let key = (profile.username.to_string(), server_hash.into());
+        self.sessions.lock().unwrap().insert(key, profile);
+    }
+}
+
+impl Authenticator for MockAuthenticator {
+    fn has_joined<'a>(
+        &'a self,
+        username: &'a str,
+        server_hash: &'a str,
+    ) -> BoxFuture<'a, Result<Option<GameProfile>, AuthError>> {
+        let key = (username.to_string(), server_hash.to_string());
+        let profile = self.sessions.lock().unwrap().get(&key).cloned();
+
+        Box::pin(async move { Ok(profile) })
+    }
+}
+
+/// Server side of the online mode login: the connection gets
+/// encrypted and players are authenticated by the session server
+pub struct OnlineLogin<A> {
+    key: Arc<KeyPair>,
+    authenticator: Arc<A>,
+    compression: Option<usize>,
+    max_size: usize,
+    cookies: Vec<Identifier>,
+}
+
+impl<A> Clone for OnlineLogin<A> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            authenticator: self.authenticator.clone(),
+            compression: self.compression,
+            max_size: self.max_size,
+            cookies: self.cookies.clone(),
+        }
+    }
+}
+
+impl<A: Authenticator> OnlineLogin<A> {
+    /// login encrypting with `key` and checking players with `authenticator`
+    pub fn new(key: KeyPair, authenticator: A) -> Self {
+        Self {
+            key: Arc::new(key),
+            authenticator: Arc::new(authenticator),
+            compression: None,
+            max_size: MAX_PACKET_SIZE,
+            cookies: vec![],
+        }
+    }
+
+    /// compresses packets bigger than `threshold`, `None` to disable compression
+    pub fn compression(self, threshold: Option<usize>) -> Self {
+        Self {
+            compression: threshold,
+            ..self
+        }
+    }
+
+    /// Maximum size of the packets received from the client, during the
+    /// login and afterwards. [`MAX_PACKET_SIZE`] by default
+    pub fn max_size(self, max_size: usize) -> Self {
+        Self { max_size, ..self }
+    }
+
+    /// Requests the cookie stored under `key` after enabling encryption
+    /// (1.20.5+), see [`Connection::get_cookie`]
+    pub fn cookie(mut self, key: Identifier) -> Self {
+        self.cookies.push(key);
+        self
+    }
+
+    /// Accepts the login of the client on the other side of `stream`,
+    /// starting from the handshake. Players get the profile returned
+    /// by the authenticator, and are kicked if they're not authenticated
+    pub async fn accept<S>(
+        self,
+        stream: S,
+    ) -> Result<Connection<S, Encrypted<SwitchCodec>>, LoginError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let codec = SwitchCodec::default().max_size(self.max_size);
+        let mut framed = Framed::new(stream, Encrypted::new(codec));
+        let (handshake, start) = start(&mut framed).await?;
+        let version = handshake.protocol_version;
+
+        let mut verify_token = [0; 4];
+        random_bytes(&mut verify_token)?;
+
+        let request = EncryptionRequest {
+            server_id: Str::from_static(""),
+            public_key: self.key.public_key_der().clone(),
+            verify_token: Bytes::copy_from_slice(&verify_token),
+            should_authenticate: true,
+        };
+        send(&mut framed, Versioned::new(request, version)).await?;
+
+        let response: EncryptionResponse = expect(&receive(&mut framed).await?, version)?;
+
+        let Verification::Token(token) = response.verification else {
+            return Err(LoginError::Verification);
+        };
+        // invalid data decrypts to random bytes, which don't match the token
+        if self.key.decrypt(&token)? != verify_token {
+            return Err(LoginError::Verification);
+        }
+
+        let secret: [u8; 16] = self.key.decrypt(&response.shared_secret)?;
+        framed.codec_mut().enable(&secret);
+
+        let hash = server_hash("", &secret, self.key.public_key_der());
+        let profile = self
+            .authenticator
+            .has_joined(&start.username, &hash)
+            .await?;
+
+        let Some(profile) = profile else {
+            let reason = "{\"translate\":\"multiplayer.disconnect.unverified_username\"}";
+            let reason = Str::from_static(reason);
+            send(&mut framed, Disconnect { reason }).await?;
+
+            return Err(LoginError::Unauthenticated);
+        };
+
+        let cookies = request_cookies(&mut framed, version, &self.cookies).await?;
+        let connection = finish(framed, version, self.compression, profile).await?;
+
+        Ok(connection
+            .transferred(handshake.intent == Intent::Transfer)
+            .cookies(cookies))
+    }
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use futures::{future::BoxFuture, FutureExt};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+
+use super::{AuthError, Authenticator};
+use crate::{
+    connection::GameProfile,
+    packet::login::{parse_uuid, Property},
+};
+
+/// session endpoints of Mojang's session server
+pub const MOJANG_URL: &str = "https://sessionserver.mojang.com/session/minecraft";
+
+/// Maximum size of the responses of the session server.
+/// A profile with its textures takes a few kilobytes
+pub const MAX_RESPONSE_SIZE: usize = 64 * 1024;
+
+/// [`Authenticator`] querying a session server over HTTPS
+#[derive(Debug, Clone)]
+pub struct SessionServer {
+    client: Client,
+    url: String,
+    timeout: Duration,
+}
+
+impl Default for SessionServer {
+    fn default() -> Self {
+        Self::new(MOJANG_URL)
+    }
+}
+
+impl SessionServer {
+    /// Session server whose `hasJoined` endpoint is under `url`,
+    /// like [`MOJANG_URL`]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Mojang's session server
+    pub fn mojang() -> Self {
+        Self::default()
+    }
+
+    /// Sends the requests with `client`, to share its connection pool
+    /// or configure it
+    pub fn client(self, client: Client) -> Self {
+        Self { client, ..self }
+    }
+
+    /// maximum duration of a query, 10 seconds by default
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    async fn query(
+        &self,
+        username: &str,
+        server_hash: &str,
+    ) -> Result<Option<GameProfile>, AuthError> {
+        let url = format!("{}/hasJoined", self.url.trim_end_matches('/'));
+
+        let mut response = self
+            .client
+            .get(url)
+            .query(&[("username", username), ("serverId", server_hash)])
+            .header("Accept", "application/json")
+            .timeout(self.timeout)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => {}
+            // the player didn't join
+            StatusCode::NO_CONTENT => return Ok(None),
+            status => return Err(AuthError::Status(status.as_u16())),
+        }
+
+        let mut body = vec![];
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > MAX_RESPONSE_SIZE {
+                return Err(AuthError::InvalidResponse);
+            }
+
+            body.extend_from_slice(&chunk);
+        }
+
+        parse_profile(&body).map(Some)
+    }
+}
+
+impl Authenticator for SessionServer {
+    fn has_joined<'a>(
+        &'a self,
+        username: &'a str,
+        server_hash: &'a str,
+    ) -> BoxFuture<'a, Result<Option<GameProfile>, AuthError>> {
+        self.query(username, server_hash).boxed()
+    }
+}
+
+fn parse_profile(body: &[u8]) -> Result<GameProfile, AuthError> {
+    let parse = || -> Option<GameProfile> {
+        let value: Value = serde_json::from_slice(body).ok()?;
+
+        let properties = match value.get("properties") {
+            Some(properties) => properties
+                .as_array()?
+                .iter()
+                .map(|property| {
+                    Some(Property {
+                        name: property["name"].as_str()?.to_string().into(),
+                        value: property["value"].as_str()?.to_string().into(),
+                        signature: match property.get("signature") {
+                            Some(signature) => Some(signature.as_str()?.to_string().into()),
+                            None => None,
+                        },
+                    })
+                })
+                .collect::<Option<_>>()?,
+            None => vec![],
+        };
+
+        Some(GameProfile {
+            uuid: parse_uuid(value["id"].as_str()?)?,
+            username: value["name"].as_str()?.to_string().into(),
+            properties,
+        })
+    };
+
+    parse().ok_or(AuthError::InvalidResponse)
+}
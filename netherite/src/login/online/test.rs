@@ -0,0 +1,232 @@
+use futures::{SinkExt, StreamExt};
+use tokio::io::{duplex, DuplexStream};
+use tokio_util::codec::Framed;
+
+use super::{MockAuthenticator, OnlineLogin};
+use crate::{
+    codec::{dual::SwitchCodec, encrypted::Encrypted},
+    connection::GameProfile,
+    crypto::{server_hash, KeyPair, PublicKey},
+    encoding::{str::Str, versioned::Versioned},
+    login::LoginError,
+    packet::{
+        handshake::{Handshake, Intent},
+        login::{
+            Disconnect, EncryptionRequest, EncryptionResponse, LoginAcknowledged, LoginStart,
+            LoginSuccess, Property, SetCompression, Verification,
+        },
+    },
+    protocol::{ProtocolVersion as V, State},
+};
+
+fn profile() -> GameProfile {
+    GameProfile {
+        uuid: 0x069a79f444e94726a5befca90e38aaf5,
+        username: Str::from_static("Notch"),
+        properties: vec![Property {
+            name: Str::from_static("textures"),
+            value: Str::from_static("e30="),
+            signature: Some(Str::from_static("c2lnbmF0dXJl")),
+        }],
+    }
+}
+
+/// Client side of the online login, joining through `authenticator`
+/// if `join`. Returns the login success with the encrypted stream,
+/// or the disconnection reason
+async fn client(
+    stream: DuplexStream,
+    version: V,
+    authenticator: &MockAuthenticator,
+    join: bool,
+) -> Result<(LoginSuccess, Framed<DuplexStream, Encrypted<SwitchCodec>>), Str> {
+    let mut framed = Framed::new(stream, Encrypted::new(SwitchCodec::default()));
+
+    let handshake = Handshake {
+        protocol_version: version,
+        server_address: Str::from_static("localhost"),
+        server_port: 25565,
+        intent: Intent::Login,
+    };
+    framed.send(handshake).await.unwrap();
+
+    let start = LoginStart {
+        username: Str::from_static("Notch"),
+        key: None,
+        uuid: Some(profile().uuid),
+    };
+    framed.send(Versioned::new(start, version)).await.unwrap();
+
+    let packet = framed.next().await.unwrap().unwrap();
+    let request: EncryptionRequest = packet.deserialize_versioned(version).unwrap().unwrap();
+    let key = PublicKey::from_der(&request.public_key).unwrap();
+
+    let secret = [0x42; 16];
+    if join {
+        authenticator.join(profile(), server_hash("", &secret, &request.public_key));
+    }
+
+    let response = EncryptionResponse {
+        shared_secret: key.encrypt(&secret).unwrap().into(),
+        verification: Verification::Token(key.encrypt(&request.verify_token).unwrap().into()),
+    };
+    framed
+        .send(Versioned::new(response, version))
+        .await
+        .unwrap();
+    framed.codec_mut().enable(&secret);
+
+    loop {
+        let packet = framed.next().await.unwrap().unwrap();
+
+        if let Some(disconnect) = packet.deserialize::<Disconnect>() {
+            return Err(disconnect.unwrap().reason);
+        }
+
+        if let Some(compression) = packet.deserialize::<SetCompression>() {
+            let threshold = compression.unwrap().threshold.0;
+            framed
+                .codec_mut()
+                .inner_mut()
+                .set_threshold(Some(threshold as usize));
+            continue;
+        }
+
+        let success = packet.deserialize_versioned(version).unwrap().unwrap();
+        if version >= V::V1_20_2 {
+            framed.send(LoginAcknowledged {}).await.unwrap();
+        }
+
+        return Ok((success, framed));
+    }
+}
+
+#[tokio::test]
+async fn online() {
+    let key = KeyPair::generate().unwrap();
+    let authenticator = MockAuthenticator::new();
+    let login = OnlineLogin::new(key, authenticator.clone()).compression(Some(64));
+
+    for version in [V::V1_21_5, V::V1_19_4, V::V1_8] {
+        let (client_stream, server_stream) = duplex(4096);
+
+        let client = client(client_stream, version, &authenticator, true);
+        let server = login.clone().accept(server_stream);
+
+        let (client, connection) = futures::join!(client, server);
+        let ((success, mut client), mut connection) = (client.unwrap(), connection.unwrap());
+
+        assert_eq!(connection.profile(), &profile());
+        assert_eq!(connection.compression(), Some(64));
+        assert_eq!(success.uuid, profile().uuid);
+        assert_eq!(
+            success.properties,
+            match version >= V::V1_19 {
+                true => profile().properties,
+                false => vec![],
+            }
+        );
+
+        let state = match version >= V::V1_20_2 {
+            true => State::Configuration,
+            false => State::Play,
+        };
+        assert_eq!(connection.state(), state);
+
+        // still encrypted after the login
+        let disconnect = Disconnect {
+            reason: Str::from_static("\"bye\""),
+        };
+        connection.send(disconnect.clone()).await.unwrap();
+
+        let packet = client.next().await.unwrap().unwrap();
+        assert_eq!(
+            packet.deserialize::<Disconnect>().unwrap().unwrap(),
+            disconnect
+        );
+    }
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    let key = KeyPair::generate().unwrap();
+    let authenticator = MockAuthenticator::new();
+    let login = OnlineLogin::new(key, authenticator.clone());
+
+    let (client_stream, server_stream) = duplex(4096);
+
+    let client = client(client_stream, V::V1_21_5, &authenticator, false);
+    let server = login.accept(server_stream);
+
+    let (client, result) = futures::join!(client, server);
+    let Err(reason) = client else {
+        panic!("expected disconnection")
+    };
+
+    assert!(reason.contains("unverified_username"));
+    assert!(matches!(result, Err(LoginError::Unauthenticated)));
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn session_server() {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::{session::SessionServer, AuthError, Authenticator};
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = async {
+        let mut requests = vec![];
+
+        let oversized = format!("HTTP/1.1 200 OK\r\n\r\n{}", " ".repeat(128 * 1024));
+
+        for response in [
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n\
+            {\"id\":\"069a79f444e94726a5befca90e38aaf5\",\"name\":\"Notch\",\
+            \"properties\":[{\"name\":\"textures\",\"value\":\"e30=\",\
+            \"signature\":\"c2lnbmF0dXJl\"}]}",
+            "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 500 Internal Server Error\r\nConnection: close\r\n\r\n",
+            &oversized,
+        ] {
+            let (mut stream, _) = listener.accept().await.unwrap();
+
+            let mut request = vec![0; 1024];
+            let len = stream.read(&mut request).await.unwrap();
+            requests.push(String::from_utf8_lossy(&request[..len]).into_owned());
+
+            // the client stops reading oversized responses
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+
+        requests
+    };
+
+    let client = async {
+        let session = SessionServer::new(format!("http://127.0.0.1:{port}/session/minecraft"));
+
+        let joined = session.has_joined("Notch", "-7c9d5b").await;
+        let absent = session.has_joined("jeb_", "1a2b").await;
+        let failed = session.has_joined("a b&c", "1a2b").await;
+        let oversized = session.has_joined("Notch", "1a2b").await;
+
+        (joined, absent, failed, oversized)
+    };
+
+    let (requests, (joined, absent, failed, oversized)) = futures::join!(server, client);
+
+    assert_eq!(joined.unwrap(), Some(profile()));
+    assert_eq!(absent.unwrap(), None);
+    assert!(matches!(failed, Err(AuthError::Status(500))));
+    assert!(matches!(oversized, Err(AuthError::InvalidResponse)));
+
+    assert!(requests[0].starts_with(
+        "GET /session/minecraft/hasJoined?username=Notch&serverId=-7c9d5b HTTP/1.1\r\n"
+    ));
+    assert!(requests[2].contains("username=a+b%26c&"));
+}
@@ -0,0 +1,19 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+fn mac(key: &[u8], data: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any size");
+    mac.update(data);
+    mac
+}
+
+/// HMAC-SHA256 of `data` with `key`
+pub fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    mac(key, data).finalize().into_bytes().into()
+}
+
+/// Checks `signature` against the HMAC-SHA256 of `data`, in constant
+/// time not to leak how much of a forged signature is right
+pub fn verify(key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+    mac(key, data).verify_slice(signature).is_ok()
+}
@@ -0,0 +1,182 @@
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use tokio::io::{duplex, AsyncWriteExt};
+use tokio_util::codec::Framed;
+
+use super::{offline_uuid, ClientLogin, LoginError, ServerLogin};
+use crate::{
+    codec::{dual::SwitchCodec, CodecError},
+    encoding::{str::Str, varint::VarInt, versioned::Versioned},
+    packet::{
+        handshake::{Handshake, Intent},
+        login::{
+            Disconnect, LoginAcknowledged, LoginPluginRequest, LoginPluginResponse, LoginSuccess,
+        },
+    },
+    protocol::{ProtocolVersion as V, State},
+};
+
+#[test]
+fn uuid() {
+    assert_eq!(offline_uuid("Notch"), 0xb50ad385829d3141a2167e7d7539ba7f);
+}
+
+async fn login(version: V, compression: Option<usize>) {
+    let (client, server) = duplex(4096);
+
+    let client = ClientLogin::new("Notch", version).login(client);
+    let server = ServerLogin::new().compression(compression).accept(server);
+
+    let (client, server) = futures::join!(client, server);
+    let (mut client, mut server) = (client.unwrap(), server.unwrap());
+
+    assert_eq!(client.profile(), server.profile());
+    assert_eq!(client.profile().uuid, offline_uuid("Notch"));
+    assert_eq!(client.state(), server.state());
+    assert_eq!(client.compression(), compression);
+    assert_eq!(server.compression(), compression);
+
+    // packets after the login use the same framing on both sides
+    let disconnect = Disconnect {
+        reason: Str::from_static("\"bye\""),
+    };
+    server.send(disconnect.clone()).await.unwrap();
+
+    let packet = client.receive().await.unwrap().unwrap();
+    assert_eq!(
+        packet.deserialize::<Disconnect>().unwrap().unwrap(),
+        disconnect
+    );
+}
+
+#[tokio::test]
+async fn configuration() {
+    login(V::V1_21_5, Some(0)).await;
+}
+
+#[tokio::test]
+async fn play() {
+    login(V::V1_12_2, Some(256)).await;
+    login(V::V1_8, None).await;
+}
+
+#[tokio::test]
+async fn status_intent() {
+    let (client, server) = duplex(4096);
+    let status = Handshake {
+        protocol_version: V::V1_21_5,
+        server_address: Str::from_static("localhost"),
+        server_port: 25565,
+        intent: Intent::Status,
+    };
+
+    let mut client = Framed::new(client, SwitchCodec::default());
+    client.send(status).await.unwrap();
+
+    let result = ServerLogin::new().accept(server).await;
+    assert!(matches!(result, Err(LoginError::Status)));
+}
+
+#[tokio::test]
+async fn oversized() {
+    let (mut client, server) = duplex(4096);
+
+    // frame declaring 2 MiB, the length alone is enough
+    client.write_all(&[0x80, 0x80, 0x80, 0x01]).await.unwrap();
+
+    let result = ServerLogin::new().accept(server).await;
+    assert!(matches!(result, Err(LoginError::Codec(CodecError::Size))));
+
+    let (mut client, server) = duplex(4096);
+    let handshake = Handshake {
+        protocol_version: V::V1_21_5,
+        server_address: Str::from_static("localhost"),
+        server_port: 25565,
+        intent: Intent::Login,
+    };
+    let mut framed = Framed::new(&mut client, SwitchCodec::default());
+    framed.send(handshake).await.unwrap();
+
+    let result = ServerLogin::new().max_size(8).accept(server).await;
+    assert!(matches!(result, Err(LoginError::Codec(CodecError::Size))));
+}
+
+#[tokio::test]
+async fn disconnected() {
+    let (client, server) = duplex(4096);
+
+    let server = async {
+        let mut framed = Framed::new(server, SwitchCodec::default());
+        framed.next().await.unwrap().unwrap(); // handshake
+        framed.next().await.unwrap().unwrap(); // login start
+
+        let reason = Str::from_static("\"whitelist\"");
+        framed.send(Disconnect { reason }).await.unwrap();
+    };
+
+    let client = ClientLogin::new("Notch", V::V1_20_3).login(client);
+    let (result, _) = futures::join!(client, server);
+
+    match result {
+        Err(LoginError::Disconnected(reason)) => assert_eq!(&*reason, "\"whitelist\""),
+        _ => panic!("expected disconnection"),
+    }
+}
+
+#[tokio::test]
+async fn plugin_requests() {
+    let (client, server) = duplex(4096);
+    let version = V::V1_20_3;
+
+    let server = async {
+        let mut framed = Framed::new(server, SwitchCodec::default());
+        framed.next().await.unwrap().unwrap(); // handshake
+        framed.next().await.unwrap().unwrap(); // login start
+
+        let mut responses = vec![];
+        for (id, channel) in [(1, "velocity:player_info"), (2, "unknown:channel")] {
+            let request = LoginPluginRequest {
+                message_id: VarInt(id),
+                channel: Str::from_static(channel),
+                data: Default::default(),
+            };
+            framed.send(request).await.unwrap();
+
+            let response = framed.next().await.unwrap().unwrap();
+            responses.push(
+                response
+                    .deserialize::<LoginPluginResponse>()
+                    .unwrap()
+                    .unwrap(),
+            );
+        }
+
+        let success = LoginSuccess {
+            uuid: 1,
+            username: Str::from_static("Notch"),
+            properties: vec![],
+            strict_error_handling: false,
+        };
+        framed.send(Versioned::new(success, version)).await.unwrap();
+
+        let acknowledged = framed.next().await.unwrap().unwrap();
+        assert!(acknowledged.is::<LoginAcknowledged>());
+
+        responses
+    };
+
+    let client = ClientLogin::new("Notch", version)
+        .plugin(|request| {
+            (&*request.channel == "velocity:player_info").then(|| Bytes::from_static(&[1]))
+        })
+        .login(client);
+
+    let (client, responses) = futures::join!(client, server);
+    let client = client.unwrap();
+
+    assert_eq!(client.state(), State::Configuration);
+    assert_eq!(client.profile().uuid, 1);
+    assert_eq!(responses[0].message_id, VarInt(1));
+    assert_eq!(responses[0].data.as_deref(), Some(&[1][..]));
+    assert_eq!(responses[1].data, None);
+}
@@ -0,0 +1,222 @@
+use std::net::IpAddr;
+
+use bytes::{Buf, BufMut, Bytes};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::Framed;
+
+use super::{read, receive, send, sha256, unexpected, ForwardingError, LoginCodec, LoginError};
+use crate::{
+    connection::GameProfile,
+    encoding::{remaining::Remaining, str::Str, varint::VarInt},
+    packet::login::{LoginPluginRequest, LoginPluginResponse, PlayerKey},
+    protocol::State,
+    DeError, Deserialize, Serialize,
+};
+
+#[cfg(test)]
+mod test;
+
+/// login plugin channel of the forwarding
+pub const CHANNEL: &str = "velocity:player_info";
+
+/// address, uuid, name and properties of the player
+pub const MODERN_DEFAULT: u8 = 1;
+/// adds the player key, for 1.19 clients
+pub const MODERN_WITH_KEY: u8 = 2;
+/// adds the uuid of the key holder, for 1.19.1 and 1.19.2 clients
+pub const MODERN_WITH_KEY_V2: u8 = 3;
+/// same data as [`MODERN_DEFAULT`], with the key sent in the login start (1.19.3+)
+pub const MODERN_LAZY_SESSION: u8 = 4;
+/// latest forwarding version supported
+pub const MAX_VERSION: u8 = MODERN_LAZY_SESSION;
+
+/// size of the HMAC-SHA256 signature leading the forwarded data
+const SIGNATURE: usize = 32;
+
+/// Key of a 1.19 to 1.19.2 player, forwarded with
+/// [`MODERN_WITH_KEY`] and [`MODERN_WITH_KEY_V2`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardedKey {
+    /// player public key
+    pub key: PlayerKey,
+    /// uuid of the player the key was signed for, if it differs
+    /// from the forwarded uuid (only with [`MODERN_WITH_KEY_V2`])
+    pub holder: Option<u128>,
+}
+
+/// Player info forwarded by Velocity to backend servers,
+/// in the response to the `velocity:player_info` request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerInfo {
+    /// forwarding version of the data, see [`MODERN_DEFAULT`]
+    pub version: u8,
+    /// address the player connected to the proxy from
+    pub address: IpAddr,
+    /// profile of the player, authenticated by the proxy
+    pub profile: GameProfile,
+    /// key of the player, with versions [`MODERN_WITH_KEY`] and [`MODERN_WITH_KEY_V2`]
+    pub key: Option<ForwardedKey>,
+}
+
+fn has_key(version: u8) -> bool {
+    (MODERN_WITH_KEY..MODERN_LAZY_SESSION).contains(&version)
+}
+
+impl Serialize for PlayerInfo {
+    fn serialize(&self, mut buf: impl BufMut) {
+        VarInt(self.version as i32).serialize(&mut buf);
+        self.address.to_string().as_str().serialize(&mut buf);
+        self.profile.uuid.serialize(&mut buf);
+        self.profile.username.serialize(&mut buf);
+        self.profile.properties.serialize(&mut buf);
+
+        if let (true, Some(key)) = (has_key(self.version), &self.key) {
+            key.key.serialize(&mut buf);
+
+            if self.version >= MODERN_WITH_KEY_V2 {
+                key.holder.serialize(buf);
+            }
+        }
+    }
+
+    fn size(&self) -> usize {
+        let key = match (has_key(self.version), &self.key) {
+            (true, Some(key)) if self.version >= MODERN_WITH_KEY_V2 => {
+                key.key.size() + key.holder.size()
+            }
+            (true, Some(key)) => key.key.size(),
+            _ => 0,
+        };
+
+        VarInt(self.version as i32).size()
+            + self.address.to_string().as_str().size()
+            + self.profile.uuid.size()
+            + self.profile.username.size()
+            + self.profile.properties.size()
+            + key
+    }
+}
+
+impl Deserialize for PlayerInfo {
+    fn deserialize(mut buffer: impl Buf) -> Result<Self, DeError> {
+        let VarInt(version) = VarInt::deserialize(&mut buffer)?;
+        let version = match u8::try_from(version) {
+            Ok(version @ MODERN_DEFAULT..=MAX_VERSION) => version,
+            _ => return Err(DeError::InvalidData),
+        };
+
+        let address = Str::deserialize(&mut buffer)?;
+        let address = address.parse().map_err(|_| DeError::InvalidData)?;
+
+        let profile = GameProfile {
+            uuid: Deserialize::deserialize(&mut buffer)?,
+            username: Deserialize::deserialize(&mut buffer)?,
+            properties: Deserialize::deserialize(&mut buffer)?,
+        };
+
+        let key = match has_key(version) {
+            true => Some(ForwardedKey {
+                key: Deserialize::deserialize(&mut buffer)?,
+                holder: match version >= MODERN_WITH_KEY_V2 {
+                    true => Deserialize::deserialize(buffer)?,
+                    false => None,
+                },
+            }),
+            false => None,
+        };
+
+        Ok(Self {
+            version,
+            address,
+            profile,
+            key,
+        })
+    }
+}
+
+impl PlayerInfo {
+    /// Data of the response, signed with the forwarding `secret`
+    pub fn sign(&self, secret: &[u8]) -> Bytes {
+        let mut payload = Vec::with_capacity(self.size());
+        self.serialize(&mut payload);
+
+        [&sha256::hmac(secret, &payload)[..], &payload]
+            .concat()
+            .into()
+    }
+
+    /// Checks the signature of `data` with the forwarding `secret`,
+    /// then reads the player info
+    pub fn verify(secret: &[u8], data: &[u8]) -> Result<Self, ForwardingError> {
+        if data.len() < SIGNATURE {
+            return Err(ForwardingError::Signature);
+        }
+
+        let (signature, payload) = data.split_at(SIGNATURE);
+        if !sha256::verify(secret, payload, signature) {
+            return Err(ForwardingError::Signature);
+        }
+
+        let version = VarInt::deserialize(payload)?.0;
+        if !(MODERN_DEFAULT as i32..=MAX_VERSION as i32).contains(&version) {
+            return Err(ForwardingError::Version(version));
+        }
+
+        Ok(Self::deserialize(payload)?)
+    }
+
+    /// Answers the forwarding `request` as a proxy, or `None` for
+    /// other channels. The version is lowered to what the server
+    /// supports, dropping the key if it can't be sent anymore
+    pub fn respond(&self, secret: &[u8], request: &LoginPluginRequest) -> Option<Bytes> {
+        if &*request.channel != CHANNEL {
+            return None;
+        }
+
+        let requested = request.data.first().copied().unwrap_or(MODERN_DEFAULT);
+        let version = match self.version.min(requested) {
+            version if has_key(version) && self.key.is_none() => MODERN_DEFAULT,
+            version => version,
+        };
+
+        Some(
+            Self {
+                version,
+                ..self.clone()
+            }
+            .sign(secret),
+        )
+    }
+}
+
+/// id of the forwarding request, the only query of the login
+const MESSAGE_ID: i32 = 0x76656c;
+
+/// Requests the player info to the proxy and verifies it
+pub(super) async fn forward<S, C>(
+    framed: &mut Framed<S, C>,
+    secret: &[u8],
+) -> Result<PlayerInfo, LoginError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: LoginCodec,
+{
+    let request = LoginPluginRequest {
+        message_id: VarInt(MESSAGE_ID),
+        channel: Str::from_static(CHANNEL),
+        data: Remaining(Bytes::from_static(&[MAX_VERSION])),
+    };
+    send(framed, request).await?;
+
+    let packet = receive(framed).await?;
+    if !packet.is::<LoginPluginResponse>() {
+        return Err(unexpected(State::Login, &packet));
+    }
+
+    let response: LoginPluginResponse = read(&packet)?;
+    match response.data {
+        Some(data) if response.message_id.0 == MESSAGE_ID => Ok(PlayerInfo::verify(secret, &data)?),
+        Some(_) => Err(unexpected(State::Login, &packet)),
+        None => Err(ForwardingError::NotForwarded.into()),
+    }
+}
@@ -0,0 +1,135 @@
+use bytes::Bytes;
+use tokio::io::duplex;
+
+use super::{
+    ForwardedKey, ForwardingError, PlayerInfo, CHANNEL, MODERN_DEFAULT, MODERN_LAZY_SESSION,
+    MODERN_WITH_KEY_V2,
+};
+use crate::{
+    connection::GameProfile,
+    encoding::{remaining::Remaining, str::Str, varint::VarInt},
+    login::{sha256, ClientLogin, LoginError, ServerLogin},
+    packet::login::{LoginPluginRequest, PlayerKey, Property},
+    protocol::ProtocolVersion as V,
+};
+
+const SECRET: &[u8] = b"forwarding secret";
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn info(version: u8) -> PlayerInfo {
+    PlayerInfo {
+        version,
+        address: "203.0.113.7".parse().unwrap(),
+        profile: GameProfile {
+            uuid: 0x069a79f444e94726a5befca90e38aaf5,
+            username: Str::from_static("Notch"),
+            properties: vec![Property {
+                name: Str::from_static("textures"),
+                value: Str::from_static("e30="),
+                signature: Some(Str::from_static("c2lnbmF0dXJl")),
+            }],
+        },
+        key: Some(ForwardedKey {
+            key: PlayerKey {
+                expires_at: 1_700_000_000_000,
+                public_key: Bytes::from_static(&[1, 2, 3]),
+                signature: Bytes::from_static(&[4, 5, 6]),
+            },
+            holder: Some(1),
+        }),
+    }
+}
+
+fn request(version: u8) -> LoginPluginRequest {
+    LoginPluginRequest {
+        message_id: VarInt(1),
+        channel: Str::from_static(CHANNEL),
+        data: Remaining(Bytes::copy_from_slice(&[version])),
+    }
+}
+
+#[test]
+fn hmac() {
+    // RFC 4231, test case 2
+    assert_eq!(
+        hex(&sha256::hmac(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+}
+
+#[test]
+fn signature() {
+    let info = info(MODERN_WITH_KEY_V2);
+    let data = info.sign(SECRET);
+    assert_eq!(PlayerInfo::verify(SECRET, &data).unwrap(), info);
+
+    assert!(matches!(
+        PlayerInfo::verify(b"other secret", &data),
+        Err(ForwardingError::Signature)
+    ));
+
+    let mut forged = data.to_vec();
+    *forged.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        PlayerInfo::verify(SECRET, &forged),
+        Err(ForwardingError::Signature)
+    ));
+}
+
+#[test]
+fn negotiation() {
+    let info = info(MODERN_WITH_KEY_V2);
+
+    // the key is dropped when the server only supports the default version
+    let data = info.respond(SECRET, &request(MODERN_DEFAULT)).unwrap();
+    let forwarded = PlayerInfo::verify(SECRET, &data).unwrap();
+    assert_eq!((forwarded.version, forwarded.key), (MODERN_DEFAULT, None));
+
+    // servers supporting newer versions get the version of the proxy
+    let data = info.respond(SECRET, &request(MODERN_LAZY_SESSION)).unwrap();
+    assert_eq!(PlayerInfo::verify(SECRET, &data).unwrap(), info);
+
+    let other = LoginPluginRequest {
+        channel: Str::from_static("other:channel"),
+        ..request(MODERN_DEFAULT)
+    };
+    assert_eq!(info.respond(SECRET, &other), None);
+}
+
+#[tokio::test]
+async fn login() {
+    let (client, server) = duplex(4096);
+    let info = info(MODERN_LAZY_SESSION);
+
+    let forwarded = info.clone();
+    let client = ClientLogin::new("Notch", V::V1_21_5)
+        .plugin(move |request| forwarded.respond(SECRET, request))
+        .login(client);
+    let server = ServerLogin::new().velocity(SECRET).accept(server);
+
+    let (client, server) = futures::join!(client, server);
+    let (client, server) = (client.unwrap(), server.unwrap());
+
+    assert_eq!(server.profile(), &info.profile);
+    assert_eq!(server.get_address(), Some(info.address));
+    assert_eq!(client.profile().uuid, info.profile.uuid);
+}
+
+#[tokio::test]
+async fn not_forwarded() {
+    let (client, server) = duplex(4096);
+
+    let client = ClientLogin::new("Notch", V::V1_21_5).login(client);
+    let server = ServerLogin::new().velocity(SECRET).accept(server);
+
+    let (client, server) = futures::join!(client, server);
+
+    assert!(matches!(client, Err(LoginError::Disconnected(_))));
+    assert!(matches!(
+        server,
+        Err(LoginError::Forwarding(ForwardingError::NotForwarded))
+    ));
+}
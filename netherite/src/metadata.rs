@@ -0,0 +1,623 @@
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::{
+    encoding::{
+        position::Position,
+        str::Str,
+        varint::{VarInt, VarLong},
+        versioned::{VersionedDeserialize, VersionedSerialize},
+    },
+    item::Slot,
+    nbt::Nbt,
+    protocol::ProtocolVersion,
+    text::Chat,
+    DeError, Deserialize, Serialize,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Index marking the end of the metadata entries
+const END: u8 = 0xFF;
+
+/// Serializer used to encode the value of a metadata entry.
+///
+/// The type id associated with each serializer changes
+/// between versions, see [`MetadataTypes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum MetadataType {
+    Byte,
+    VarInt,
+    VarLong,
+    Float,
+    String,
+    Chat,
+    OptionalChat,
+    Slot,
+    Bool,
+    Rotation,
+    Position,
+    OptionalPosition,
+    Direction,
+    OptionalUuid,
+    BlockState,
+    OptionalBlockState,
+    Nbt,
+    Particle,
+    Particles,
+    VillagerData,
+    OptionalVarInt,
+    Pose,
+    CatVariant,
+    WolfVariant,
+    FrogVariant,
+    OptionalGlobalPosition,
+    PaintingVariant,
+    SnifferState,
+    ArmadilloState,
+    Vector3,
+    Quaternion,
+}
+
+/// Table mapping metadata type ids to their serializer
+/// for a range of protocol versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataTypes(&'static [MetadataType]);
+
+mod tables {
+    use super::MetadataType::{self, *};
+
+    pub const V1_13: &[MetadataType] = &[
+        Byte,
+        VarInt,
+        Float,
+        String,
+        Chat,
+        OptionalChat,
+        Slot,
+        Bool,
+        Rotation,
+        Position,
+        OptionalPosition,
+        Direction,
+        OptionalUuid,
+        OptionalBlockState,
+        Nbt,
+        Particle,
+        VillagerData,
+        OptionalVarInt,
+        Pose,
+    ];
+
+    pub const V1_19: &[MetadataType] = &[
+        Byte,
+        VarInt,
+        Float,
+        String,
+        Chat,
+        OptionalChat,
+        Slot,
+        Bool,
+        Rotation,
+        Position,
+        OptionalPosition,
+        Direction,
+        OptionalUuid,
+        OptionalBlockState,
+        Nbt,
+        Particle,
+        VillagerData,
+        OptionalVarInt,
+        Pose,
+        CatVariant,
+        FrogVariant,
+        OptionalGlobalPosition,
+        PaintingVariant,
+    ];
+
+    pub const V1_19_3: &[MetadataType] = &[
+        Byte,
+        VarInt,
+        VarLong,
+        Float,
+        String,
+        Chat,
+        OptionalChat,
+        Slot,
+        Bool,
+        Rotation,
+        Position,
+        OptionalPosition,
+        Direction,
+        OptionalUuid,
+        OptionalBlockState,
+        Nbt,
+        Particle,
+        VillagerData,
+        OptionalVarInt,
+        Pose,
+        CatVariant,
+        FrogVariant,
+        OptionalGlobalPosition,
+        PaintingVariant,
+    ];
+
+    pub const V1_19_4: &[MetadataType] = &[
+        Byte,
+        VarInt,
+        VarLong,
+        Float,
+        String,
+        Chat,
+        OptionalChat,
+        Slot,
+        Bool,
+        Rotation,
+        Position,
+        OptionalPosition,
+        Direction,
+        OptionalUuid,
+        BlockState,
+        OptionalBlockState,
+        Nbt,
+        Particle,
+        VillagerData,
+        OptionalVarInt,
+        Pose,
+        CatVariant,
+        FrogVariant,
+        OptionalGlobalPosition,
+        PaintingVariant,
+        SnifferState,
+        Vector3,
+        Quaternion,
+    ];
+
+    pub const V1_20_5: &[MetadataType] = &[
+        Byte,
+        VarInt,
+        VarLong,
+        Float,
+        String,
+        Chat,
+        OptionalChat,
+        Slot,
+        Bool,
+        Rotation,
+        Position,
+        OptionalPosition,
+        Direction,
+        OptionalUuid,
+        BlockState,
+        OptionalBlockState,
+        Nbt,
+        Particle,
+        Particles,
+        VillagerData,
+        OptionalVarInt,
+        Pose,
+        CatVariant,
+        WolfVariant,
+        FrogVariant,
+        OptionalGlobalPosition,
+        PaintingVariant,
+        SnifferState,
+        ArmadilloState,
+        Vector3,
+        Quaternion,
+    ];
+}
+
+impl MetadataTypes {
+    /// Type table of `version`, if bundled.
+    ///
+    /// Tables go from 1.13 up to 1.21.1
+    pub fn for_version(version: ProtocolVersion) -> Option<Self> {
+        use ProtocolVersion as V;
+
+        let table = match version {
+            v if v < V::V1_13 => return None,
+            v if v < V::V1_19 => tables::V1_13,
+            v if v < V::V1_19_3 => tables::V1_19,
+            v if v < V::V1_19_4 => tables::V1_19_3,
+            v if v < V::V1_20_5 => tables::V1_19_4,
+            v if v <= V::V1_21 => tables::V1_20_5,
+            _ => return None,
+        };
+
+        Some(Self(table))
+    }
+
+    /// serializer associated to type `id`
+    pub fn get(&self, id: i32) -> Option<MetadataType> {
+        usize::try_from(id)
+            .ok()
+            .and_then(|id| self.0.get(id))
+            .copied()
+    }
+
+    /// type id of serializer `kind`
+    pub fn id(&self, kind: MetadataType) -> Option<i32> {
+        self.0.iter().position(|&k| k == kind).map(|id| id as i32)
+    }
+}
+
+/// Value of a metadata entry
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum MetadataValue {
+    Byte(i8),
+    VarInt(i32),
+    VarLong(i64),
+    Float(f32),
+    String(Str),
+    Chat(Chat),
+    OptionalChat(Option<Chat>),
+    Slot(Slot),
+    Bool(bool),
+    /// rotation on the x, y and z axis, in degrees
+    Rotation([f32; 3]),
+    Position(Position),
+    OptionalPosition(Option<Position>),
+    Direction(i32),
+    OptionalUuid(Option<u128>),
+    BlockState(i32),
+    /// block state id, where 0 (air) means absent
+    OptionalBlockState(i32),
+    Nbt(Nbt),
+    /// villager type, profession and level
+    VillagerData([i32; 3]),
+    OptionalVarInt(Option<i32>),
+    Pose(i32),
+    CatVariant(i32),
+    WolfVariant(i32),
+    FrogVariant(i32),
+    /// dimension identifier and position
+    OptionalGlobalPosition(Option<(Str, Position)>),
+    PaintingVariant(i32),
+    SnifferState(i32),
+    ArmadilloState(i32),
+    Vector3([f32; 3]),
+    Quaternion([f32; 4]),
+    /// Value of a type that couldn't be decoded, either
+    /// because it's unknown or because it has no typed
+    /// representation (like particles).
+    ///
+    /// Since its length is unknown, `data` contains the rest
+    /// of the metadata including the following entries and the
+    /// terminator, so it must always be the last entry
+    Raw {
+        type_id: i32,
+        data: Bytes,
+    },
+}
+
+fn varint(buffer: impl Buf) -> Result<i32, DeError> {
+    VarInt::deserialize(buffer).map(|VarInt(v)| v)
+}
+
+fn floats<const N: usize>(mut buffer: impl Buf) -> Result<[f32; N], DeError> {
+    let mut floats = [0f32; N];
+    for float in &mut floats {
+        *float = f32::deserialize(&mut buffer)?;
+    }
+
+    Ok(floats)
+}
+
+impl MetadataValue {
+    /// serializer of this value, `None` for [`MetadataValue::Raw`]
+    pub fn kind(&self) -> Option<MetadataType> {
+        use MetadataType as T;
+
+        let kind = match self {
+            MetadataValue::Byte(_) => T::Byte,
+            MetadataValue::VarInt(_) => T::VarInt,
+            MetadataValue::VarLong(_) => T::VarLong,
+            MetadataValue::Float(_) => T::Float,
+            MetadataValue::String(_) => T::String,
+            MetadataValue::Chat(_) => T::Chat,
+            MetadataValue::OptionalChat(_) => T::OptionalChat,
+            MetadataValue::Slot(_) => T::Slot,
+            MetadataValue::Bool(_) => T::Bool,
+            MetadataValue::Rotation(_) => T::Rotation,
+            MetadataValue::Position(_) => T::Position,
+            MetadataValue::OptionalPosition(_) => T::OptionalPosition,
+            MetadataValue::Direction(_) => T::Direction,
+            MetadataValue::OptionalUuid(_) => T::OptionalUuid,
+            MetadataValue::BlockState(_) => T::BlockState,
+            MetadataValue::OptionalBlockState(_) => T::OptionalBlockState,
+            MetadataValue::Nbt(_) => T::Nbt,
+            MetadataValue::VillagerData(_) => T::VillagerData,
+            MetadataValue::OptionalVarInt(_) => T::OptionalVarInt,
+            MetadataValue::Pose(_) => T::Pose,
+            MetadataValue::CatVariant(_) => T::CatVariant,
+            MetadataValue::WolfVariant(_) => T::WolfVariant,
+            MetadataValue::FrogVariant(_) => T::FrogVariant,
+            MetadataValue::OptionalGlobalPosition(_) => T::OptionalGlobalPosition,
+            MetadataValue::PaintingVariant(_) => T::PaintingVariant,
+            MetadataValue::SnifferState(_) => T::SnifferState,
+            MetadataValue::ArmadilloState(_) => T::ArmadilloState,
+            MetadataValue::Vector3(_) => T::Vector3,
+            MetadataValue::Quaternion(_) => T::Quaternion,
+            MetadataValue::Raw { .. } => return None,
+        };
+
+        Some(kind)
+    }
+
+    /// Decodes a value of `kind`, `None` if it has no typed representation
+    fn read(
+        kind: MetadataType,
+        mut buffer: impl Buf,
+        version: ProtocolVersion,
+    ) -> Result<Option<Self>, DeError> {
+        use MetadataType as T;
+        use MetadataValue as V;
+
+        let value = match kind {
+            T::Byte => V::Byte(Deserialize::deserialize(buffer)?),
+            T::VarInt => V::VarInt(varint(&mut buffer)?),
+            T::VarLong => V::VarLong(VarLong::deserialize(buffer)?.0),
+            T::Float => V::Float(Deserialize::deserialize(buffer)?),
+            T::String => V::String(Deserialize::deserialize(buffer)?),
+            T::Chat => V::Chat(Chat::deserialize_versioned(buffer, version)?),
+            T::OptionalChat => V::OptionalChat(match bool::deserialize(&mut buffer)? {
+                true => Some(Chat::deserialize_versioned(buffer, version)?),
+                false => None,
+            }),
+            T::Slot => V::Slot(Slot::deserialize_versioned(buffer, version)?),
+            T::Bool => V::Bool(Deserialize::deserialize(buffer)?),
+            T::Rotation => V::Rotation(floats(buffer)?),
+            T::Position => V::Position(Deserialize::deserialize(buffer)?),
+            T::OptionalPosition => V::OptionalPosition(Deserialize::deserialize(buffer)?),
+            T::Direction => V::Direction(varint(&mut buffer)?),
+            T::OptionalUuid => V::OptionalUuid(Deserialize::deserialize(buffer)?),
+            T::BlockState => V::BlockState(varint(&mut buffer)?),
+            T::OptionalBlockState => V::OptionalBlockState(varint(&mut buffer)?),
+            T::Nbt => V::Nbt(Nbt::deserialize_versioned(buffer, version)?),
+            T::VillagerData => V::VillagerData([
+                varint(&mut buffer)?,
+                varint(&mut buffer)?,
+                varint(&mut buffer)?,
+            ]),
+            T::OptionalVarInt => V::OptionalVarInt(match varint(&mut buffer)? {
+                0 => None,
+                v => Some(v - 1),
+            }),
+            T::Pose => V::Pose(varint(&mut buffer)?),
+            T::CatVariant => V::CatVariant(varint(&mut buffer)?),
+            T::WolfVariant => V::WolfVariant(varint(&mut buffer)?),
+            T::FrogVariant => V::FrogVariant(varint(&mut buffer)?),
+            T::OptionalGlobalPosition => {
+                V::OptionalGlobalPosition(match bool::deserialize(&mut buffer)? {
+                    true => Some((
+                        Str::deserialize(&mut buffer)?,
+                        Position::deserialize(buffer)?,
+                    )),
+                    false => None,
+                })
+            }
+            T::PaintingVariant => V::PaintingVariant(varint(&mut buffer)?),
+            T::SnifferState => V::SnifferState(varint(&mut buffer)?),
+            T::ArmadilloState => V::ArmadilloState(varint(&mut buffer)?),
+            T::Vector3 => V::Vector3(floats(buffer)?),
+            T::Quaternion => V::Quaternion(floats(buffer)?),
+            T::Particle | T::Particles => return Ok(None),
+        };
+
+        Ok(Some(value))
+    }
+
+    fn write(&self, mut buf: impl BufMut, version: ProtocolVersion) {
+        use MetadataValue as V;
+
+        match self {
+            V::Byte(v) => v.serialize(buf),
+            V::VarLong(v) => VarLong(*v).serialize(buf),
+            V::Float(v) => v.serialize(buf),
+            V::String(v) => v.serialize(buf),
+            V::Chat(v) => v.serialize_versioned(buf, version),
+            V::OptionalChat(v) => {
+                v.is_some().serialize(&mut buf);
+                if let Some(v) = v {
+                    v.serialize_versioned(buf, version)
+                }
+            }
+            V::Slot(v) => v.serialize_versioned(buf, version),
+            V::Bool(v) => v.serialize(buf),
+            V::Rotation(v) | V::Vector3(v) => v.iter().for_each(|f| f.serialize(&mut buf)),
+            V::Quaternion(v) => v.iter().for_each(|f| f.serialize(&mut buf)),
+            V::Position(v) => v.serialize(buf),
+            V::OptionalPosition(v) => v.serialize(buf),
+            V::OptionalUuid(v) => v.serialize(buf),
+            V::Nbt(v) => v.serialize_versioned(buf, version),
+            V::VillagerData(v) => v.iter().for_each(|&v| VarInt(v).serialize(&mut buf)),
+            V::OptionalVarInt(v) => VarInt(v.map(|v| v + 1).unwrap_or_default()).serialize(buf),
+            V::OptionalGlobalPosition(v) => {
+                v.is_some().serialize(&mut buf);
+                if let Some((dimension, position)) = v {
+                    dimension.serialize(&mut buf);
+                    position.serialize(buf);
+                }
+            }
+            V::VarInt(v)
+            | V::Direction(v)
+            | V::BlockState(v)
+            | V::OptionalBlockState(v)
+            | V::Pose(v)
+            | V::CatVariant(v)
+            | V::WolfVariant(v)
+            | V::FrogVariant(v)
+            | V::PaintingVariant(v)
+            | V::SnifferState(v)
+            | V::ArmadilloState(v) => VarInt(*v).serialize(buf),
+            V::Raw { data, .. } => buf.put_slice(data),
+        }
+    }
+
+    fn size(&self, version: ProtocolVersion) -> usize {
+        use MetadataValue as V;
+
+        match self {
+            V::Byte(_) | V::Bool(_) => 1,
+            V::VarLong(v) => VarLong(*v).size(),
+            V::Float(_) => 4,
+            V::String(v) => v.size(),
+            V::Chat(v) => v.size_versioned(version),
+            V::OptionalChat(v) => 1 + v.as_ref().map_or(0, |v| v.size_versioned(version)),
+            V::Slot(v) => v.size_versioned(version),
+            V::Rotation(_) | V::Vector3(_) => 12,
+            V::Quaternion(_) => 16,
+            V::Position(v) => v.size(),
+            V::OptionalPosition(v) => v.size(),
+            V::OptionalUuid(v) => v.size(),
+            V::Nbt(v) => v.size_versioned(version),
+            V::VillagerData(v) => v.iter().map(|&v| VarInt(v).size()).sum(),
+            V::OptionalVarInt(v) => VarInt(v.map(|v| v + 1).unwrap_or_default()).size(),
+            V::OptionalGlobalPosition(v) => {
+                1 + v.as_ref().map_or(0, |(dimension, _)| dimension.size() + 8)
+            }
+            V::VarInt(v)
+            | V::Direction(v)
+            | V::BlockState(v)
+            | V::OptionalBlockState(v)
+            | V::Pose(v)
+            | V::CatVariant(v)
+            | V::WolfVariant(v)
+            | V::FrogVariant(v)
+            | V::PaintingVariant(v)
+            | V::SnifferState(v)
+            | V::ArmadilloState(v) => VarInt(*v).size(),
+            V::Raw { data, .. } => data.len(),
+        }
+    }
+}
+
+/// A single metadata entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataEntry {
+    /// index of the field in the entity metadata
+    pub index: u8,
+    /// value of the field
+    pub value: MetadataValue,
+}
+
+/// Entity metadata, as found in the Set Entity Metadata packet (1.13+).
+///
+/// Entries whose value isn't supported by the version used
+/// to serialize are skipped. If no type table is bundled
+/// for the version, the whole metadata is kept raw
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityMetadata {
+    /// entries in the order they're sent
+    pub entries: Vec<MetadataEntry>,
+}
+
+impl EntityMetadata {
+    /// value of the entry at `index`
+    pub fn get(&self, index: u8) -> Option<&MetadataValue> {
+        self.entries
+            .iter()
+            .find(|entry| entry.index == index)
+            .map(|entry| &entry.value)
+    }
+
+    /// Sets the value of the entry at `index`, keeping its position if present.
+    /// New entries go before a trailing [`MetadataValue::Raw`] entry, which
+    /// holds the rest of the metadata up to the terminator
+    pub fn set(&mut self, index: u8, value: MetadataValue) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.index == index) {
+            entry.value = value;
+            return;
+        }
+
+        let position = self.entries.len() - self.ends_raw() as usize;
+        self.entries
+            .insert(position, MetadataEntry { index, value });
+    }
+
+    /// entries which are going to be written, with their type id
+    fn encoded(&self, version: ProtocolVersion) -> impl Iterator<Item = (&MetadataEntry, i32)> {
+        let types = MetadataTypes::for_version(version);
+
+        self.entries.iter().filter_map(move |entry| {
+            let id = match &entry.value {
+                MetadataValue::Raw { type_id, .. } => *type_id,
+                value => types?.id(value.kind()?)?,
+            };
+
+            Some((entry, id))
+        })
+    }
+
+    fn ends_raw(&self) -> bool {
+        matches!(
+            self.entries.last(),
+            Some(MetadataEntry {
+                value: MetadataValue::Raw { .. },
+                ..
+            })
+        )
+    }
+}
+
+impl VersionedSerialize for EntityMetadata {
+    fn serialize_versioned(&self, mut buf: impl BufMut, version: ProtocolVersion) {
+        for (entry, id) in self.encoded(version) {
+            entry.index.serialize(&mut buf);
+            VarInt(id).serialize(&mut buf);
+            entry.value.write(&mut buf, version);
+        }
+
+        if !self.ends_raw() {
+            END.serialize(buf);
+        }
+    }
+
+    fn size_versioned(&self, version: ProtocolVersion) -> usize {
+        let entries = self
+            .encoded(version)
+            .map(|(entry, id)| 1 + VarInt(id).size() + entry.value.size(version));
+
+        entries.sum::<usize>() + (!self.ends_raw()) as usize
+    }
+}
+
+impl VersionedDeserialize for EntityMetadata {
+    fn deserialize_versioned(
+        mut buffer: impl Buf,
+        version: ProtocolVersion,
+    ) -> Result<Self, DeError> {
+        let types = MetadataTypes::for_version(version);
+        let mut entries = vec![];
+
+        loop {
+            let index = u8::deserialize(&mut buffer)?;
+            if index == END {
+                break;
+            }
+
+            let VarInt(type_id) = VarInt::deserialize(&mut buffer)?;
+            let kind = types.and_then(|types| types.get(type_id));
+
+            let value = match kind {
+                Some(kind) => MetadataValue::read(kind, &mut buffer, version)?,
+                None => None,
+            };
+
+            let Some(value) = value else {
+                let data = buffer.copy_to_bytes(buffer.remaining());
+                let value = MetadataValue::Raw { type_id, data };
+                entries.push(MetadataEntry { index, value });
+
+                break;
+            };
+
+            entries.push(MetadataEntry { index, value });
+        }
+
+        Ok(Self { entries })
+    }
+}
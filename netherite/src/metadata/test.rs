@@ -0,0 +1,129 @@
+use bytes::Bytes;
+
+use super::{EntityMetadata, MetadataEntry, MetadataType, MetadataTypes, MetadataValue};
+use crate::{
+    assert_serialization,
+    encoding::{
+        position::Position,
+        versioned::{Versioned, VersionedDeserialize},
+    },
+    protocol::ProtocolVersion,
+};
+
+const VERSION: ProtocolVersion = ProtocolVersion::V1_20_5;
+
+fn metadata(entries: impl IntoIterator<Item = (u8, MetadataValue)>) -> EntityMetadata {
+    let entries = entries.into_iter();
+    EntityMetadata {
+        entries: entries
+            .map(|(index, value)| MetadataEntry { index, value })
+            .collect(),
+    }
+}
+
+#[test]
+fn type_tables() {
+    let old = MetadataTypes::for_version(ProtocolVersion::V1_19).unwrap();
+    let new = MetadataTypes::for_version(VERSION).unwrap();
+
+    assert_eq!(old.get(2), Some(MetadataType::Float));
+    assert_eq!(new.get(2), Some(MetadataType::VarLong));
+    assert_eq!(old.id(MetadataType::Pose), Some(18));
+    assert_eq!(new.id(MetadataType::Pose), Some(21));
+    assert_eq!(old.id(MetadataType::ArmadilloState), None);
+
+    assert!(MetadataTypes::for_version(ProtocolVersion::V1_12_2).is_none());
+}
+
+#[test]
+fn roundtrip() {
+    let metadata = metadata([
+        (0, MetadataValue::Byte(0x02)),
+        (1, MetadataValue::VarInt(300)),
+        (2, MetadataValue::OptionalChat(None)),
+        (6, MetadataValue::Pose(5)),
+        (8, MetadataValue::OptionalVarInt(Some(0))),
+        (
+            9,
+            MetadataValue::OptionalPosition(Some(Position::new(1, 2, 3))),
+        ),
+        (10, MetadataValue::Quaternion([0.0, 0.0, 0.0, 1.0])),
+    ]);
+
+    let bytes = crate::encoding::serialize_bytes(Versioned::new(&metadata, VERSION));
+    let parsed = EntityMetadata::deserialize_versioned(&bytes[..], VERSION).unwrap();
+
+    assert_eq!(parsed, metadata);
+    assert_eq!(bytes.last(), Some(&0xFF));
+}
+
+#[test]
+fn encoding() {
+    let metadata = metadata([
+        (0, MetadataValue::Byte(1)),
+        (6, MetadataValue::Pose(1)),
+        (7, MetadataValue::OptionalVarInt(None)),
+    ]);
+
+    let bytes = [0x00, 0x00, 0x01, 0x06, 0x15, 0x01, 0x07, 0x14, 0x00, 0xFF];
+    assert_serialization!(&Versioned::new(&metadata, VERSION) => &bytes);
+}
+
+#[test]
+fn unsupported_types_are_skipped() {
+    let metadata = metadata([
+        (0, MetadataValue::ArmadilloState(1)),
+        (1, MetadataValue::Bool(true)),
+    ]);
+
+    let bytes = [0x01, 0x07, 0x01, 0xFF];
+    assert_serialization!(&Versioned::new(&metadata, ProtocolVersion::V1_19) => &bytes);
+}
+
+#[test]
+fn raw_fallback() {
+    // a particle (id 17) has no typed representation
+    let data = [0x00, 0x00, 0x05, 0x03, 0x11, 0x2a, 0x04, 0x00, 0x01, 0xFF];
+    let parsed = EntityMetadata::deserialize_versioned(&data[..], VERSION).unwrap();
+
+    assert_eq!(parsed.get(0), Some(&MetadataValue::Byte(5)));
+    assert_eq!(
+        parsed.get(3),
+        Some(&MetadataValue::Raw {
+            type_id: 17,
+            data: Bytes::from_static(&[0x2a, 0x04, 0x00, 0x01, 0xFF]),
+        })
+    );
+
+    // forwarded byte for byte
+    assert_serialization!(&Versioned::new(&parsed, VERSION) => &data);
+
+    // new entries are written before the raw tail and its terminator
+    let mut parsed = parsed;
+    parsed.set(8, MetadataValue::Bool(true));
+
+    let expected = [
+        0x00, 0x00, 0x05, 0x08, 0x08, 0x01, 0x03, 0x11, 0x2a, 0x04, 0x00, 0x01, 0xFF,
+    ];
+    assert_serialization!(&Versioned::new(&parsed, VERSION) => &expected);
+}
+
+#[test]
+fn unknown_version_is_raw() {
+    let data = [0x00, 0x00, 0x05, 0xFF];
+    let version = ProtocolVersion::V1_21_5;
+    let parsed = EntityMetadata::deserialize_versioned(&data[..], version).unwrap();
+
+    assert_eq!(parsed.entries.len(), 1);
+    assert_serialization!(&Versioned::new(&parsed, version) => &data);
+}
+
+#[test]
+fn set() {
+    let mut metadata = metadata([(0, MetadataValue::Byte(0)), (1, MetadataValue::Bool(false))]);
+    metadata.set(0, MetadataValue::Byte(1));
+    metadata.set(4, MetadataValue::Bool(true));
+
+    assert_eq!(metadata.entries[0].value, MetadataValue::Byte(1));
+    assert_eq!(metadata.get(4), Some(&MetadataValue::Bool(true)));
+}
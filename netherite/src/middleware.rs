@@ -0,0 +1,286 @@
+use std::{collections::VecDeque, marker::PhantomData};
+
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{
+    encoding::packetid::PacketId, packet::RawPacket, protocol::Direction, DeError, Deserialize,
+    Serialize,
+};
+
+#[cfg(test)]
+mod test;
+
+/// State available to a [`Middleware`] while
+/// it's handling a packet
+#[derive(Debug)]
+pub struct Context {
+    direction: Direction,
+    emitted: Vec<RawPacket>,
+    replies: Vec<RawPacket>,
+}
+
+impl Context {
+    fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            emitted: vec![],
+            replies: vec![],
+        }
+    }
+
+    /// direction of the packet being handled
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Injects `packet` in the same direction, right after the handled
+    /// one. It goes through the layers following the current one
+    pub fn emit(&mut self, packet: impl Into<RawPacket>) {
+        self.emitted.push(packet.into())
+    }
+
+    /// Sends `packet` back where the handled packet came
+    /// from. Replies don't go through any other layer
+    pub fn reply(&mut self, packet: impl Into<RawPacket>) {
+        self.replies.push(packet.into())
+    }
+}
+
+/// A layer inspecting packets passing through a [`Chain`]
+pub trait Middleware: Send {
+    /// Handles `packet`, returning the packet to pass to
+    /// the next layer or `None` to drop it
+    fn handle(
+        &mut self,
+        packet: RawPacket,
+        ctx: &mut Context,
+    ) -> Result<Option<RawPacket>, DeError>;
+}
+
+impl<F> Middleware for F
+where
+    F: FnMut(RawPacket, &mut Context) -> Result<Option<RawPacket>, DeError> + Send,
+{
+    fn handle(
+        &mut self,
+        packet: RawPacket,
+        ctx: &mut Context,
+    ) -> Result<Option<RawPacket>, DeError> {
+        self(packet, ctx)
+    }
+}
+
+/// Typed middleware, see [`hook`]
+pub struct Hook<T, F> {
+    direction: Direction,
+    handler: F,
+    _packet: PhantomData<fn(T) -> T>,
+}
+
+/// Creates a middleware handling only packets of type `T` travelling in
+/// `direction`, leaving the others untouched. Returning `None` drops the packet.
+///
+/// Matching packets are deserialized and then serialized again
+/// after `handler`, so `T` must encode all of the packet data
+pub fn hook<T, F>(direction: Direction, handler: F) -> Hook<T, F>
+where
+    T: Serialize + Deserialize + PacketId,
+    F: FnMut(T, &mut Context) -> Option<T> + Send,
+{
+    Hook {
+        direction,
+        handler,
+        _packet: PhantomData,
+    }
+}
+
+impl<T, F> Middleware for Hook<T, F>
+where
+    T: Serialize + Deserialize + PacketId,
+    F: FnMut(T, &mut Context) -> Option<T> + Send,
+{
+    fn handle(
+        &mut self,
+        packet: RawPacket,
+        ctx: &mut Context,
+    ) -> Result<Option<RawPacket>, DeError> {
+        if ctx.direction != self.direction || !packet.is::<T>() {
+            return Ok(Some(packet));
+        }
+
+        let packet = packet.deserialize_unchecked::<T>()?;
+        Ok((self.handler)(packet, ctx).map(RawPacket::from))
+    }
+}
+
+/// Result of a packet going through a [`Chain`]
+#[derive(Debug, Default)]
+pub struct Processed {
+    /// packets to forward in the original direction, in order
+    pub forward: Vec<RawPacket>,
+    /// packets to send back in the opposite direction, in order
+    pub replies: Vec<RawPacket>,
+}
+
+/// Ordered list of middlewares.
+///
+/// Packets go through the layers in the order they were added, in both
+/// directions. Packets emitted by a layer follow the packet which
+/// caused them, so the relative order of packets is always preserved
+#[derive(Default)]
+pub struct Chain {
+    layers: Vec<Box<dyn Middleware>>,
+}
+
+impl Chain {
+    /// creates an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds `middleware` as the last layer
+    pub fn layer(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.push(middleware);
+        self
+    }
+
+    /// adds `middleware` as the last layer
+    pub fn push(&mut self, middleware: impl Middleware + 'static) {
+        self.layers.push(Box::new(middleware))
+    }
+
+    /// passes `packet` travelling in `direction` through every layer
+    pub fn process(
+        &mut self,
+        direction: Direction,
+        packet: RawPacket,
+    ) -> Result<Processed, DeError> {
+        let mut processed = Processed::default();
+        run(&mut self.layers, direction, packet, &mut processed)?;
+
+        Ok(processed)
+    }
+}
+
+fn run(
+    layers: &mut [Box<dyn Middleware>],
+    direction: Direction,
+    packet: RawPacket,
+    processed: &mut Processed,
+) -> Result<(), DeError> {
+    let Some((layer, rest)) = layers.split_first_mut() else {
+        processed.forward.push(packet);
+        return Ok(());
+    };
+
+    let mut ctx = Context::new(direction);
+    let packet = layer.handle(packet, &mut ctx)?;
+    processed.replies.extend(ctx.replies);
+
+    for packet in packet.into_iter().chain(ctx.emitted) {
+        run(rest, direction, packet, processed)?;
+    }
+
+    Ok(())
+}
+
+/// Codec wrapper passing every decoded and encoded packet through a [`Chain`].
+///
+/// Replies can't be written or read by the codec itself, so both kinds are
+/// queued and must be pulled by the caller after each call:
+/// replies to decoded packets should be encoded, see [`Self::take_replies`],
+/// and replies to encoded packets should be handled as if received, see
+/// [`Self::take_injected`]. A [`Framed`](tokio_util::codec::Framed) only
+/// decodes when bytes arrive, so it would never yield the latter on its own
+pub struct Intercept<C> {
+    inner: C,
+    chain: Chain,
+    inbound: Direction,
+    received: VecDeque<RawPacket>,
+    replies: VecDeque<RawPacket>,
+    injected: VecDeque<RawPacket>,
+}
+
+impl<C> Intercept<C> {
+    /// Wraps `inner`, which decodes packets travelling in `inbound`
+    /// direction and encodes packets travelling in the opposite one
+    pub fn new(inner: C, chain: Chain, inbound: Direction) -> Self {
+        Self {
+            inner,
+            chain,
+            inbound,
+            received: VecDeque::new(),
+            replies: VecDeque::new(),
+            injected: VecDeque::new(),
+        }
+    }
+
+    /// wrapped codec
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// wrapped codec
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// middleware chain
+    pub fn chain_mut(&mut self) -> &mut Chain {
+        &mut self.chain
+    }
+
+    /// takes the replies to decoded packets, which should be encoded
+    pub fn take_replies(&mut self) -> impl Iterator<Item = RawPacket> + '_ {
+        self.replies.drain(..)
+    }
+
+    /// takes the replies to encoded packets, which should be handled as received
+    pub fn take_injected(&mut self) -> impl Iterator<Item = RawPacket> + '_ {
+        self.injected.drain(..)
+    }
+}
+
+impl<C> Decoder for Intercept<C>
+where
+    C: Decoder<Item = RawPacket>,
+    C::Error: From<DeError>,
+{
+    type Item = RawPacket;
+    type Error = C::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            if let Some(packet) = self.received.pop_front() {
+                return Ok(Some(packet));
+            }
+
+            let Some(packet) = self.inner.decode(src)? else {
+                return Ok(None);
+            };
+
+            let processed = self.chain.process(self.inbound, packet)?;
+            self.received.extend(processed.forward);
+            self.replies.extend(processed.replies);
+        }
+    }
+}
+
+impl<C, E> Encoder<RawPacket> for Intercept<C>
+where
+    C: for<'a> Encoder<&'a RawPacket, Error = E>,
+    E: From<DeError> + From<std::io::Error>,
+{
+    type Error = E;
+
+    fn encode(&mut self, item: RawPacket, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let processed = self.chain.process(self.inbound.opposite(), item)?;
+        self.injected.extend(processed.replies);
+
+        processed
+            .forward
+            .iter()
+            .try_for_each(|packet| self.inner.encode(packet, dst))
+    }
+}
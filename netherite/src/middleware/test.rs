@@ -0,0 +1,142 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use super::{hook, Chain, Context, Intercept};
+use crate::{
+    encoding::packetid::PacketId, packet::RawPacket, protocol::Direction, DeError, Deserialize,
+    Serialize, UncompressedCodec,
+};
+
+const IN: Direction = Direction::Serverbound;
+
+struct Chat(u8);
+
+impl Serialize for Chat {
+    fn serialize(&self, mut buf: impl BufMut) {
+        buf.put_u8(self.0)
+    }
+
+    fn size(&self) -> usize {
+        1
+    }
+}
+
+impl Deserialize for Chat {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        u8::deserialize(buffer).map(Self)
+    }
+}
+
+impl PacketId for Chat {
+    const ID: i32 = 0x03;
+}
+
+fn raw(packet_id: i32, data: &'static [u8]) -> RawPacket {
+    let data = Bytes::from_static(data);
+    RawPacket { packet_id, data }
+}
+
+fn ids(packets: &[RawPacket]) -> Vec<i32> {
+    packets.iter().map(|packet| packet.packet_id).collect()
+}
+
+#[test]
+fn ordering() {
+    let mut chain = Chain::new()
+        .layer(|packet: RawPacket, ctx: &mut Context| {
+            if packet.packet_id == 1 {
+                ctx.emit(raw(2, &[]));
+            }
+
+            Ok(Some(packet))
+        })
+        .layer(|mut packet: RawPacket, ctx: &mut Context| {
+            // runs after the first layer, for emitted packets too
+            packet.packet_id *= 10;
+            ctx.emit(raw(packet.packet_id + 1, &[]));
+            Ok(Some(packet))
+        });
+
+    let processed = chain.process(IN, raw(1, &[])).unwrap();
+    assert_eq!(ids(&processed.forward), [10, 11, 20, 21]);
+    assert!(processed.replies.is_empty());
+}
+
+#[test]
+fn drop_and_reply() {
+    let mut chain = Chain::new()
+        .layer(|packet: RawPacket, ctx: &mut Context| {
+            ctx.reply(raw(packet.packet_id + 1, &[]));
+            Ok(None)
+        })
+        .layer(|_: RawPacket, _: &mut Context| -> Result<_, DeError> {
+            panic!("dropped packets don't reach following layers")
+        });
+
+    let processed = chain.process(IN, raw(1, &[])).unwrap();
+    assert!(processed.forward.is_empty());
+    assert_eq!(ids(&processed.replies), [2]);
+}
+
+#[test]
+fn typed_hook() {
+    let mut chain = Chain::new().layer(hook(IN, |chat: Chat, _: &mut Context| {
+        (chat.0 != 0).then_some(Chat(chat.0 + 1))
+    }));
+
+    let processed = chain.process(IN, raw(0x03, &[1])).unwrap();
+    assert_eq!(processed.forward[0].data.as_ref(), [2]);
+
+    let processed = chain.process(IN, raw(0x03, &[0])).unwrap();
+    assert!(processed.forward.is_empty());
+
+    // other direction and other ids are untouched, even if invalid
+    let processed = chain.process(IN.opposite(), raw(0x03, &[])).unwrap();
+    assert_eq!(processed.forward[0].data.len(), 0);
+    let processed = chain.process(IN, raw(0x04, &[])).unwrap();
+    assert_eq!(ids(&processed.forward), [0x04]);
+
+    assert!(matches!(
+        chain.process(IN, raw(0x03, &[])),
+        Err(DeError::Eof)
+    ));
+}
+
+#[test]
+fn codec() {
+    let chain = Chain::new().layer(|packet: RawPacket, ctx: &mut Context| {
+        match ctx.direction() {
+            Direction::Serverbound => ctx.reply(raw(0x09, &[])),
+            Direction::Clientbound => ctx.emit(raw(0x08, &[])),
+        }
+
+        Ok(Some(packet))
+    });
+
+    let mut codec = Intercept::new(UncompressedCodec::default(), chain, IN);
+
+    let mut src = BytesMut::from(&[0x01, 0x05][..]);
+    let packet = codec.decode(&mut src).unwrap().unwrap();
+    assert_eq!(packet.packet_id, 0x05);
+    assert!(codec.decode(&mut src).unwrap().is_none());
+    assert_eq!(ids(&codec.take_replies().collect::<Vec<_>>()), [0x09]);
+
+    let mut dst = BytesMut::new();
+    codec.encode(raw(0x06, &[]), &mut dst).unwrap();
+    assert_eq!(dst.as_ref(), [0x01, 0x06, 0x01, 0x08]);
+    assert!(codec.take_replies().next().is_none());
+
+    // replies to encoded packets aren't decoded, but pulled
+    let chain = Chain::new().layer(|packet: RawPacket, ctx: &mut Context| {
+        if ctx.direction() == Direction::Clientbound {
+            ctx.reply(raw(0x0a, &[]));
+        }
+
+        Ok(Some(packet))
+    });
+    let mut codec = Intercept::new(UncompressedCodec::default(), chain, IN);
+
+    codec.encode(raw(0x06, &[]), &mut dst).unwrap();
+    assert!(codec.decode(&mut BytesMut::new()).unwrap().is_none());
+    assert_eq!(ids(&codec.take_injected().collect::<Vec<_>>()), [0x0a]);
+}
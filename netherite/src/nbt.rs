@@ -0,0 +1,430 @@
+use bytes::{Buf, BufMut};
+
+use crate::{
+    encoding::versioned::{VersionedDeserialize, VersionedSerialize},
+    protocol::ProtocolVersion,
+    DeError, Deserialize, Serialize,
+};
+
+#[cfg(feature = "serde_json")]
+mod json;
+mod mutf8;
+#[cfg(test)]
+mod test;
+
+/// Maximum nesting of lists and compounds, the same as vanilla
+pub const MAX_DEPTH: usize = 512;
+
+const END: u8 = 0;
+
+/// A single NBT tag
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(ListTag),
+    Compound(Compound),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+/// List of tags sharing the same type.
+///
+/// The element type is kept even for empty lists,
+/// so that they serialize back to the same bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListTag {
+    element: u8,
+    items: Vec<Tag>,
+}
+
+/// Compound tag, preserving the order of its entries
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Compound(Vec<(String, Tag)>);
+
+fn read_string(mut buffer: impl Buf) -> Result<String, DeError> {
+    let len = u16::deserialize(&mut buffer)? as usize;
+    if buffer.remaining() < len {
+        return Err(DeError::Eof);
+    }
+
+    let bytes = buffer.copy_to_bytes(len);
+    mutf8::decode(&bytes)
+}
+
+fn write_string(mut buf: impl BufMut, str: &str) {
+    (mutf8::size(str) as u16).serialize(&mut buf);
+    mutf8::write(buf, str);
+}
+
+fn string_size(str: &str) -> usize {
+    2 + mutf8::size(str)
+}
+
+fn read_array<T: Deserialize>(mut buffer: impl Buf) -> Result<Vec<T>, DeError> {
+    let len: usize = i32::deserialize(&mut buffer)?.try_into()?;
+    if buffer.remaining() < len * std::mem::size_of::<T>() {
+        return Err(DeError::Eof);
+    }
+
+    (0..len).map(|_| T::deserialize(&mut buffer)).collect()
+}
+
+fn write_array<T: Serialize>(mut buf: impl BufMut, array: &[T]) {
+    (array.len() as i32).serialize(&mut buf);
+    array.iter().for_each(|item| item.serialize(&mut buf));
+}
+
+impl Tag {
+    /// NBT type id of this tag
+    pub fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 3,
+            Tag::Long(_) => 4,
+            Tag::Float(_) => 5,
+            Tag::Double(_) => 6,
+            Tag::ByteArray(_) => 7,
+            Tag::String(_) => 8,
+            Tag::List(_) => 9,
+            Tag::Compound(_) => 10,
+            Tag::IntArray(_) => 11,
+            Tag::LongArray(_) => 12,
+        }
+    }
+
+    /// reads the payload of a tag of type `id`
+    fn read_payload<B: Buf + ?Sized>(
+        id: u8,
+        buffer: &mut B,
+        depth: usize,
+    ) -> Result<Self, DeError> {
+        if depth > MAX_DEPTH {
+            return Err(DeError::InvalidData);
+        }
+
+        let tag = match id {
+            1 => Tag::Byte(Deserialize::deserialize(buffer)?),
+            2 => Tag::Short(Deserialize::deserialize(buffer)?),
+            3 => Tag::Int(Deserialize::deserialize(buffer)?),
+            4 => Tag::Long(Deserialize::deserialize(buffer)?),
+            5 => Tag::Float(Deserialize::deserialize(buffer)?),
+            6 => Tag::Double(Deserialize::deserialize(buffer)?),
+            7 => Tag::ByteArray(read_array(buffer)?),
+            8 => Tag::String(read_string(buffer)?),
+            9 => {
+                let element = u8::deserialize(&mut *buffer)?;
+                let len: usize = i32::deserialize(&mut *buffer)?.try_into()?;
+                if element == END && len > 0 {
+                    return Err(DeError::InvalidData);
+                }
+
+                let items = (0..len)
+                    .map(|_| Tag::read_payload(element, buffer, depth + 1))
+                    .collect::<Result<_, _>>()?;
+
+                Tag::List(ListTag { element, items })
+            }
+            10 => {
+                let mut entries = vec![];
+
+                loop {
+                    let id = u8::deserialize(&mut *buffer)?;
+                    if id == END {
+                        break;
+                    }
+
+                    let name = read_string(&mut *buffer)?;
+                    let tag = Tag::read_payload(id, buffer, depth + 1)?;
+                    entries.push((name, tag));
+                }
+
+                Tag::Compound(Compound(entries))
+            }
+            11 => Tag::IntArray(read_array(buffer)?),
+            12 => Tag::LongArray(read_array(buffer)?),
+            _ => return Err(DeError::InvalidData),
+        };
+
+        Ok(tag)
+    }
+
+    fn write_payload<B: BufMut + ?Sized>(&self, buf: &mut B) {
+        match self {
+            Tag::Byte(v) => v.serialize(buf),
+            Tag::Short(v) => v.serialize(buf),
+            Tag::Int(v) => v.serialize(buf),
+            Tag::Long(v) => v.serialize(buf),
+            Tag::Float(v) => v.serialize(buf),
+            Tag::Double(v) => v.serialize(buf),
+            Tag::ByteArray(v) => write_array(buf, v),
+            Tag::String(v) => write_string(buf, v),
+            Tag::List(list) => {
+                list.element.serialize(&mut *buf);
+                (list.items.len() as i32).serialize(&mut *buf);
+                list.items.iter().for_each(|tag| tag.write_payload(buf));
+            }
+            Tag::Compound(compound) => {
+                for (name, tag) in compound.iter() {
+                    tag.id().serialize(&mut *buf);
+                    write_string(&mut *buf, name);
+                    tag.write_payload(buf);
+                }
+
+                END.serialize(buf);
+            }
+            Tag::IntArray(v) => write_array(buf, v),
+            Tag::LongArray(v) => write_array(buf, v),
+        }
+    }
+
+    fn payload_size(&self) -> usize {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) | Tag::Float(_) => 4,
+            Tag::Long(_) | Tag::Double(_) => 8,
+            Tag::ByteArray(v) => 4 + v.len(),
+            Tag::String(v) => string_size(v),
+            Tag::List(list) => 5 + list.items.iter().map(Tag::payload_size).sum::<usize>(),
+            Tag::Compound(compound) => {
+                let entries = compound
+                    .iter()
+                    .map(|(name, tag)| 1 + string_size(name) + tag.payload_size());
+
+                entries.sum::<usize>() + 1
+            }
+            Tag::IntArray(v) => 4 + v.len() * 4,
+            Tag::LongArray(v) => 4 + v.len() * 8,
+        }
+    }
+
+    /// `Some` if this is a string tag
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(str) => Some(str),
+            _ => None,
+        }
+    }
+
+    /// `Some` if this is a compound tag
+    pub fn as_compound(&self) -> Option<&Compound> {
+        match self {
+            Tag::Compound(compound) => Some(compound),
+            _ => None,
+        }
+    }
+
+    /// `Some` if this is a list tag
+    pub fn as_list(&self) -> Option<&ListTag> {
+        match self {
+            Tag::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    /// value of any integer tag (byte, short, int, long) widened to an i64
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Tag::Byte(v) => Some(v.into()),
+            Tag::Short(v) => Some(v.into()),
+            Tag::Int(v) => Some(v.into()),
+            Tag::Long(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ListTag {
+    /// Creates a list from `items`.
+    ///
+    /// Returns `None` if the items are not all of the same type
+    pub fn new(items: Vec<Tag>) -> Option<Self> {
+        let element = items.first().map(Tag::id).unwrap_or(END);
+
+        items
+            .iter()
+            .all(|tag| tag.id() == element)
+            .then_some(Self { element, items })
+    }
+
+    /// NBT type id of the elements, `0` (TAG_End) for lists created empty
+    pub fn element_id(&self) -> u8 {
+        self.element
+    }
+
+    /// items of this list
+    pub fn items(&self) -> &[Tag] {
+        &self.items
+    }
+}
+
+impl Compound {
+    /// Creates an empty compound
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// value of the entry called `name`
+    pub fn get(&self, name: &str) -> Option<&Tag> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, tag)| tag)
+    }
+
+    /// Sets the entry `name` to `tag`, keeping its
+    /// position if it was already present
+    pub fn insert(&mut self, name: impl Into<String>, tag: impl Into<Tag>) {
+        let name = name.into();
+        let tag = tag.into();
+
+        match self.0.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, old)) => *old = tag,
+            None => self.0.push((name, tag)),
+        }
+    }
+
+    /// Builder-style [`Self::insert`]
+    pub fn with(mut self, name: impl Into<String>, tag: impl Into<Tag>) -> Self {
+        self.insert(name, tag);
+        self
+    }
+
+    /// removes the entry called `name`, returning its value
+    pub fn remove(&mut self, name: &str) -> Option<Tag> {
+        let position = self.0.iter().position(|(n, _)| n == name)?;
+        Some(self.0.remove(position).1)
+    }
+
+    /// entries in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Tag)> {
+        self.0.iter().map(|(name, tag)| (name.as_str(), tag))
+    }
+
+    /// number of entries
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// true if the compound has no entries
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+macro_rules! impl_from {
+    ($($type:ty => $variant:ident),*) => {
+        $(impl From<$type> for Tag {
+            fn from(value: $type) -> Self {
+                Tag::$variant(value.into())
+            }
+        })*
+    };
+}
+
+impl_from!(
+    i8 => Byte, i16 => Short, i32 => Int, i64 => Long,
+    f32 => Float, f64 => Double, String => String, &str => String,
+    ListTag => List, Compound => Compound,
+    Vec<i8> => ByteArray, Vec<i32> => IntArray, Vec<i64> => LongArray
+);
+
+impl From<bool> for Tag {
+    fn from(value: bool) -> Self {
+        Tag::Byte(value.into())
+    }
+}
+
+/// Root tag of a network NBT, which may be absent (encoded as TAG_End).
+///
+/// Its [`Serialize`] and [`Deserialize`] implementations use the nameless
+/// root format introduced with 1.20.2, while the versioned implementations
+/// also handle the (always empty) root name of earlier versions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Nbt(pub Option<Tag>);
+
+impl Nbt {
+    fn read(mut buffer: impl Buf, named: bool) -> Result<Self, DeError> {
+        let id = u8::deserialize(&mut buffer)?;
+        if id == END {
+            return Ok(Self(None));
+        }
+
+        if named {
+            read_string(&mut buffer)?;
+        }
+
+        Tag::read_payload(id, &mut buffer, 0).map(|tag| Self(Some(tag)))
+    }
+
+    /// writes `tag` as a root tag, without taking ownership of it
+    pub(crate) fn write_root(tag: Option<&Tag>, mut buf: impl BufMut, named: bool) {
+        let Some(tag) = tag else {
+            return END.serialize(buf);
+        };
+
+        tag.id().serialize(&mut buf);
+        if named {
+            write_string(&mut buf, "");
+        }
+
+        tag.write_payload(&mut buf);
+    }
+
+    /// size of [`Self::write_root`]
+    pub(crate) fn root_size(tag: Option<&Tag>, named: bool) -> usize {
+        match tag {
+            Some(tag) if named => 1 + string_size("") + tag.payload_size(),
+            Some(tag) => 1 + tag.payload_size(),
+            None => 1,
+        }
+    }
+
+    pub(crate) fn named(version: ProtocolVersion) -> bool {
+        version < ProtocolVersion::V1_20_2
+    }
+}
+
+impl From<Compound> for Nbt {
+    fn from(value: Compound) -> Self {
+        Self(Some(value.into()))
+    }
+}
+
+impl Serialize for Nbt {
+    fn serialize(&self, buf: impl BufMut) {
+        Nbt::write_root(self.0.as_ref(), buf, false)
+    }
+
+    fn size(&self) -> usize {
+        Nbt::root_size(self.0.as_ref(), false)
+    }
+}
+
+impl Deserialize for Nbt {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        Nbt::read(buffer, false)
+    }
+}
+
+impl VersionedSerialize for Nbt {
+    fn serialize_versioned(&self, buf: impl BufMut, version: ProtocolVersion) {
+        Nbt::write_root(self.0.as_ref(), buf, Nbt::named(version))
+    }
+
+    fn size_versioned(&self, version: ProtocolVersion) -> usize {
+        Nbt::root_size(self.0.as_ref(), Nbt::named(version))
+    }
+}
+
+impl VersionedDeserialize for Nbt {
+    fn deserialize_versioned(buffer: impl Buf, version: ProtocolVersion) -> Result<Self, DeError> {
+        Nbt::read(buffer, Nbt::named(version))
+    }
+}
@@ -0,0 +1,66 @@
+use serde_json::Value;
+
+use super::{Compound, ListTag, Tag};
+
+impl Tag {
+    /// Converts a JSON value the way the game reads data pack files:
+    /// booleans become bytes, integers ints (or longs if they don't
+    /// fit), other numbers doubles. Lists mixing types have their
+    /// elements wrapped into compounds with an empty key.
+    /// Returns `None` for null values, which are left out of compounds
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let tag = match value {
+            Value::Null => return None,
+            Value::Bool(value) => Tag::from(*value),
+            Value::Number(number) => match number.as_i64() {
+                Some(int) => match i32::try_from(int) {
+                    Ok(int) => Tag::Int(int),
+                    Err(_) => Tag::Long(int),
+                },
+                None => Tag::Double(number.as_f64().unwrap_or_default()),
+            },
+            Value::String(value) => Tag::String(value.clone()),
+            Value::Array(values) => Tag::List(list(values.iter().filter_map(Tag::from_json))),
+            Value::Object(object) => Tag::Compound(
+                object
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.clone(), Tag::from_json(value)?)))
+                    .fold(Compound::new(), |compound, (name, tag)| {
+                        compound.with(name, tag)
+                    }),
+            ),
+        };
+
+        Some(tag)
+    }
+}
+
+fn list(items: impl Iterator<Item = Tag>) -> ListTag {
+    let items: Vec<Tag> = items.collect();
+    let same = |items: &[Tag]| items.iter().all(|tag| tag.id() == items[0].id());
+    let numeric = |tag: &Tag| matches!(tag, Tag::Int(_) | Tag::Long(_) | Tag::Double(_));
+
+    let items = match !same(&items) && items.iter().all(numeric) {
+        true => items.iter().map(|tag| Tag::Double(as_f64(tag))).collect(),
+        false => items,
+    };
+
+    let items = match same(&items) {
+        true => items,
+        false => items
+            .into_iter()
+            .map(|tag| Tag::Compound(Compound::new().with("", tag)))
+            .collect(),
+    };
+
+    ListTag::new(items).expect("items of the same type")
+}
+
+fn as_f64(tag: &Tag) -> f64 {
+    match *tag {
+        Tag::Int(value) => value.into(),
+        Tag::Long(value) => value as f64,
+        Tag::Double(value) => value,
+        _ => 0.0,
+    }
+}
@@ -0,0 +1,79 @@
+// Java's "modified UTF-8", used by NBT strings.
+//
+// It differs from UTF-8 in that NUL is encoded with two bytes
+// and supplementary characters are encoded as a surrogate pair
+// of three bytes each
+
+use bytes::BufMut;
+
+use crate::DeError;
+
+fn char_size(c: char) -> usize {
+    match c as u32 {
+        0 => 2,
+        0x01..=0x7F => 1,
+        0x80..=0x7FF => 2,
+        0x800..=0xFFFF => 3,
+        _ => 6,
+    }
+}
+
+/// size in bytes of `str` once encoded
+pub fn size(str: &str) -> usize {
+    str.chars().map(char_size).sum()
+}
+
+fn put_unit(mut buf: impl BufMut, unit: u16) {
+    match unit {
+        0x01..=0x7F => buf.put_u8(unit as u8),
+        0x00 | 0x80..=0x7FF => {
+            buf.put_u8(0xC0 | (unit >> 6) as u8);
+            buf.put_u8(0x80 | (unit & 0x3F) as u8);
+        }
+        _ => {
+            buf.put_u8(0xE0 | (unit >> 12) as u8);
+            buf.put_u8(0x80 | ((unit >> 6) & 0x3F) as u8);
+            buf.put_u8(0x80 | (unit & 0x3F) as u8);
+        }
+    }
+}
+
+/// writes `str` encoded, without any length prefix
+pub fn write(mut buf: impl BufMut, str: &str) {
+    str.encode_utf16().for_each(|unit| put_unit(&mut buf, unit))
+}
+
+/// decodes a whole buffer of modified UTF-8
+pub fn decode(bytes: &[u8]) -> Result<String, DeError> {
+    // fast path: plain ascii is the same in both encodings
+    if bytes.iter().all(|&b| (0x01..0x80).contains(&b)) {
+        return Ok(std::str::from_utf8(bytes)?.to_owned());
+    }
+
+    let continuation = |b: Option<&u8>| {
+        b.filter(|&&b| b & 0xC0 == 0x80)
+            .map(|&b| (b & 0x3F) as u16)
+            .ok_or(DeError::InvalidData)
+    };
+
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+
+    while let Some(&byte) = iter.next() {
+        let unit = match byte {
+            0x01..=0x7F => byte as u16,
+            0xC0..=0xDF => ((byte & 0x1F) as u16) << 6 | continuation(iter.next())?,
+            0xE0..=0xEF => {
+                let high = continuation(iter.next())?;
+                let low = continuation(iter.next())?;
+
+                ((byte & 0x0F) as u16) << 12 | high << 6 | low
+            }
+            _ => return Err(DeError::InvalidData),
+        };
+
+        units.push(unit);
+    }
+
+    String::from_utf16(&units).map_err(|_| DeError::InvalidData)
+}
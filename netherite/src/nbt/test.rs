@@ -0,0 +1,128 @@
+use bytes::BytesMut;
+
+use super::{mutf8, Compound, ListTag, Nbt, Tag};
+use crate::{
+    assert_serialization,
+    encoding::{
+        deserialize_bytes,
+        versioned::{Versioned, VersionedDeserialize},
+    },
+    protocol::ProtocolVersion,
+    DeError, Serialize,
+};
+
+#[test]
+fn serialize_compound() {
+    let nbt = Nbt::from(Compound::new().with("a", 1i8));
+    assert_serialization!(nbt => &[0x0a, 0x01, 0x00, 0x01, b'a', 0x01, 0x00]);
+}
+
+#[test]
+fn serialize_named_root() {
+    let nbt = Versioned::new(Nbt::from(Compound::new()), ProtocolVersion::V1_20);
+    assert_serialization!(nbt => &[0x0a, 0x00, 0x00, 0x00]);
+}
+
+#[test]
+fn serialize_empty() {
+    assert_serialization!(Nbt(None) => &[0x00]);
+}
+
+#[test]
+fn deserialize_named_root() {
+    let data = [
+        0x0a, 0x00, 0x02, b'h', b'i', 0x03, 0x00, 0x01, b'x', 0, 0, 0, 7, 0x00,
+    ];
+    let nbt = Nbt::deserialize_versioned(&data[..], ProtocolVersion::V1_19_4).unwrap();
+
+    let expected = Nbt::from(Compound::new().with("x", 7));
+    assert_eq!(nbt, expected);
+}
+
+#[test]
+fn roundtrip() {
+    let list = ListTag::new(vec![Tag::from("a"), Tag::from("b")]).unwrap();
+    let compound = Compound::new()
+        .with("list", list)
+        .with("empty", ListTag::new(vec![]).unwrap())
+        .with("long", Vec::<i64>::from([1, -1]))
+        .with("double", 0.5f64)
+        .with("nested", Compound::new().with("unicode", "\0é😀"));
+
+    let nbt = Nbt::from(compound);
+
+    let mut buf = BytesMut::new();
+    nbt.serialize(&mut buf);
+    assert_eq!(buf.len(), nbt.size());
+
+    let res: Nbt = deserialize_bytes(buf).unwrap();
+    assert_eq!(res, nbt);
+}
+
+#[test]
+fn mixed_list() {
+    assert!(ListTag::new(vec![Tag::from(1i8), Tag::from(1i16)]).is_none());
+}
+
+#[test]
+fn modified_utf8() {
+    let mut buf = vec![];
+    mutf8::write(&mut buf, "\0😀");
+
+    assert_eq!(buf, [0xc0, 0x80, 0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80]);
+    assert_eq!(mutf8::size("\0😀"), buf.len());
+    assert_eq!(mutf8::decode(&buf).unwrap(), "\0😀");
+}
+
+#[test]
+fn max_depth() {
+    // a list of lists nested way deeper than allowed
+    let mut data = vec![0x09];
+    for _ in 0..600 {
+        data.extend([0x09, 0x00, 0x00, 0x00, 0x01]);
+    }
+
+    let res = deserialize_bytes::<Nbt>(&data[..]);
+    assert!(matches!(res, Err(DeError::InvalidData)));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn from_json() {
+    let json = serde_json::json!({
+        "flag": true,
+        "int": 3,
+        "long": 1i64 << 40,
+        "double": 0.5,
+        "missing": null,
+        "numbers": [1, 0.5],
+        "mixed": [1, "a"],
+        "empty": [],
+    });
+
+    let Some(Tag::Compound(compound)) = Tag::from_json(&json) else {
+        panic!("expected a compound")
+    };
+
+    assert_eq!(compound.get("flag"), Some(&Tag::Byte(1)));
+    assert_eq!(compound.get("int"), Some(&Tag::Int(3)));
+    assert_eq!(compound.get("long"), Some(&Tag::Long(1 << 40)));
+    assert_eq!(compound.get("double"), Some(&Tag::Double(0.5)));
+    assert_eq!(compound.get("missing"), None);
+
+    let numbers = ListTag::new(vec![Tag::Double(1.0), Tag::Double(0.5)]).unwrap();
+    assert_eq!(compound.get("numbers"), Some(&Tag::List(numbers)));
+
+    let mixed = ListTag::new(vec![
+        Compound::new().with("", 1).into(),
+        Compound::new().with("", "a").into(),
+    ]);
+    assert_eq!(compound.get("mixed"), Some(&Tag::List(mixed.unwrap())));
+    assert_eq!(
+        compound
+            .get("empty")
+            .and_then(Tag::as_list)
+            .map(ListTag::element_id),
+        Some(0)
+    );
+}
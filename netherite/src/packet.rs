@@ -1,8 +1,22 @@
 use crate::encoding::serialize_bytes;
+use crate::encoding::versioned::VersionedDeserialize;
 use crate::encoding::{deserialize_bytes, packetid::PacketId};
+use crate::protocol::ProtocolVersion;
 use crate::{DeError, Deserialize, Serialize};
 use bytes::Bytes;
 
+/// configuration state packets, with the ids of 1.20.5+
+pub mod configuration;
+/// handshake packet, sent by the client when connecting
+pub mod handshake;
+/// login state packets
+pub mod login;
+/// status state packets, used by the server list ping
+pub mod status;
+
+#[cfg(test)]
+mod test;
+
 #[derive(Debug, Clone)]
 /// A Minecraft frame unit composing
 /// of a packet_id and byte data
@@ -38,6 +52,16 @@ impl RawPacket {
     {
         self.is::<T>().then(|| self.deserialize_unchecked())
     }
+
+    /// Like [`Self::deserialize`], for packets whose
+    /// layout depends on the protocol `version`
+    pub fn deserialize_versioned<T>(&self, version: ProtocolVersion) -> Option<Result<T, DeError>>
+    where
+        T: VersionedDeserialize + PacketId,
+    {
+        self.is::<T>()
+            .then(|| T::deserialize_versioned(self.data.clone(), version))
+    }
 }
 
 impl<T: Serialize + PacketId> From<T> for RawPacket {
@@ -0,0 +1,161 @@
+use bytes::Bytes;
+
+use crate::{
+    encoding::{identifier::Identifier, str::Str, varint::VarInt},
+    nbt::Nbt,
+    Deserialize, PacketId, Serialize,
+};
+
+/// Asks the client for a cookie stored by a server, answered with a [`CookieResponse`] (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x00)]
+pub struct CookieRequest {
+    /// key of the cookie
+    pub key: Identifier,
+}
+
+/// Answer to a [`CookieRequest`] (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x01)]
+pub struct CookieResponse {
+    /// key of the cookie
+    pub key: Identifier,
+    /// content of the cookie, `None` if the client doesn't have it
+    pub payload: Option<Bytes>,
+}
+
+/// Stores a cookie on the client, kept across transfers (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x0a)]
+pub struct StoreCookie {
+    /// key of the cookie
+    pub key: Identifier,
+    /// content of the cookie, up to [`MAX_SIZE`](crate::cookie::MAX_SIZE) bytes
+    pub payload: Bytes,
+}
+
+/// Makes the client connect to another server, with the
+/// [`Intent::Transfer`](super::handshake::Intent::Transfer) (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x0b)]
+pub struct Transfer {
+    /// host of the server
+    pub host: Str,
+    /// port of the server
+    pub port: VarInt,
+}
+
+/// Ends the configuration, switching to the play state once acknowledged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x03)]
+pub struct FinishConfiguration {}
+
+/// Acknowledges the [`FinishConfiguration`], switching to the play state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x03)]
+pub struct AcknowledgeFinishConfiguration {}
+
+/// Entry of a synchronized registry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    /// id of the entry
+    pub id: Identifier,
+    /// Element of the entry, `None` if the client
+    /// already knows it from a [`KnownPack`]
+    pub data: Option<Nbt>,
+}
+
+/// Entries of a synchronized registry, like `minecraft:dimension_type`.
+/// Their network ids are the positions in the list (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x07)]
+pub struct RegistryData {
+    /// id of the registry
+    pub registry: Identifier,
+    /// entries of the registry, in network id order
+    pub entries: Vec<RegistryEntry>,
+}
+
+/// Every synchronized registry in a single compound keyed by registry id,
+/// each with its `type` and the `value` list of its entries, named and
+/// numbered (1.20.2 to 1.20.4, replaced by [`RegistryData`])
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x05)]
+pub struct RegistryCodec {
+    /// compound of the registries
+    pub codec: Nbt,
+}
+
+/// Enabled feature flags, like `minecraft:vanilla`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x0c)]
+pub struct FeatureFlags {
+    /// ids of the enabled features
+    pub flags: Vec<Identifier>,
+}
+
+/// Tag of a registry, with the network ids of its entries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagEntries {
+    /// name of the tag
+    pub name: Identifier,
+    /// network ids of the entries
+    pub entries: Vec<VarInt>,
+}
+
+/// Tags of a registry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistryTags {
+    /// id of the registry
+    pub registry: Identifier,
+    /// tags of the registry
+    pub tags: Vec<TagEntries>,
+}
+
+/// Sets the tags of registries, replacing the previous ones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x0d)]
+pub struct UpdateTags {
+    /// tags by registry
+    pub registries: Vec<RegistryTags>,
+}
+
+/// Data pack shipped with the game, whose registry
+/// entries don't have to be sent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownPack {
+    /// namespace of the pack, `minecraft` for vanilla
+    pub namespace: Str,
+    /// id of the pack, `core` for vanilla
+    pub id: Str,
+    /// version of the pack, the game version for vanilla
+    pub version: Str,
+}
+
+impl KnownPack {
+    /// vanilla data of the game `version`, like `1.21.5`
+    pub fn core(version: impl Into<String>) -> Self {
+        Self {
+            namespace: Str::from_static("minecraft"),
+            id: Str::from_static("core"),
+            version: version.into().into(),
+        }
+    }
+}
+
+/// Packs the server's registries come from, answered
+/// with the [`ServerboundKnownPacks`] known to the client (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x0e)]
+pub struct ClientboundKnownPacks {
+    /// packs of the server
+    pub packs: Vec<KnownPack>,
+}
+
+/// Packs of the [`ClientboundKnownPacks`] the client also has (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x07)]
+pub struct ServerboundKnownPacks {
+    /// packs known to the client
+    pub packs: Vec<KnownPack>,
+}
@@ -0,0 +1,71 @@
+use bytes::{Buf, BufMut};
+
+use crate::{
+    encoding::{str::Str, varint::VarInt},
+    protocol::{ProtocolVersion, State},
+    DeError, Deserialize, PacketId, Serialize,
+};
+
+/// State the client wants to switch to after the [`Handshake`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intent {
+    /// server list ping
+    Status,
+    /// joining the server
+    Login,
+    /// joining after being transferred from another server (1.20.5+)
+    Transfer,
+}
+
+impl Intent {
+    /// state of the connection after the handshake
+    pub fn state(&self) -> State {
+        match self {
+            Intent::Status => State::Status,
+            Intent::Login | Intent::Transfer => State::Login,
+        }
+    }
+
+    fn id(&self) -> i32 {
+        match self {
+            Intent::Status => 1,
+            Intent::Login => 2,
+            Intent::Transfer => 3,
+        }
+    }
+}
+
+impl Serialize for Intent {
+    fn serialize(&self, buf: impl BufMut) {
+        VarInt(self.id()).serialize(buf)
+    }
+
+    fn size(&self) -> usize {
+        VarInt(self.id()).size()
+    }
+}
+
+impl Deserialize for Intent {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        match VarInt::deserialize(buffer)?.0 {
+            1 => Ok(Intent::Status),
+            2 => Ok(Intent::Login),
+            3 => Ok(Intent::Transfer),
+            _ => Err(DeError::InvalidData),
+        }
+    }
+}
+
+/// First packet sent by the client, with the same layout in every version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x00)]
+pub struct Handshake {
+    /// protocol version of the client
+    pub protocol_version: ProtocolVersion,
+    /// address used by the client to connect
+    pub server_address: Str,
+    /// port used by the client to connect
+    pub server_port: u16,
+    /// state to switch to
+    pub intent: Intent,
+}
@@ -0,0 +1,419 @@
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::{
+    encoding::{
+        identifier::Identifier,
+        packetid::PacketId,
+        remaining::Remaining,
+        str::Str,
+        varint::VarInt,
+        versioned::{VersionedDeserialize, VersionedSerialize},
+    },
+    protocol::ProtocolVersion as V,
+    DeError, Deserialize, Serialize,
+};
+
+/// Kicks the client during login
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, crate::PacketId)]
+#[packet(id = 0x00)]
+pub struct Disconnect {
+    /// JSON text component, even in versions using NBT for chat
+    pub reason: Str,
+}
+
+/// Asks the client to enable encryption
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptionRequest {
+    /// empty since 1.7
+    pub server_id: Str,
+    /// DER encoded public key of the server
+    pub public_key: Bytes,
+    /// random bytes the client has to encrypt with the public key
+    pub verify_token: Bytes,
+    /// whether the client should authenticate with Mojang (1.20.5+)
+    pub should_authenticate: bool,
+}
+
+impl PacketId for EncryptionRequest {
+    const ID: i32 = 0x01;
+}
+
+fn should_authenticate(version: V) -> bool {
+    version >= V::V1_20_5
+}
+
+impl VersionedSerialize for EncryptionRequest {
+    fn serialize_versioned(&self, mut buf: impl BufMut, version: V) {
+        self.server_id.serialize(&mut buf);
+        self.public_key.serialize(&mut buf);
+        self.verify_token.serialize(&mut buf);
+
+        if should_authenticate(version) {
+            self.should_authenticate.serialize(buf);
+        }
+    }
+
+    fn size_versioned(&self, version: V) -> usize {
+        self.server_id.size()
+            + self.public_key.size()
+            + self.verify_token.size()
+            + should_authenticate(version) as usize
+    }
+}
+
+impl VersionedDeserialize for EncryptionRequest {
+    fn deserialize_versioned(mut buffer: impl Buf, version: V) -> Result<Self, DeError> {
+        Ok(Self {
+            server_id: Deserialize::deserialize(&mut buffer)?,
+            public_key: Deserialize::deserialize(&mut buffer)?,
+            verify_token: Deserialize::deserialize(&mut buffer)?,
+            should_authenticate: match should_authenticate(version) {
+                true => Deserialize::deserialize(buffer)?,
+                false => true,
+            },
+        })
+    }
+}
+
+/// Enables compression of packets bigger than the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, crate::PacketId)]
+#[packet(id = 0x03)]
+pub struct SetCompression {
+    /// minimum size of a compressed packet, negative to disable compression
+    pub threshold: VarInt,
+}
+
+/// Player profile property, like the skin textures
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Property {
+    /// property name
+    pub name: Str,
+    /// base64 encoded value
+    pub value: Str,
+    /// signature of the value, from Mojang
+    pub signature: Option<Str>,
+}
+
+/// Ends the login, sent after encryption and compression
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginSuccess {
+    /// uuid of the player
+    pub uuid: u128,
+    /// name of the player
+    pub username: Str,
+    /// profile properties (1.19+)
+    pub properties: Vec<Property>,
+    /// whether the client should disconnect on invalid packets (1.20.5 to 1.21.1)
+    pub strict_error_handling: bool,
+}
+
+impl PacketId for LoginSuccess {
+    const ID: i32 = 0x02;
+}
+
+/// uuid was a hyphenated string before 1.16
+fn uuid_string(version: V) -> bool {
+    version < V::V1_16
+}
+
+fn properties(version: V) -> bool {
+    version >= V::V1_19
+}
+
+fn strict_error_handling(version: V) -> bool {
+    version >= V::V1_20_5 && version < V::V1_21_2
+}
+
+/// hyphenated representation of `uuid`
+pub(crate) fn hyphenated(uuid: u128) -> String {
+    let hex = format!("{uuid:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// parses a uuid with or without hyphens
+pub(crate) fn parse_uuid(uuid: &str) -> Option<u128> {
+    let hex: String = uuid.chars().filter(|&c| c != '-').collect();
+    if hex.len() != 32 {
+        return None;
+    }
+
+    u128::from_str_radix(&hex, 16).ok()
+}
+
+impl VersionedSerialize for LoginSuccess {
+    fn serialize_versioned(&self, mut buf: impl BufMut, version: V) {
+        match uuid_string(version) {
+            true => hyphenated(self.uuid).as_str().serialize(&mut buf),
+            false => self.uuid.serialize(&mut buf),
+        }
+
+        self.username.serialize(&mut buf);
+
+        if properties(version) {
+            self.properties.serialize(&mut buf);
+        }
+
+        if strict_error_handling(version) {
+            self.strict_error_handling.serialize(buf);
+        }
+    }
+
+    fn size_versioned(&self, version: V) -> usize {
+        let uuid = match uuid_string(version) {
+            true => hyphenated(self.uuid).as_str().size(),
+            false => self.uuid.size(),
+        };
+
+        let properties = match properties(version) {
+            true => self.properties.size(),
+            false => 0,
+        };
+
+        uuid + self.username.size() + properties + strict_error_handling(version) as usize
+    }
+}
+
+impl VersionedDeserialize for LoginSuccess {
+    fn deserialize_versioned(mut buffer: impl Buf, version: V) -> Result<Self, DeError> {
+        let uuid = match uuid_string(version) {
+            true => parse_uuid(&Str::deserialize(&mut buffer)?).ok_or(DeError::InvalidData)?,
+            false => u128::deserialize(&mut buffer)?,
+        };
+
+        Ok(Self {
+            uuid,
+            username: Deserialize::deserialize(&mut buffer)?,
+            properties: match properties(version) {
+                true => Deserialize::deserialize(&mut buffer)?,
+                false => vec![],
+            },
+            strict_error_handling: match strict_error_handling(version) {
+                true => Deserialize::deserialize(buffer)?,
+                false => false,
+            },
+        })
+    }
+}
+
+/// Public key of the player, sent when logging in with 1.19 to 1.19.2
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerKey {
+    /// expiration time, in milliseconds since the epoch
+    pub expires_at: i64,
+    /// DER encoded public key
+    pub public_key: Bytes,
+    /// signature of the key, from Mojang
+    pub signature: Bytes,
+}
+
+/// First packet sent by the client during login
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginStart {
+    /// name of the player
+    pub username: Str,
+    /// public key of the player (1.19 to 1.19.2)
+    pub key: Option<PlayerKey>,
+    /// uuid of the player, optional from 1.19.1 to 1.20.1
+    /// and always present since 1.20.2
+    pub uuid: Option<u128>,
+}
+
+impl PacketId for LoginStart {
+    const ID: i32 = 0x00;
+}
+
+fn player_key(version: V) -> bool {
+    version >= V::V1_19 && version < V::V1_19_3
+}
+
+enum UuidField {
+    Absent,
+    Optional,
+    Required,
+}
+
+fn uuid_field(version: V) -> UuidField {
+    match version {
+        v if v < V::V1_19_1 => UuidField::Absent,
+        v if v < V::V1_20_2 => UuidField::Optional,
+        _ => UuidField::Required,
+    }
+}
+
+impl VersionedSerialize for LoginStart {
+    fn serialize_versioned(&self, mut buf: impl BufMut, version: V) {
+        self.username.serialize(&mut buf);
+
+        if player_key(version) {
+            self.key.serialize(&mut buf);
+        }
+
+        match uuid_field(version) {
+            UuidField::Absent => {}
+            UuidField::Optional => self.uuid.serialize(buf),
+            UuidField::Required => self.uuid.unwrap_or_default().serialize(buf),
+        }
+    }
+
+    fn size_versioned(&self, version: V) -> usize {
+        let key = match player_key(version) {
+            true => self.key.size(),
+            false => 0,
+        };
+
+        let uuid = match uuid_field(version) {
+            UuidField::Absent => 0,
+            UuidField::Optional => self.uuid.size(),
+            UuidField::Required => 16,
+        };
+
+        self.username.size() + key + uuid
+    }
+}
+
+impl VersionedDeserialize for LoginStart {
+    fn deserialize_versioned(mut buffer: impl Buf, version: V) -> Result<Self, DeError> {
+        Ok(Self {
+            username: Deserialize::deserialize(&mut buffer)?,
+            key: match player_key(version) {
+                true => Deserialize::deserialize(&mut buffer)?,
+                false => None,
+            },
+            uuid: match uuid_field(version) {
+                UuidField::Absent => None,
+                UuidField::Optional => Deserialize::deserialize(buffer)?,
+                UuidField::Required => Some(Deserialize::deserialize(buffer)?),
+            },
+        })
+    }
+}
+
+/// Proof that the client owns the private key, in the [`EncryptionResponse`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verification {
+    /// verify token of the request, encrypted with the public key of the server
+    Token(Bytes),
+    /// signature of the verify token with the player key (1.19 to 1.19.2)
+    Signature {
+        /// salt of the signature
+        salt: i64,
+        /// signed verify token and salt
+        signature: Bytes,
+    },
+}
+
+/// Shared secret chosen by the client, encrypted with the public key of the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptionResponse {
+    /// encrypted shared secret
+    pub shared_secret: Bytes,
+    /// proof of the encryption
+    pub verification: Verification,
+}
+
+impl PacketId for EncryptionResponse {
+    const ID: i32 = 0x01;
+}
+
+impl VersionedSerialize for EncryptionResponse {
+    fn serialize_versioned(&self, mut buf: impl BufMut, version: V) {
+        self.shared_secret.serialize(&mut buf);
+
+        match &self.verification {
+            Verification::Token(token) => {
+                if player_key(version) {
+                    true.serialize(&mut buf);
+                }
+
+                token.serialize(buf);
+            }
+            // not representable outside of 1.19 to 1.19.2
+            Verification::Signature { salt, signature } => {
+                false.serialize(&mut buf);
+                salt.serialize(&mut buf);
+                signature.serialize(buf);
+            }
+        }
+    }
+
+    fn size_versioned(&self, version: V) -> usize {
+        let verification = match &self.verification {
+            Verification::Token(token) => player_key(version) as usize + token.size(),
+            Verification::Signature { signature, .. } => 1 + 8 + signature.size(),
+        };
+
+        self.shared_secret.size() + verification
+    }
+}
+
+impl VersionedDeserialize for EncryptionResponse {
+    fn deserialize_versioned(mut buffer: impl Buf, version: V) -> Result<Self, DeError> {
+        let shared_secret = Deserialize::deserialize(&mut buffer)?;
+        let has_token = !player_key(version) || bool::deserialize(&mut buffer)?;
+
+        let verification = match has_token {
+            true => Verification::Token(Deserialize::deserialize(buffer)?),
+            false => Verification::Signature {
+                salt: Deserialize::deserialize(&mut buffer)?,
+                signature: Deserialize::deserialize(buffer)?,
+            },
+        };
+
+        Ok(Self {
+            shared_secret,
+            verification,
+        })
+    }
+}
+
+/// Acknowledges the [`LoginSuccess`], switching to the configuration state (1.20.2+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, crate::PacketId)]
+#[packet(id = 0x03)]
+pub struct LoginAcknowledged {}
+
+/// Custom query of the server, answered with a [`LoginPluginResponse`] (1.13+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, crate::PacketId)]
+#[packet(id = 0x04)]
+pub struct LoginPluginRequest {
+    /// id of the query, repeated in the response
+    pub message_id: VarInt,
+    /// channel of the query
+    pub channel: Str,
+    /// content of the query
+    pub data: Remaining,
+}
+
+/// Answer to a [`LoginPluginRequest`] (1.13+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, crate::PacketId)]
+#[packet(id = 0x02)]
+pub struct LoginPluginResponse {
+    /// id of the query
+    pub message_id: VarInt,
+    /// content of the answer, `None` if the client didn't understand the query
+    pub data: Option<Remaining>,
+}
+
+/// Asks the client for a cookie stored by a server, answered with a [`CookieResponse`] (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, crate::PacketId)]
+#[packet(id = 0x05)]
+pub struct CookieRequest {
+    /// key of the cookie
+    pub key: Identifier,
+}
+
+/// Answer to a [`CookieRequest`] (1.20.5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, crate::PacketId)]
+#[packet(id = 0x04)]
+pub struct CookieResponse {
+    /// key of the cookie
+    pub key: Identifier,
+    /// content of the cookie, `None` if the client doesn't have it
+    pub payload: Option<Bytes>,
+}
@@ -0,0 +1,30 @@
+use crate::{encoding::str::Str, Deserialize, PacketId, Serialize};
+
+/// Asks the server for its [`StatusResponse`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x00)]
+pub struct StatusRequest {}
+
+/// Status of the server shown in the server list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x00)]
+pub struct StatusResponse {
+    /// JSON encoded status (version, players, description, favicon...)
+    pub response: Str,
+}
+
+/// Sent by the client to measure latency
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x01)]
+pub struct PingRequest {
+    /// any value, sent back in the [`PongResponse`]
+    pub payload: i64,
+}
+
+/// Answer to a [`PingRequest`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, PacketId)]
+#[packet(id = 0x01)]
+pub struct PongResponse {
+    /// payload of the ping request
+    pub payload: i64,
+}
@@ -0,0 +1,154 @@
+use bytes::Bytes;
+
+use super::{
+    handshake::{Handshake, Intent},
+    login::{
+        hyphenated, parse_uuid, EncryptionRequest, EncryptionResponse, LoginStart, LoginSuccess,
+        PlayerKey, Property, Verification,
+    },
+    status::PingRequest,
+    RawPacket,
+};
+use crate::{
+    assert_serialization,
+    encoding::{
+        str::Str,
+        versioned::{Versioned, VersionedDeserialize, VersionedSerialize},
+    },
+    protocol::{ProtocolVersion as V, State},
+};
+
+const UUID: u128 = 0x069a79f444e94726a5befca90e38aaf5;
+
+fn roundtrip<T>(packet: &T, version: V) -> T
+where
+    T: VersionedSerialize + VersionedDeserialize,
+{
+    let mut buf = vec![];
+    packet.serialize_versioned(&mut buf, version);
+    assert_eq!(
+        buf.len(),
+        packet.size_versioned(version),
+        "wrong size estimate"
+    );
+
+    T::deserialize_versioned(&buf[..], version).unwrap()
+}
+
+#[test]
+fn handshake() {
+    let handshake = Handshake {
+        protocol_version: V::V1_20_3,
+        server_address: Str::from_static("localhost"),
+        server_port: 25565,
+        intent: Intent::Login,
+    };
+
+    assert_serialization!(&handshake => b"\xfd\x05\x09localhost\x63\xdd\x02");
+
+    let packet = RawPacket::from(handshake.clone());
+    assert_eq!(
+        packet.deserialize::<Handshake>().unwrap().unwrap(),
+        handshake
+    );
+    assert_eq!(Intent::Transfer.state(), State::Login);
+}
+
+#[test]
+fn status() {
+    let packet = RawPacket::from(PingRequest { payload: 1 });
+    assert_eq!(packet.packet_id, 0x01);
+    assert_eq!(packet.data.as_ref(), [0, 0, 0, 0, 0, 0, 0, 1]);
+}
+
+#[test]
+fn uuid() {
+    let uuid = hyphenated(UUID);
+    assert_eq!(uuid, "069a79f4-44e9-4726-a5be-fca90e38aaf5");
+    assert_eq!(parse_uuid(&uuid), Some(UUID));
+    assert_eq!(parse_uuid("069a79f444e94726a5befca90e38aaf5"), Some(UUID));
+    assert_eq!(parse_uuid("069a79f4"), None);
+}
+
+#[test]
+fn login_start() {
+    let login = LoginStart {
+        username: Str::from_static("Notch"),
+        key: Some(PlayerKey {
+            expires_at: 0,
+            public_key: Bytes::from_static(&[1, 2]),
+            signature: Bytes::from_static(&[3]),
+        }),
+        uuid: Some(UUID),
+    };
+
+    assert_serialization!(Versioned::new(&login, V::V1_8) => b"\x05Notch");
+
+    let legacy = roundtrip(&login, V::V1_18);
+    assert_eq!((legacy.key, legacy.uuid), (None, None));
+
+    assert_eq!(roundtrip(&login, V::V1_19_1), login);
+
+    let modern = roundtrip(&login, V::V1_20_2);
+    assert_eq!((modern.key, modern.uuid), (None, Some(UUID)));
+
+    let packet = RawPacket::from(Versioned::new(&login, V::V1_20_2));
+    let decoded = packet.deserialize_versioned::<LoginStart>(V::V1_20_2);
+    assert_eq!(decoded.unwrap().unwrap().uuid, Some(UUID));
+}
+
+#[test]
+fn login_success() {
+    let success = LoginSuccess {
+        uuid: UUID,
+        username: Str::from_static("Notch"),
+        properties: vec![Property {
+            name: Str::from_static("textures"),
+            value: Str::from_static("e30="),
+            signature: None,
+        }],
+        strict_error_handling: true,
+    };
+
+    let mut buf = vec![];
+    success.serialize_versioned(&mut buf, V::V1_12_2);
+    assert_eq!(&buf[..37], b"\x24069a79f4-44e9-4726-a5be-fca90e38aaf5");
+
+    let legacy = roundtrip(&success, V::V1_12_2);
+    assert_eq!(legacy.uuid, UUID);
+    assert!(legacy.properties.is_empty());
+
+    assert_eq!(roundtrip(&success, V::V1_20_5), success);
+    assert!(!roundtrip(&success, V::V1_21_2).strict_error_handling);
+}
+
+#[test]
+fn encryption() {
+    let request = EncryptionRequest {
+        server_id: Str::from_static(""),
+        public_key: Bytes::from_static(&[1, 2, 3]),
+        verify_token: Bytes::from_static(&[4, 5, 6, 7]),
+        should_authenticate: false,
+    };
+
+    assert_eq!(roundtrip(&request, V::V1_20_5), request);
+    assert!(roundtrip(&request, V::V1_20_3).should_authenticate);
+
+    let response = EncryptionResponse {
+        shared_secret: Bytes::from_static(&[1; 4]),
+        verification: Verification::Token(Bytes::from_static(&[2; 4])),
+    };
+
+    assert_eq!(roundtrip(&response, V::V1_8), response);
+    assert_eq!(roundtrip(&response, V::V1_19), response);
+
+    let signed = EncryptionResponse {
+        verification: Verification::Signature {
+            salt: 42,
+            signature: Bytes::from_static(&[3; 4]),
+        },
+        ..response
+    };
+
+    assert_eq!(roundtrip(&signed, V::V1_19_1), signed);
+}
@@ -0,0 +1,118 @@
+use std::fmt::Display;
+
+use bytes::{Buf, BufMut};
+
+use crate::{encoding::varint::VarInt, DeError, Deserialize, Serialize};
+
+/// Port used when an address doesn't specify one
+pub const DEFAULT_PORT: u16 = 25565;
+
+/// Protocol version number of a Minecraft release,
+/// as sent by the client in the Handshake packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProtocolVersion(pub i32);
+
+macro_rules! versions {
+    ($($name:ident = $number:literal, $release:literal;)*) => {
+        impl ProtocolVersion {
+            $(
+                #[doc = concat!("Minecraft ", $release)]
+                pub const $name: Self = Self($number);
+            )*
+
+            /// name of the earliest release using this protocol version, if known
+            pub fn release(&self) -> Option<&'static str> {
+                match self.0 {
+                    $($number => Some($release),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+versions! {
+    V1_8 = 47, "1.8";
+    V1_9 = 107, "1.9";
+    V1_12_2 = 340, "1.12.2";
+    V1_13 = 393, "1.13";
+    V1_13_2 = 404, "1.13.2";
+    V1_14 = 477, "1.14";
+    V1_15 = 573, "1.15";
+    V1_16 = 735, "1.16";
+    V1_16_2 = 751, "1.16.2";
+    V1_17 = 755, "1.17";
+    V1_18 = 757, "1.18";
+    V1_19 = 759, "1.19";
+    V1_19_1 = 760, "1.19.1";
+    V1_19_3 = 761, "1.19.3";
+    V1_19_4 = 762, "1.19.4";
+    V1_20 = 763, "1.20";
+    V1_20_2 = 764, "1.20.2";
+    V1_20_3 = 765, "1.20.3";
+    V1_20_5 = 766, "1.20.5";
+    V1_21 = 767, "1.21";
+    V1_21_2 = 768, "1.21.2";
+    V1_21_4 = 769, "1.21.4";
+    V1_21_5 = 770, "1.21.5";
+}
+
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.release() {
+            Some(release) => write!(f, "{} ({release})", self.0),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl Serialize for ProtocolVersion {
+    fn serialize(&self, buf: impl BufMut) {
+        VarInt(self.0).serialize(buf)
+    }
+
+    fn size(&self) -> usize {
+        VarInt(self.0).size()
+    }
+}
+
+impl Deserialize for ProtocolVersion {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        VarInt::deserialize(buffer).map(|VarInt(version)| Self(version))
+    }
+}
+
+/// State of a connection, which determines
+/// the meaning of packet ids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum State {
+    /// first state, waiting for the Handshake packet
+    Handshaking,
+    /// server list ping
+    Status,
+    /// authentication and encryption
+    Login,
+    /// configuration phase, since 1.20.2
+    Configuration,
+    /// in game
+    Play,
+}
+
+/// Direction a packet is travelling in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// sent by the client to the server
+    Serverbound,
+    /// sent by the server to the client
+    Clientbound,
+}
+
+impl Direction {
+    /// the other direction
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Serverbound => Direction::Clientbound,
+            Direction::Clientbound => Direction::Serverbound,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use bytes::{Buf, Bytes, BytesMut};
+
+/// Buf adapter keeping a copy of every byte
+/// consumed from the inner buffer
+pub struct Recorder<B> {
+    inner: B,
+    recorded: BytesMut,
+}
+
+impl<B: Buf> Recorder<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            recorded: BytesMut::new(),
+        }
+    }
+
+    // every byte consumed since the creation of the recorder
+    pub fn finish(self) -> Bytes {
+        self.recorded.freeze()
+    }
+}
+
+impl<B: Buf> Buf for Recorder<B> {
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.inner.chunk()
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        while cnt > 0 {
+            let chunk = self.inner.chunk();
+            let len = chunk.len().min(cnt);
+            assert!(len > 0, "advance out of bounds");
+
+            self.recorded.extend_from_slice(&chunk[..len]);
+            self.inner.advance(len);
+            cnt -= len;
+        }
+    }
+}
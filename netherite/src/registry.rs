@@ -0,0 +1,287 @@
+use std::{any::TypeId, collections::HashMap, str::FromStr, sync::Arc};
+
+use thiserror::Error;
+
+use crate::{
+    encoding::{deserialize_bytes, serialize_bytes},
+    packet::RawPacket,
+    protocol::{Direction, ProtocolVersion, State},
+    DeError, Deserialize, Serialize,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Identifies a packet id table: the same id means different
+/// packets depending on the version, state and direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    /// protocol version
+    pub version: ProtocolVersion,
+    /// state of the connection
+    pub state: State,
+    /// direction of the packet
+    pub direction: Direction,
+}
+
+impl Key {
+    /// creates a key
+    pub fn new(version: ProtocolVersion, state: State, direction: Direction) -> Self {
+        Self {
+            version,
+            state,
+            direction,
+        }
+    }
+}
+
+/// Error loading a registry data file
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    /// line couldn't be parsed
+    #[error("line {line}: {message}")]
+    Syntax {
+        /// line number, starting from 1
+        line: usize,
+        /// what was expected
+        message: &'static str,
+    },
+
+    /// a packet id or name is defined twice for the same key
+    #[error("line {line}: packet {name} ({id:#04x}) is already defined")]
+    Duplicate {
+        /// line number, starting from 1
+        line: usize,
+        /// packet id
+        id: i32,
+        /// packet name
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+struct Table {
+    names: HashMap<i32, Arc<str>>,
+    ids: HashMap<Arc<str>, i32>,
+}
+
+impl Table {
+    fn insert(&mut self, id: i32, name: &str) -> bool {
+        if self.names.contains_key(&id) || self.ids.contains_key(name) {
+            return false;
+        }
+
+        let name: Arc<str> = name.into();
+        self.names.insert(id, name.clone());
+        self.ids.insert(name, id);
+
+        true
+    }
+}
+
+/// Maps packet ids of multiple protocol versions to logical packet names,
+/// so that a packet can be recognized regardless of the version in use.
+///
+/// Rust types can be bound to a name with [`Self::bind`], and then be
+/// (de)serialized without relying on a fixed [`PacketId`](crate::encoding::packetid::PacketId).
+///
+/// # Data file
+/// Tables can be loaded from a text file with [`Self::load`]. Each table starts
+/// with a header listing one or more protocol versions, the state and the direction,
+/// followed by one `<id> <name>` line per packet. Ids can be decimal or hexadecimal
+/// and `#` starts a comment:
+///
+/// ```text
+/// [764 765 status clientbound]
+/// 0x00 status_response
+/// 0x01 pong_response
+/// ```
+#[derive(Debug, Default)]
+pub struct PacketRegistry {
+    tables: HashMap<Key, Arc<Table>>,
+    types: HashMap<TypeId, Arc<str>>,
+}
+
+fn parse_state(state: &str) -> Option<State> {
+    let state = match state {
+        "handshaking" | "handshake" => State::Handshaking,
+        "status" => State::Status,
+        "login" => State::Login,
+        "configuration" => State::Configuration,
+        "play" => State::Play,
+        _ => return None,
+    };
+
+    Some(state)
+}
+
+fn parse_direction(direction: &str) -> Option<Direction> {
+    match direction {
+        "serverbound" => Some(Direction::Serverbound),
+        "clientbound" => Some(Direction::Clientbound),
+        _ => None,
+    }
+}
+
+fn parse_id(id: &str) -> Option<i32> {
+    match id.strip_prefix("0x") {
+        Some(hex) => i32::from_str_radix(hex, 16).ok(),
+        None => id.parse().ok(),
+    }
+}
+
+impl PacketRegistry {
+    /// creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds packet `name` with `id` to the table of `key`.
+    /// Returns false if either is already registered
+    pub fn insert(&mut self, key: Key, id: i32, name: &str) -> bool {
+        let table = self.tables.entry(key).or_default();
+        Arc::make_mut(table).insert(id, name)
+    }
+
+    /// Loads the tables of a data file, see [`PacketRegistry`] for the format.
+    /// Loaded tables replace the ones already registered with the same key
+    pub fn load(&mut self, data: &str) -> Result<(), RegistryError> {
+        let mut current: Option<(Vec<Key>, Table)> = None;
+
+        for (index, line) in data.lines().enumerate() {
+            let line_number = index + 1;
+            let syntax = |message| RegistryError::Syntax {
+                line: line_number,
+                message,
+            };
+
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header.strip_suffix(']').ok_or(syntax("expected ]"))?;
+                let mut words = header.split_whitespace().rev();
+
+                let direction = words.next().and_then(parse_direction);
+                let direction = direction.ok_or(syntax("expected direction"))?;
+                let state = words.next().and_then(parse_state);
+                let state = state.ok_or(syntax("expected state"))?;
+
+                let keys = words
+                    .map(|version| version.parse().map(ProtocolVersion))
+                    .map(|version| version.map(|version| Key::new(version, state, direction)))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| syntax("expected protocol version"))?;
+
+                if keys.is_empty() {
+                    return Err(syntax("expected protocol version"));
+                }
+
+                self.commit(current.take());
+                current = Some((keys, Table::default()));
+                continue;
+            }
+
+            let (_, table) = current.as_mut().ok_or(syntax("expected table header"))?;
+            let (id, name) = line
+                .split_once(char::is_whitespace)
+                .ok_or(syntax("expected <id> <name>"))?;
+            let id = parse_id(id).ok_or(syntax("invalid packet id"))?;
+            let name = name.trim();
+
+            if !table.insert(id, name) {
+                return Err(RegistryError::Duplicate {
+                    line: line_number,
+                    id,
+                    name: name.to_string(),
+                });
+            }
+        }
+
+        self.commit(current);
+        Ok(())
+    }
+
+    fn commit(&mut self, table: Option<(Vec<Key>, Table)>) {
+        let Some((keys, table)) = table else { return };
+
+        let table = Arc::new(table);
+        for key in keys {
+            self.tables.insert(key, table.clone());
+        }
+    }
+
+    /// keys for which a table is registered
+    pub fn keys(&self) -> impl Iterator<Item = &Key> {
+        self.tables.keys()
+    }
+
+    /// name of packet `id` in the table of `key`
+    pub fn name(&self, key: Key, id: i32) -> Option<&str> {
+        self.tables.get(&key)?.names.get(&id).map(AsRef::as_ref)
+    }
+
+    /// id of packet `name` in the table of `key`
+    pub fn id(&self, key: Key, name: &str) -> Option<i32> {
+        self.tables.get(&key)?.ids.get(name).copied()
+    }
+
+    /// name of `packet`, received with `key`
+    pub fn resolve(&self, key: Key, packet: &RawPacket) -> Option<&str> {
+        self.name(key, packet.packet_id)
+    }
+
+    /// Binds type `T` to packet `name`, replacing the previous binding
+    pub fn bind<T: 'static>(&mut self, name: &str) {
+        self.types.insert(TypeId::of::<T>(), name.into());
+    }
+
+    /// name bound to `T`
+    pub fn name_of<T: 'static>(&self) -> Option<&str> {
+        self.types.get(&TypeId::of::<T>()).map(AsRef::as_ref)
+    }
+
+    /// id of the packet bound to `T` in the table of `key`
+    pub fn id_of<T: 'static>(&self, key: Key) -> Option<i32> {
+        self.id(key, self.name_of::<T>()?)
+    }
+
+    /// whether `packet` is the packet bound to `T`
+    pub fn is<T: 'static>(&self, key: Key, packet: &RawPacket) -> bool {
+        self.id_of::<T>(key) == Some(packet.packet_id)
+    }
+
+    /// Deserializes `packet` as `T`, if it's the packet bound to `T`
+    pub fn deserialize<T>(&self, key: Key, packet: &RawPacket) -> Option<Result<T, DeError>>
+    where
+        T: Deserialize + 'static,
+    {
+        self.is::<T>(key, packet)
+            .then(|| deserialize_bytes(packet.data.clone()))
+    }
+
+    /// Serializes `packet` with the id of the packet bound to `T`
+    /// in the table of `key`, if any
+    pub fn encode<T>(&self, key: Key, packet: &T) -> Option<RawPacket>
+    where
+        T: Serialize + 'static,
+    {
+        let packet_id = self.id_of::<T>(key)?;
+        let data = serialize_bytes(packet);
+
+        Some(RawPacket { packet_id, data })
+    }
+}
+
+impl FromStr for PacketRegistry {
+    type Err = RegistryError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        let mut registry = Self::new();
+        registry.load(data)?;
+
+        Ok(registry)
+    }
+}
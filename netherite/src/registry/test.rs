@@ -0,0 +1,111 @@
+use bytes::{Buf, BufMut, Bytes};
+
+use super::{Key, PacketRegistry, RegistryError};
+use crate::{
+    packet::RawPacket,
+    protocol::{Direction, ProtocolVersion, State},
+    DeError, Deserialize, Serialize,
+};
+
+const DATA: &str = "
+# status packets
+[764 765 status clientbound]
+0x00 status_response
+0x01 pong_response # comment
+
+[766 status clientbound]
+1 status_response
+0 pong_response
+";
+
+fn key(version: i32) -> Key {
+    Key::new(
+        ProtocolVersion(version),
+        State::Status,
+        Direction::Clientbound,
+    )
+}
+
+#[derive(Debug, PartialEq)]
+struct Pong(u64);
+
+impl Serialize for Pong {
+    fn serialize(&self, mut buf: impl BufMut) {
+        buf.put_u64(self.0)
+    }
+
+    fn size(&self) -> usize {
+        8
+    }
+}
+
+impl Deserialize for Pong {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        u64::deserialize(buffer).map(Self)
+    }
+}
+
+#[test]
+fn load() {
+    let registry: PacketRegistry = DATA.parse().unwrap();
+
+    assert_eq!(registry.name(key(764), 0x01), Some("pong_response"));
+    assert_eq!(registry.name(key(765), 0x01), Some("pong_response"));
+    assert_eq!(registry.name(key(766), 0x01), Some("status_response"));
+    assert_eq!(registry.id(key(766), "pong_response"), Some(0));
+
+    assert_eq!(registry.name(key(763), 0x01), None);
+    assert_eq!(registry.keys().count(), 3);
+}
+
+#[test]
+fn load_errors() {
+    let registry = "0x00 status_response".parse::<PacketRegistry>();
+    assert!(matches!(
+        registry,
+        Err(RegistryError::Syntax { line: 1, .. })
+    ));
+
+    let registry = "[765 status]\n".parse::<PacketRegistry>();
+    assert!(matches!(
+        registry,
+        Err(RegistryError::Syntax { line: 1, .. })
+    ));
+
+    let registry = "[765 status clientbound]\n0 a\n0x00 b".parse::<PacketRegistry>();
+    assert!(matches!(
+        registry,
+        Err(RegistryError::Duplicate { line: 3, id: 0, .. })
+    ));
+}
+
+#[test]
+fn typed() {
+    let mut registry: PacketRegistry = DATA.parse().unwrap();
+    registry.bind::<Pong>("pong_response");
+
+    let old = registry.encode(key(765), &Pong(7)).unwrap();
+    let new = registry.encode(key(766), &Pong(7)).unwrap();
+    assert_eq!((old.packet_id, new.packet_id), (0x01, 0x00));
+
+    let pong = registry.deserialize::<Pong>(key(766), &new).unwrap();
+    assert_eq!(pong.unwrap(), Pong(7));
+    assert!(registry.deserialize::<Pong>(key(765), &new).is_none());
+
+    let unknown = RawPacket {
+        packet_id: 0x05,
+        data: Bytes::new(),
+    };
+
+    assert_eq!(registry.resolve(key(765), &unknown), None);
+    assert!(registry.encode(key(763), &Pong(7)).is_none());
+}
+
+#[test]
+fn insert() {
+    let mut registry = PacketRegistry::new();
+    assert!(registry.insert(key(765), 0x00, "a"));
+    assert!(!registry.insert(key(765), 0x00, "b"));
+    assert!(!registry.insert(key(765), 0x01, "a"));
+    assert!(registry.insert(key(764), 0x00, "b"));
+}
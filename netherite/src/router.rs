@@ -0,0 +1,156 @@
+use std::{any::type_name, collections::HashMap, future::Future};
+
+use futures::{future::BoxFuture, FutureExt, Stream, StreamExt};
+use thiserror::Error;
+
+use crate::{
+    codec::CodecError, encoding::packetid::PacketId, packet::RawPacket, protocol::State, DeError,
+    Deserialize,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Error returned while routing a packet
+#[derive(Debug, Error)]
+pub enum RouterError<E> {
+    /// Packet couldn't be deserialized into
+    /// the type its handler was registered with
+    #[error("packet {id:#04x} as {type_name}: {source}")]
+    Deserialize {
+        /// id of the packet
+        id: i32,
+        /// name of the type the packet was deserialized into
+        type_name: &'static str,
+        /// deserialization error
+        source: DeError,
+    },
+
+    /// Error reading from the packet stream
+    #[error("codec: {0}")]
+    Codec(#[from] CodecError),
+
+    /// Error returned by a handler
+    #[error("handler: {0}")]
+    Handler(E),
+}
+
+type Handler<C, E> =
+    Box<dyn Fn(C, RawPacket) -> BoxFuture<'static, Result<(), RouterError<E>>> + Send + Sync>;
+
+/// Dispatches packets to async handlers registered
+/// for their type and the state of the connection.
+///
+/// `C` is a context passed to every handler, usually
+/// a cheaply clonable handle to the connection
+pub struct Router<C, E> {
+    handlers: HashMap<(State, i32), Handler<C, E>>,
+    fallback: Option<Handler<C, E>>,
+}
+
+impl<C, E> Default for Router<C, E> {
+    fn default() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            fallback: None,
+        }
+    }
+}
+
+impl<C, E> Router<C, E>
+where
+    C: Send + 'static,
+    E: Send + 'static,
+{
+    /// creates a router without handlers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` for packets of type `T` received in
+    /// `state`, replacing the previous handler of the same id
+    pub fn route<T, F, Fut>(mut self, state: State, handler: F) -> Self
+    where
+        T: Deserialize + PacketId + Send + 'static,
+        F: Fn(C, T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        let handler = move |ctx, raw: RawPacket| {
+            let packet =
+                raw.deserialize_unchecked::<T>()
+                    .map_err(|source| RouterError::Deserialize {
+                        id: raw.packet_id,
+                        type_name: type_name::<T>(),
+                        source,
+                    });
+
+            match packet {
+                Ok(packet) => handler(ctx, packet)
+                    .map(|res| res.map_err(RouterError::Handler))
+                    .boxed(),
+                Err(err) => futures::future::ready(Err(err)).boxed(),
+            }
+        };
+
+        self.handlers.insert((state, T::ID), Box::new(handler));
+        self
+    }
+
+    /// Sets the handler of the packets without a route,
+    /// which are otherwise ignored
+    pub fn fallback<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(C, RawPacket) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        let handler = move |ctx, raw| {
+            handler(ctx, raw)
+                .map(|res| res.map_err(RouterError::Handler))
+                .boxed()
+        };
+
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// whether a handler is registered for `id` in `state`
+    pub fn handles(&self, state: State, id: i32) -> bool {
+        self.handlers.contains_key(&(state, id))
+    }
+
+    /// Calls the handler registered for `packet` in `state`
+    pub async fn dispatch(
+        &self,
+        state: State,
+        ctx: C,
+        packet: RawPacket,
+    ) -> Result<(), RouterError<E>> {
+        let handler = self.handlers.get(&(state, packet.packet_id));
+
+        match handler.or(self.fallback.as_ref()) {
+            Some(handler) => handler(ctx, packet).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Dispatches every packet of `stream` (usually a `FramedRead`) until it
+    /// ends or an error occurs, handling one packet at a time. The state used
+    /// for each packet is read from the context with `state`, so that handlers
+    /// can switch it
+    pub async fn run<S>(
+        &self,
+        mut stream: S,
+        ctx: C,
+        state: impl Fn(&C) -> State,
+    ) -> Result<(), RouterError<E>>
+    where
+        S: Stream<Item = Result<RawPacket, CodecError>> + Unpin,
+        C: Clone,
+    {
+        while let Some(packet) = stream.next().await {
+            self.dispatch(state(&ctx), ctx.clone(), packet?).await?;
+        }
+
+        Ok(())
+    }
+}
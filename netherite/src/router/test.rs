@@ -0,0 +1,152 @@
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+};
+
+use bytes::{Buf, Bytes};
+use tokio_util::codec::FramedRead;
+
+use super::{Router, RouterError};
+use crate::{
+    encoding::packetid::PacketId, packet::RawPacket, protocol::State, DeError, Deserialize,
+    UncompressedCodec,
+};
+
+struct Ping {
+    payload: u64,
+}
+
+impl Deserialize for Ping {
+    fn deserialize(buffer: impl Buf) -> Result<Self, DeError> {
+        u64::deserialize(buffer).map(|payload| Self { payload })
+    }
+}
+
+impl PacketId for Ping {
+    const ID: i32 = 0x01;
+}
+
+struct Start;
+
+impl Deserialize for Start {
+    fn deserialize(_: impl Buf) -> Result<Self, DeError> {
+        Ok(Self)
+    }
+}
+
+impl PacketId for Start {
+    const ID: i32 = 0x00;
+}
+
+#[derive(Clone, Default)]
+struct Ctx {
+    state: Arc<Mutex<Option<State>>>,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Ctx {
+    fn state(&self) -> State {
+        self.state.lock().unwrap().unwrap_or(State::Status)
+    }
+
+    fn log(&self, line: String) {
+        self.log.lock().unwrap().push(line)
+    }
+}
+
+fn router() -> Router<Ctx, &'static str> {
+    Router::new()
+        .route(State::Status, |ctx: Ctx, _: Start| async move {
+            *ctx.state.lock().unwrap() = Some(State::Play);
+            ctx.log("start".into());
+            Ok(())
+        })
+        .route(State::Play, |ctx: Ctx, ping: Ping| async move {
+            ctx.log(format!("ping {}", ping.payload));
+            match ping.payload {
+                0 => Err("zero"),
+                _ => Ok(()),
+            }
+        })
+}
+
+fn raw(packet_id: i32, data: &'static [u8]) -> RawPacket {
+    let data = Bytes::from_static(data);
+    RawPacket { packet_id, data }
+}
+
+#[tokio::test]
+async fn dispatch_by_state() {
+    let router = router();
+    let ctx = Ctx::default();
+
+    let ping = raw(0x01, &[0, 0, 0, 0, 0, 0, 0, 1]);
+    router
+        .dispatch(State::Status, ctx.clone(), ping.clone())
+        .await
+        .unwrap();
+    router
+        .dispatch(State::Play, ctx.clone(), ping)
+        .await
+        .unwrap();
+
+    assert_eq!(*ctx.log.lock().unwrap(), ["ping 1"]);
+    assert!(router.handles(State::Play, 0x01));
+    assert!(!router.handles(State::Status, 0x01));
+}
+
+#[tokio::test]
+async fn fallback() {
+    let router = router().fallback(|ctx: Ctx, raw: RawPacket| async move {
+        ctx.log(format!("unknown {}", raw.packet_id));
+        Ok(())
+    });
+
+    let ctx = Ctx::default();
+    router
+        .dispatch(State::Play, ctx.clone(), raw(0x05, &[]))
+        .await
+        .unwrap();
+
+    assert_eq!(*ctx.log.lock().unwrap(), ["unknown 5"]);
+}
+
+#[tokio::test]
+async fn errors() {
+    let router = router();
+    let ctx = Ctx::default();
+
+    let res = router
+        .dispatch(State::Play, ctx.clone(), raw(0x01, &[0]))
+        .await;
+    let Err(RouterError::Deserialize {
+        id,
+        type_name,
+        source,
+    }) = res
+    else {
+        panic!("expected deserialize error")
+    };
+
+    assert_eq!(id, 0x01);
+    assert!(type_name.ends_with("Ping"));
+    assert!(matches!(source, DeError::Eof));
+
+    let ping = raw(0x01, &[0; 8]);
+    let res = router.dispatch(State::Play, ctx, ping).await;
+    assert!(matches!(res, Err(RouterError::Handler("zero"))));
+}
+
+#[tokio::test]
+async fn run() {
+    let bytes = [
+        0x01, 0x00, // start
+        0x09, 0x01, 0, 0, 0, 0, 0, 0, 0, 2, // ping
+    ];
+
+    let stream = FramedRead::new(Cursor::new(bytes), UncompressedCodec::default());
+    let ctx = Ctx::default();
+
+    router().run(stream, ctx.clone(), Ctx::state).await.unwrap();
+    assert_eq!(*ctx.log.lock().unwrap(), ["start", "ping 2"]);
+}
@@ -0,0 +1,410 @@
+use std::time::{Duration, Instant};
+
+use futures::{SinkExt, StreamExt};
+use serde::{de::Error as _, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+};
+use tokio_util::codec::Framed;
+
+use crate::{
+    codec::CodecError,
+    legacy::LegacyStatus,
+    packet::{
+        handshake::{Handshake, Intent},
+        login::{hyphenated, parse_uuid},
+        status::{PingRequest, PongResponse, StatusRequest, StatusResponse as Response},
+        RawPacket,
+    },
+    protocol::ProtocolVersion,
+    text::TextComponent,
+    UncompressedCodec,
+};
+
+/// server side of the server list ping
+pub mod responder;
+#[cfg(test)]
+mod test;
+
+pub use crate::protocol::DEFAULT_PORT;
+
+/// Version of the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    /// version name, which may be any text
+    pub name: String,
+    /// protocol version of the server
+    pub protocol: i32,
+}
+
+/// Player shown when hovering the player count
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerSample {
+    /// name of the player
+    pub name: String,
+    /// uuid of the player
+    pub id: u128,
+}
+
+/// Player count of the server
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Players {
+    /// maximum number of players
+    pub max: i32,
+    /// players online
+    pub online: i32,
+    /// some of the players online
+    pub sample: Vec<PlayerSample>,
+}
+
+/// Status of a server, shown in the server list.
+///
+/// Encoded as JSON in the [`Response`] packet, use [`crate::encoding::json::Json`]
+/// or `serde_json` to convert it
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusResponse {
+    /// version of the server
+    pub version: Version,
+    /// player count, hidden if missing
+    pub players: Option<Players>,
+    /// message of the day
+    pub description: TextComponent,
+    /// base64 encoded PNG image, prefixed with `data:image/png;base64,`
+    pub favicon: Option<String>,
+    /// whether the server requires signed chat messages (1.19.1+)
+    pub enforces_secure_chat: bool,
+}
+
+fn field<'a>(object: &'a Map<String, Value>, key: &str) -> Result<&'a Value, String> {
+    object.get(key).ok_or_else(|| format!("missing {key}"))
+}
+
+fn int(value: &Value, key: &str) -> Result<i32, String> {
+    value
+        .as_i64()
+        .and_then(|value| value.try_into().ok())
+        .ok_or_else(|| format!("{key} must be an integer"))
+}
+
+fn string(value: &Value, key: &str) -> Result<String, String> {
+    value
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("{key} must be a string"))
+}
+
+fn version(value: &Value) -> Result<Version, String> {
+    let object = value.as_object().ok_or("version must be an object")?;
+
+    Ok(Version {
+        name: string(field(object, "name")?, "name")?,
+        protocol: int(field(object, "protocol")?, "protocol")?,
+    })
+}
+
+fn players(value: &Value) -> Result<Players, String> {
+    let object = value.as_object().ok_or("players must be an object")?;
+
+    let sample = match object.get("sample") {
+        Some(Value::Array(sample)) => sample
+            .iter()
+            .map(|player| {
+                let player = player.as_object().ok_or("sample must contain objects")?;
+                let id = string(field(player, "id")?, "id")?;
+
+                Ok(PlayerSample {
+                    name: string(field(player, "name")?, "name")?,
+                    id: parse_uuid(&id).ok_or("invalid player id")?,
+                })
+            })
+            .collect::<Result<_, String>>()?,
+        Some(_) => return Err("sample must be an array".into()),
+        None => vec![],
+    };
+
+    Ok(Players {
+        max: int(field(object, "max")?, "max")?,
+        online: int(field(object, "online")?, "online")?,
+        sample,
+    })
+}
+
+fn from_value(value: Value) -> Result<StatusResponse, String> {
+    let Value::Object(object) = value else {
+        return Err("status must be an object".into());
+    };
+
+    let description = match object.get("description") {
+        Some(description) => {
+            TextComponent::deserialize(description).map_err(|err| err.to_string())?
+        }
+        None => TextComponent::default(),
+    };
+
+    Ok(StatusResponse {
+        version: version(field(&object, "version")?)?,
+        players: object.get("players").map(players).transpose()?,
+        description,
+        favicon: object
+            .get("favicon")
+            .and_then(Value::as_str)
+            .map(Into::into),
+        enforces_secure_chat: object
+            .get("enforcesSecureChat")
+            .and_then(Value::as_bool)
+            .unwrap_or_default(),
+    })
+}
+
+impl Serialize for StatusResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let version = json!({ "name": self.version.name, "protocol": self.version.protocol });
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("version", &version)?;
+
+        if let Some(players) = &self.players {
+            let sample: Vec<_> = players
+                .sample
+                .iter()
+                .map(|player| json!({ "name": player.name, "id": hyphenated(player.id) }))
+                .collect();
+
+            let players = json!({ "max": players.max, "online": players.online, "sample": sample });
+            map.serialize_entry("players", &players)?;
+        }
+
+        map.serialize_entry("description", &self.description)?;
+
+        if let Some(favicon) = &self.favicon {
+            map.serialize_entry("favicon", favicon)?;
+        }
+
+        map.serialize_entry("enforcesSecureChat", &self.enforces_secure_chat)?;
+        map.end()
+    }
+}
+
+/// Missing optional fields take their default value,
+/// while unknown fields (like mod lists) are ignored
+impl<'de> Deserialize<'de> for StatusResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        from_value(value).map_err(D::Error::custom)
+    }
+}
+
+/// Status answering legacy pings, with a plain text motd.
+/// Hidden players are shown as `0/0`
+impl From<&StatusResponse> for LegacyStatus {
+    fn from(status: &StatusResponse) -> Self {
+        let players = status.players.clone().unwrap_or_default();
+
+        Self {
+            protocol: status.version.protocol,
+            version: status.version.name.clone(),
+            motd: status.description.to_plain(),
+            online: players.online,
+            max: players.max,
+        }
+    }
+}
+
+/// Error of a Server List Ping exchange, on either side
+#[derive(Debug, Error)]
+pub enum PingError {
+    /// Error connecting to the server
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Error reading or writing packets
+    #[error("codec: {0}")]
+    Codec(#[from] CodecError),
+
+    /// Status JSON couldn't be parsed
+    #[error("invalid status: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Server sent a packet other than the expected one
+    #[error("unexpected packet {0:#04x}")]
+    UnexpectedPacket(i32),
+
+    /// Pong payload is different from the one sent
+    #[error("pong payload doesn't match the ping")]
+    Payload,
+
+    /// Server closed the connection
+    #[error("connection closed")]
+    Closed,
+
+    /// Server didn't complete the exchange in time
+    #[error("timed out")]
+    Timeout,
+}
+
+/// Maximum size of the packets read from servers, a status
+/// response of 32767 characters of up to 4 bytes and its framing
+pub const MAX_RESPONSE_SIZE: usize = 32767 * 4 + 16;
+
+/// Options of [`ping`]
+#[derive(Debug, Clone)]
+pub struct PingOptions {
+    timeout: Duration,
+    protocol_version: ProtocolVersion,
+    latency: bool,
+    max_size: usize,
+}
+
+impl Default for PingOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            protocol_version: ProtocolVersion::V1_21_5,
+            latency: true,
+            max_size: MAX_RESPONSE_SIZE,
+        }
+    }
+}
+
+impl PingOptions {
+    /// maximum duration of the whole exchange, connection included
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// protocol version sent in the handshake
+    pub fn protocol_version(self, protocol_version: ProtocolVersion) -> Self {
+        Self {
+            protocol_version,
+            ..self
+        }
+    }
+
+    /// Whether to measure latency with a ping request after the status.
+    /// If disabled, latency is measured with the status request
+    pub fn latency(self, latency: bool) -> Self {
+        Self { latency, ..self }
+    }
+
+    /// maximum size of the packets read from the server, [`MAX_RESPONSE_SIZE`] by default
+    pub fn max_size(self, max_size: usize) -> Self {
+        Self { max_size, ..self }
+    }
+}
+
+/// Result of [`ping`]
+#[derive(Debug, Clone)]
+pub struct Ping {
+    /// status of the server
+    pub status: StatusResponse,
+    /// round trip time of the ping request
+    pub latency: Duration,
+}
+
+/// splits `address` into host and port, using [`DEFAULT_PORT`] if missing
+fn host_port(address: &str) -> (&str, u16) {
+    let port = address
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse().ok()?)));
+
+    match port {
+        // ipv6 addresses without port contain colons too
+        Some((host, port)) if !host.contains(':') || host.ends_with(']') => (host, port),
+        _ => (address, DEFAULT_PORT),
+    }
+}
+
+/// Pings the server at `address` (`host` or `host:port`) with the Server List Ping
+/// protocol, getting its status and measuring latency
+pub async fn ping(address: &str, options: PingOptions) -> Result<Ping, PingError> {
+    let (host, port) = host_port(address);
+    let host = host.trim_matches(['[', ']']);
+    let timeout = options.timeout;
+
+    let exchange = async move {
+        let stream = TcpStream::connect((host, port)).await?;
+        exchange(stream, host, port, &options).await
+    };
+
+    tokio::time::timeout(timeout, exchange)
+        .await
+        .map_err(|_| PingError::Timeout)?
+}
+
+/// Like [`ping`], on an already connected `stream`. `host` and `port`
+/// are sent in the handshake
+pub async fn ping_stream<S>(
+    stream: S,
+    host: &str,
+    port: u16,
+    options: PingOptions,
+) -> Result<Ping, PingError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    tokio::time::timeout(options.timeout, exchange(stream, host, port, &options))
+        .await
+        .map_err(|_| PingError::Timeout)?
+}
+
+async fn exchange<S>(
+    stream: S,
+    host: &str,
+    port: u16,
+    options: &PingOptions,
+) -> Result<Ping, PingError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let codec = UncompressedCodec::default().max_size(options.max_size);
+    let mut framed = Framed::new(stream, codec);
+
+    framed
+        .send(Handshake {
+            protocol_version: options.protocol_version,
+            server_address: host.to_string().into(),
+            server_port: port,
+            intent: Intent::Status,
+        })
+        .await?;
+
+    let start = Instant::now();
+    framed.send(StatusRequest {}).await?;
+
+    let response: Response = receive(&mut framed).await?;
+    let mut latency = start.elapsed();
+    let status = serde_json::from_str(&response.response)?;
+
+    if options.latency {
+        // only needs to be different across pings
+        let payload = start.elapsed().as_nanos() as i64;
+
+        let start = Instant::now();
+        framed.send(PingRequest { payload }).await?;
+
+        let pong: PongResponse = receive(&mut framed).await?;
+        latency = start.elapsed();
+
+        if pong.payload != payload {
+            return Err(PingError::Payload);
+        }
+    }
+
+    Ok(Ping { status, latency })
+}
+
+async fn receive<S, T>(framed: &mut Framed<S, UncompressedCodec>) -> Result<T, PingError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: crate::Deserialize + crate::encoding::packetid::PacketId,
+{
+    let packet: RawPacket = framed.next().await.ok_or(PingError::Closed)??;
+
+    match packet.deserialize() {
+        Some(packet) => Ok(packet.map_err(CodecError::from)?),
+        None => Err(PingError::UnexpectedPacket(packet.packet_id)),
+    }
+}
@@ -0,0 +1,185 @@
+use std::{io, pin::pin, time::Duration};
+
+use bytes::BytesMut;
+use futures::{
+    future::{select, Either},
+    stream::FuturesUnordered,
+    SinkExt, StreamExt,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpListener,
+};
+use tokio_util::codec::{Framed, FramedParts};
+
+use super::{receive, PingError, StatusResponse};
+use crate::{
+    legacy::{self, LegacyPing, LegacyStatus},
+    packet::{
+        handshake::{Handshake, Intent},
+        login::Disconnect,
+        status::{PingRequest, PongResponse, StatusRequest, StatusResponse as Response},
+        RawPacket,
+    },
+    protocol::{ProtocolVersion, DEFAULT_PORT},
+    text::TextComponent,
+    UncompressedCodec,
+};
+
+/// Maximum size of the packets read from clients. Handshakes, status requests
+/// and pings take a few hundred bytes at most, the connection isn't trusted
+const MAX_SIZE: usize = 1024;
+/// How long to wait for the rest of a legacy ping split across segments
+const LEGACY_WAIT: Duration = Duration::from_millis(200);
+
+/// Answers Server List Pings with the status returned by a provider,
+/// optionally kicking players trying to join.
+///
+/// The provider gets the [`Handshake`] of the client, so that the
+/// status can depend on its protocol version or on the address used.
+/// Legacy pings of pre-Netty clients are answered too, see [`legacy`]:
+/// their handshake carries the protocol version and address sent by 1.6
+/// clients, or protocol version -1 and no address for older ones
+pub struct Responder<F> {
+    provider: F,
+    kick: Option<TextComponent>,
+    timeout: Duration,
+}
+
+impl<F> Responder<F>
+where
+    F: Fn(&Handshake) -> StatusResponse,
+{
+    /// creates a responder answering with the status returned by `provider`
+    pub fn new(provider: F) -> Self {
+        Self {
+            provider,
+            kick: None,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Disconnects login attempts with `message`, instead of
+    /// closing the connection without any explanation
+    pub fn kick(self, message: TextComponent) -> Self {
+        Self {
+            kick: Some(message),
+            ..self
+        }
+    }
+
+    /// maximum duration of a connection
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Handles a single connection, from the handshake until the client
+    /// closes it or the exchange is complete
+    pub async fn respond<S>(&self, stream: S) -> Result<(), PingError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        tokio::time::timeout(self.timeout, self.exchange(stream))
+            .await
+            .map_err(|_| PingError::Timeout)?
+    }
+
+    /// Accepts connections from `listener` forever, handling them concurrently.
+    /// Errors of single connections are ignored
+    pub async fn serve(&self, listener: &TcpListener) -> io::Result<()> {
+        let mut connections = FuturesUnordered::new();
+
+        loop {
+            let accept = pin!(listener.accept());
+
+            // a stream without futures would end immediately
+            let accepted = match connections.is_empty() {
+                true => accept.await,
+                false => match select(accept, connections.next()).await {
+                    Either::Left((accepted, _)) => accepted,
+                    Either::Right(_) => continue,
+                },
+            };
+
+            let (stream, _) = accepted?;
+            connections.push(self.respond(stream));
+        }
+    }
+
+    async fn exchange<S>(&self, mut stream: S) -> Result<(), PingError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        // a handshake can't start with the legacy ping byte, see legacy::detect
+        let mut buffer = BytesMut::new();
+        stream.read_buf(&mut buffer).await?;
+
+        if buffer.first() == Some(&legacy::PING) {
+            return self.legacy(stream, buffer).await;
+        }
+
+        // the bytes read so far must be decoded before reading again
+        let codec = UncompressedCodec::default().max_size(MAX_SIZE);
+        let mut parts = FramedParts::new::<&RawPacket>(stream, codec);
+        parts.read_buf = buffer;
+
+        let mut framed = Framed::from_parts(parts);
+        let handshake: Handshake = receive(&mut framed).await?;
+
+        if handshake.intent != Intent::Status {
+            if let Some(message) = &self.kick {
+                let reason = serde_json::to_string(message)?.into();
+                framed.send(Disconnect { reason }).await?;
+            }
+
+            return Ok(());
+        }
+
+        while let Some(packet) = framed.next().await {
+            let packet: RawPacket = packet?;
+
+            if packet.is::<StatusRequest>() {
+                let status = (self.provider)(&handshake);
+                let response = serde_json::to_string(&status)?.into();
+                framed.send(Response { response }).await?;
+            } else if let Some(ping) = packet.deserialize::<PingRequest>() {
+                let PingRequest { payload } = ping.map_err(crate::codec::CodecError::from)?;
+                framed.send(PongResponse { payload }).await?;
+
+                // the client closes the connection after the pong
+                break;
+            } else {
+                return Err(PingError::UnexpectedPacket(packet.packet_id));
+            }
+        }
+
+        Ok(())
+    }
+    async fn legacy<S>(&self, mut stream: S, mut buffer: BytesMut) -> Result<(), PingError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let ping = LegacyPing::read(&mut stream, &mut buffer, LEGACY_WAIT).await?;
+
+        let (protocol, host, port) = match &ping {
+            LegacyPing::V1_6 {
+                protocol,
+                host,
+                port,
+            } => (*protocol as i32, host.clone(), *port as u16),
+            _ => (-1, String::new(), DEFAULT_PORT),
+        };
+
+        let handshake = Handshake {
+            protocol_version: ProtocolVersion(protocol),
+            server_address: host.into(),
+            server_port: port,
+            intent: Intent::Status,
+        };
+
+        let status = LegacyStatus::from(&(self.provider)(&handshake));
+        stream.write_all(&status.response(&ping)).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use std::{pin::pin, time::Duration};
+
+use futures::{
+    future::{select, Either},
+    SinkExt, StreamExt,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+use tokio_util::codec::Framed;
+
+use super::{
+    host_port, ping, ping_stream, responder::Responder, PingError, PingOptions, StatusResponse,
+    DEFAULT_PORT,
+};
+use crate::{
+    codec::CodecError,
+    encoding::{deserialize_bytes, serialize_bytes},
+    legacy::{LegacyPing, LegacyStatus},
+    packet::{
+        handshake::{Handshake, Intent},
+        login::Disconnect,
+        status::{PingRequest, PongResponse, StatusResponse as Response},
+    },
+    protocol::ProtocolVersion,
+    text::{Color, TextComponent},
+    UncompressedCodec,
+};
+
+const STATUS: &str = r#"{
+    "version": { "name": "1.21.5", "protocol": 770 },
+    "players": {
+        "max": 20,
+        "online": 1,
+        "sample": [{ "name": "Notch", "id": "069a79f4-44e9-4726-a5be-fca90e38aaf5" }]
+    },
+    "description": { "text": "hello", "color": "gold" },
+    "favicon": "data:image/png;base64,",
+    "enforcesSecureChat": true,
+    "modinfo": { "type": "FML" }
+}"#;
+
+#[test]
+fn parse() {
+    let status: StatusResponse = serde_json::from_str(STATUS).unwrap();
+
+    assert_eq!(status.version.protocol, 770);
+    assert_eq!(
+        status.description,
+        TextComponent::text("hello").color(Color::Gold)
+    );
+    assert!(status.enforces_secure_chat);
+
+    let players = status.players.as_ref().unwrap();
+    assert_eq!((players.online, players.max), (1, 20));
+    assert_eq!(players.sample[0].id, 0x069a79f444e94726a5befca90e38aaf5);
+
+    let encoded = serde_json::to_string(&status).unwrap();
+    assert_eq!(
+        serde_json::from_str::<StatusResponse>(&encoded).unwrap(),
+        status
+    );
+
+    // legacy servers only send the mandatory fields
+    let minimal = r#"{ "version": { "name": "1.8", "protocol": 47 }, "description": "motd" }"#;
+    let status: StatusResponse = serde_json::from_str(minimal).unwrap();
+    assert_eq!(status.players, None);
+    assert_eq!(status.description.text, "motd");
+
+    assert!(serde_json::from_str::<StatusResponse>("{}").is_err());
+}
+
+#[test]
+fn address() {
+    assert_eq!(host_port("localhost"), ("localhost", DEFAULT_PORT));
+    assert_eq!(host_port("localhost:25566"), ("localhost", 25566));
+    assert_eq!(host_port("::1"), ("::1", DEFAULT_PORT));
+    assert_eq!(host_port("[::1]:25566"), ("[::1]", 25566));
+}
+
+#[tokio::test]
+async fn exchange() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut framed = Framed::new(stream, UncompressedCodec::default());
+
+        let handshake = framed.next().await.unwrap().unwrap();
+        let handshake: Handshake = handshake.deserialize().unwrap().unwrap();
+        assert_eq!(handshake.intent, Intent::Status);
+        assert_eq!(handshake.server_port, address.port());
+
+        // status request
+        framed.next().await.unwrap().unwrap();
+        let response = Response {
+            response: STATUS.to_string().into(),
+        };
+        framed.send(response).await.unwrap();
+
+        let ping = framed.next().await.unwrap().unwrap();
+        let PingRequest { payload } = ping.deserialize().unwrap().unwrap();
+        framed.send(PongResponse { payload }).await.unwrap();
+    });
+
+    let ping = ping(&address.to_string(), PingOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(ping.status.version.name, "1.21.5");
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn oversized_response() {
+    let (client, mut server) = tokio::io::duplex(1024);
+
+    // a 2 GiB status response is refused before its data arrives
+    server
+        .write_all(&[0x80, 0x80, 0x80, 0x80, 0x08])
+        .await
+        .unwrap();
+    let result = ping_stream(client, "localhost", DEFAULT_PORT, PingOptions::default()).await;
+    assert!(matches!(result, Err(PingError::Codec(CodecError::Size))));
+
+    let (client, mut server) = tokio::io::duplex(1024);
+    let mut framed = Framed::new(&mut server, UncompressedCodec::default());
+    let response = Response {
+        response: STATUS.to_string().into(),
+    };
+    framed.send(response).await.unwrap();
+
+    let options = PingOptions::default().max_size(64);
+    let result = ping_stream(client, "localhost", DEFAULT_PORT, options).await;
+    assert!(matches!(result, Err(PingError::Codec(CodecError::Size))));
+}
+
+#[tokio::test]
+async fn timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    // accepts but never answers
+    let server = tokio::spawn(async move { listener.accept().await.unwrap() });
+
+    let options = PingOptions::default().timeout(Duration::from_millis(50));
+    let result = ping(&address.to_string(), options).await;
+
+    assert!(matches!(result, Err(PingError::Timeout)));
+    server.await.unwrap();
+}
+
+fn status(handshake: &Handshake) -> StatusResponse {
+    let mut status: StatusResponse = serde_json::from_str(STATUS).unwrap();
+    status.version.protocol = handshake.protocol_version.0;
+
+    status
+}
+
+#[tokio::test]
+async fn respond() {
+    let (client, server) = tokio::io::duplex(1024);
+    let responder = Responder::new(status);
+
+    let options = PingOptions::default().protocol_version(ProtocolVersion::V1_8);
+    let (ping, result) = futures::join!(
+        ping_stream(client, "localhost", DEFAULT_PORT, options),
+        responder.respond(server)
+    );
+
+    result.unwrap();
+    assert_eq!(ping.unwrap().status.version.protocol, 47);
+}
+
+#[tokio::test]
+async fn kick() {
+    let (client, server) = tokio::io::duplex(1024);
+    let responder = Responder::new(status).kick(TextComponent::text("maintenance"));
+
+    let client = async {
+        let mut framed = Framed::new(client, UncompressedCodec::default());
+        let handshake = Handshake {
+            protocol_version: ProtocolVersion::V1_21_5,
+            server_address: String::from("localhost").into(),
+            server_port: DEFAULT_PORT,
+            intent: Intent::Login,
+        };
+
+        framed.send(handshake).await.unwrap();
+        framed.next().await.unwrap().unwrap()
+    };
+
+    let (packet, result) = futures::join!(client, responder.respond(server));
+    result.unwrap();
+
+    let Disconnect { reason } = packet.deserialize().unwrap().unwrap();
+    assert_eq!(&*reason, r#"{"text":"maintenance"}"#);
+}
+
+#[tokio::test]
+async fn legacy() {
+    let (mut client, server) = tokio::io::duplex(1024);
+    let responder = Responder::new(status);
+
+    let ping = LegacyPing::V1_6 {
+        protocol: 78,
+        host: "localhost".into(),
+        port: 25565,
+    };
+    client.write_all(&serialize_bytes(ping)).await.unwrap();
+    responder.respond(server).await.unwrap();
+
+    let mut response = vec![];
+    client.read_to_end(&mut response).await.unwrap();
+
+    let status: LegacyStatus = deserialize_bytes(&response[..]).unwrap();
+    assert_eq!((status.protocol, status.motd.as_str()), (78, "hello"));
+    assert_eq!((status.online, status.max), (1, 20));
+}
+
+#[tokio::test]
+async fn oversized() {
+    let (mut client, server) = tokio::io::duplex(1024);
+    let responder = Responder::new(status);
+
+    // a 1 MiB handshake is refused before its data arrives
+    client.write_all(&[0x80, 0x80, 0x40]).await.unwrap();
+    let result = responder.respond(server).await;
+    assert!(matches!(result, Err(PingError::Codec(CodecError::Size))));
+}
+
+#[tokio::test]
+async fn serve() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let responder = Responder::new(status);
+
+    let pings = async {
+        let first = ping(&address, PingOptions::default());
+        let second = ping(&address, PingOptions::default());
+
+        let (first, second) = futures::join!(first, second);
+        (first.unwrap(), second.unwrap())
+    };
+
+    let server = responder.serve(&listener);
+    let (first, second) = match select(pin!(pings), pin!(server)).await {
+        Either::Left((pings, _)) => pings,
+        Either::Right(_) => panic!("server stopped"),
+    };
+
+    assert_eq!(first.status, second.status);
+}
@@ -0,0 +1,294 @@
+use std::fmt::Display;
+
+/// ANSI terminal rendering of text components
+pub mod ansi;
+/// JSON (de)serialization of text components through serde
+#[cfg(feature = "serde_json")]
+pub mod json;
+/// conversion between components and legacy `§` formatted strings
+pub mod legacy;
+mod nbt;
+
+#[cfg(test)]
+mod test;
+
+pub use nbt::Chat;
+
+/// One of the 16 named Minecraft colors,
+/// or an arbitrary RGB color (1.16+)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Color {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+    /// `#RRGGBB` color
+    Rgb(u8, u8, u8),
+}
+
+struct Named {
+    color: Color,
+    code: char,
+    name: &'static str,
+    rgb: (u8, u8, u8),
+}
+
+macro_rules! named {
+    ($($color:ident $code:literal $name:literal $rgb:expr),* $(,)?) => {
+        [$(Named { color: Color::$color, code: $code, name: $name, rgb: $rgb }),*]
+    };
+}
+
+const NAMED: [Named; 16] = named![
+    Black '0' "black" (0x00, 0x00, 0x00),
+    DarkBlue '1' "dark_blue" (0x00, 0x00, 0xAA),
+    DarkGreen '2' "dark_green" (0x00, 0xAA, 0x00),
+    DarkAqua '3' "dark_aqua" (0x00, 0xAA, 0xAA),
+    DarkRed '4' "dark_red" (0xAA, 0x00, 0x00),
+    DarkPurple '5' "dark_purple" (0xAA, 0x00, 0xAA),
+    Gold '6' "gold" (0xFF, 0xAA, 0x00),
+    Gray '7' "gray" (0xAA, 0xAA, 0xAA),
+    DarkGray '8' "dark_gray" (0x55, 0x55, 0x55),
+    Blue '9' "blue" (0x55, 0x55, 0xFF),
+    Green 'a' "green" (0x55, 0xFF, 0x55),
+    Aqua 'b' "aqua" (0x55, 0xFF, 0xFF),
+    Red 'c' "red" (0xFF, 0x55, 0x55),
+    LightPurple 'd' "light_purple" (0xFF, 0x55, 0xFF),
+    Yellow 'e' "yellow" (0xFF, 0xFF, 0x55),
+    White 'f' "white" (0xFF, 0xFF, 0xFF),
+];
+
+impl Color {
+    fn named(&self) -> Option<&'static Named> {
+        NAMED.iter().find(|named| named.color == *self)
+    }
+
+    /// legacy formatting code of this color (`0-9a-f`), `None` for rgb colors
+    pub fn code(&self) -> Option<char> {
+        self.named().map(|named| named.code)
+    }
+
+    /// parses a legacy color code (`0-9a-f`, case insensitive)
+    pub fn from_code(code: char) -> Option<Self> {
+        let code = code.to_ascii_lowercase();
+        NAMED
+            .iter()
+            .find(|named| named.code == code)
+            .map(|named| named.color)
+    }
+
+    /// the name used in json text components (`dark_red`, `#ff00aa`, ...)
+    pub fn name(&self) -> String {
+        match self.named() {
+            Some(named) => named.name.to_string(),
+            None => {
+                let (r, g, b) = self.rgb();
+                format!("#{r:02x}{g:02x}{b:02x}")
+            }
+        }
+    }
+
+    /// parses a json color name, either a named color or `#RRGGBB`
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(hex) = name.strip_prefix('#') {
+            let rgb = (hex.len() == 6)
+                .then(|| u32::from_str_radix(hex, 16).ok())
+                .flatten()?;
+            let [_, r, g, b] = rgb.to_be_bytes();
+
+            return Some(Self::Rgb(r, g, b));
+        }
+
+        NAMED
+            .iter()
+            .find(|named| named.name == name)
+            .map(|named| named.color)
+    }
+
+    /// rgb value of this color as rendered by the vanilla client
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            Self::Rgb(r, g, b) => (r, g, b),
+            _ => self.named().map(|named| named.rgb).unwrap_or_default(),
+        }
+    }
+}
+
+/// Formatting of a [`TextComponent`].
+///
+/// `None` fields are inherited from the parent component
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+}
+
+impl Style {
+    /// fills every unset field of `self` with the one of `parent`
+    pub fn inherit(self, parent: &Style) -> Self {
+        Self {
+            color: self.color.or(parent.color),
+            bold: self.bold.or(parent.bold),
+            italic: self.italic.or(parent.italic),
+            underlined: self.underlined.or(parent.underlined),
+            strikethrough: self.strikethrough.or(parent.strikethrough),
+            obfuscated: self.obfuscated.or(parent.obfuscated),
+        }
+    }
+
+    /// true if no formatting would be applied with this style
+    pub fn is_plain(&self) -> bool {
+        self.color.is_none() && self.decorations().all(|(_, enabled)| !enabled)
+    }
+
+    /// strips the `false` decorations, which once the style
+    /// has been resolved are the same as unset
+    pub(crate) fn normalized(self) -> Self {
+        let enabled = |field: Option<bool>| field.filter(|&e| e);
+
+        Self {
+            color: self.color,
+            bold: enabled(self.bold),
+            italic: enabled(self.italic),
+            underlined: enabled(self.underlined),
+            strikethrough: enabled(self.strikethrough),
+            obfuscated: enabled(self.obfuscated),
+        }
+    }
+
+    /// iterates over the decorations of this style with their legacy code,
+    /// in the order they should be applied: `k`, `l`, `m`, `n`, `o`
+    pub(crate) fn decorations(&self) -> impl Iterator<Item = (char, bool)> {
+        [
+            ('k', self.obfuscated),
+            ('l', self.bold),
+            ('m', self.strikethrough),
+            ('n', self.underlined),
+            ('o', self.italic),
+        ]
+        .into_iter()
+        .map(|(code, enabled)| (code, enabled.unwrap_or_default()))
+    }
+
+    /// applies the decoration associated to the legacy `code`, if any
+    pub(crate) fn set_decoration(&mut self, code: char) -> bool {
+        let field = match code.to_ascii_lowercase() {
+            'k' => &mut self.obfuscated,
+            'l' => &mut self.bold,
+            'm' => &mut self.strikethrough,
+            'n' => &mut self.underlined,
+            'o' => &mut self.italic,
+            _ => return false,
+        };
+
+        *field = Some(true);
+        true
+    }
+}
+
+/// A Minecraft text (chat) component, limited to
+/// literal text, styling and children
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TextComponent {
+    /// literal text of this component
+    pub text: String,
+    /// formatting applied to this component and inherited by `extra`
+    pub style: Style,
+    /// child components appended after `text`
+    pub extra: Vec<TextComponent>,
+}
+
+impl TextComponent {
+    /// creates an unstyled component containing `text`
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// sets the color of this component
+    pub fn color(self, color: Color) -> Self {
+        let style = Style {
+            color: Some(color),
+            ..self.style
+        };
+
+        Self { style, ..self }
+    }
+
+    /// sets the style of this component
+    pub fn style(self, style: Style) -> Self {
+        Self { style, ..self }
+    }
+
+    /// appends `child` to the children of this component
+    pub fn push(mut self, child: TextComponent) -> Self {
+        self.extra.push(child);
+        self
+    }
+
+    /// Flattens the component tree into text runs, resolving
+    /// the effective style of each of them. Empty runs are skipped
+    pub fn runs(&self) -> Vec<(Style, &str)> {
+        fn walk<'a>(component: &'a TextComponent, parent: &Style, out: &mut Vec<(Style, &'a str)>) {
+            let style = component.style.inherit(parent);
+
+            if !component.text.is_empty() {
+                out.push((style, &component.text));
+            }
+
+            for child in &component.extra {
+                walk(child, &style, out);
+            }
+        }
+
+        let mut runs = vec![];
+        walk(self, &Style::default(), &mut runs);
+
+        runs
+    }
+
+    /// Concatenated text of the component tree, without any formatting
+    pub fn to_plain(&self) -> String {
+        self.runs().into_iter().map(|(_, text)| text).collect()
+    }
+}
+
+impl From<&str> for TextComponent {
+    fn from(value: &str) -> Self {
+        Self::text(value)
+    }
+}
+
+impl From<String> for TextComponent {
+    fn from(value: String) -> Self {
+        Self::text(value)
+    }
+}
+
+impl Display for TextComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.runs()
+            .into_iter()
+            .try_for_each(|(_, text)| f.write_str(text))
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Write;
+
+use super::{Color, Style, TextComponent};
+
+const RESET: &str = "\x1b[0m";
+
+/// SGR foreground code for a named color, following
+/// the usual 16 color terminal palette
+fn color_code(color: Color) -> Option<u8> {
+    let code = match color {
+        Color::Black => 30,
+        Color::DarkRed => 31,
+        Color::DarkGreen => 32,
+        Color::Gold => 33,
+        Color::DarkBlue => 34,
+        Color::DarkPurple => 35,
+        Color::DarkAqua => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::Red => 91,
+        Color::Green => 92,
+        Color::Yellow => 93,
+        Color::Blue => 94,
+        Color::LightPurple => 95,
+        Color::Aqua => 96,
+        Color::White => 97,
+        Color::Rgb(..) => return None,
+    };
+
+    Some(code)
+}
+
+fn push_sgr(out: &mut String, style: &Style) {
+    let mut params: Vec<String> = vec![];
+
+    let decorations = [
+        (style.bold, 1),
+        (style.italic, 3),
+        (style.underlined, 4),
+        (style.strikethrough, 9),
+    ];
+
+    params.extend(
+        decorations
+            .into_iter()
+            .filter(|(enabled, _)| enabled.unwrap_or_default())
+            .map(|(_, code)| code.to_string()),
+    );
+
+    match style.color {
+        Some(Color::Rgb(r, g, b)) => params.push(format!("38;2;{r};{g};{b}")),
+        Some(color) => params.extend(color_code(color).map(|code| code.to_string())),
+        None => {}
+    }
+
+    if !params.is_empty() {
+        let _ = write!(out, "\x1b[{}m", params.join(";"));
+    }
+}
+
+impl TextComponent {
+    /// Renders this component for a terminal using ANSI SGR escape sequences.
+    ///
+    /// Named colors use the 16 color palette of the terminal, while rgb colors
+    /// are emitted as 24-bit colors. Obfuscated text has no terminal counterpart
+    /// and is rendered as is. The output always ends with the terminal style reset
+    /// if any formatting was applied
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        let mut current = Style::default();
+
+        for (style, text) in self.runs() {
+            let style = Style {
+                obfuscated: None,
+                ..style.normalized()
+            };
+
+            if style != current {
+                if !current.is_plain() {
+                    out.push_str(RESET);
+                }
+
+                push_sgr(&mut out, &style);
+                current = style;
+            }
+
+            out.push_str(text);
+        }
+
+        if !current.is_plain() {
+            out.push_str(RESET);
+        }
+
+        out
+    }
+}
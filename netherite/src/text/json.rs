@@ -0,0 +1,128 @@
+use serde::{de::Error as _, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::{Color, Style, TextComponent};
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Color::from_name(&name).ok_or_else(|| D::Error::custom(format!("invalid color {name}")))
+    }
+}
+
+impl Serialize for TextComponent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Style {
+            color,
+            bold,
+            italic,
+            underlined,
+            strikethrough,
+            obfuscated,
+        } = &self.style;
+
+        let decorations = [
+            ("bold", bold),
+            ("italic", italic),
+            ("underlined", underlined),
+            ("strikethrough", strikethrough),
+            ("obfuscated", obfuscated),
+        ];
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("text", &self.text)?;
+
+        if let Some(color) = color {
+            map.serialize_entry("color", color)?;
+        }
+
+        for (key, value) in decorations {
+            if let Some(value) = value {
+                map.serialize_entry(key, value)?;
+            }
+        }
+
+        if !self.extra.is_empty() {
+            map.serialize_entry("extra", &self.extra)?;
+        }
+
+        map.end()
+    }
+}
+
+fn from_object(mut object: Map<String, Value>) -> Result<TextComponent, String> {
+    let text = match object.remove("text") {
+        Some(Value::String(text)) => text,
+        Some(value @ (Value::Number(_) | Value::Bool(_))) => value.to_string(),
+        Some(_) => return Err("text must be a string".into()),
+        None => String::new(),
+    };
+
+    let mut decoration = |key: &str| match object.remove(key) {
+        Some(Value::Bool(value)) => Ok(Some(value)),
+        Some(_) => Err(format!("{key} must be a boolean")),
+        None => Ok(None),
+    };
+
+    let style = Style {
+        color: None,
+        bold: decoration("bold")?,
+        italic: decoration("italic")?,
+        underlined: decoration("underlined")?,
+        strikethrough: decoration("strikethrough")?,
+        obfuscated: decoration("obfuscated")?,
+    };
+
+    let color = match object.remove("color") {
+        Some(Value::String(name)) => Color::from_name(&name),
+        Some(_) => return Err("color must be a string".into()),
+        None => None,
+    };
+
+    let extra = match object.remove("extra") {
+        Some(Value::Array(extra)) => extra
+            .into_iter()
+            .map(from_value)
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("extra must be an array".into()),
+        None => vec![],
+    };
+
+    let style = Style { color, ..style };
+    Ok(TextComponent { text, style, extra })
+}
+
+fn from_value(value: Value) -> Result<TextComponent, String> {
+    match value {
+        Value::String(text) => Ok(TextComponent::text(text)),
+        Value::Number(_) | Value::Bool(_) => Ok(TextComponent::text(value.to_string())),
+        Value::Object(object) => from_object(object),
+        Value::Array(components) => {
+            let mut components = components.into_iter().map(from_value);
+
+            let mut parent = components.next().ok_or("empty component array")??;
+            for component in components {
+                parent.extra.push(component?);
+            }
+
+            Ok(parent)
+        }
+        Value::Null => Err("invalid null component".into()),
+    }
+}
+
+/// Accepts every JSON shape of a component: a plain string, an object
+/// or an array of components. Content other than literal text (like
+/// `translate` or `score`) is ignored, as well as unknown colors
+impl<'de> Deserialize<'de> for TextComponent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        from_value(value).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,156 @@
+use super::{Color, Style, TextComponent};
+
+/// Formatting marker used by the vanilla client
+pub const SECTION: char = '§';
+
+/// Formatting marker commonly used for user input,
+/// for example in admin commands and config files
+pub const AMPERSAND: char = '&';
+
+/// parses the `x` hex color sequence (`§x§R§R§G§G§B§B`) following a marker
+fn parse_hex(rest: &str, marker: char) -> Option<Color> {
+    let mut chars = rest.chars();
+    let mut hex = String::with_capacity(6);
+
+    for _ in 0..6 {
+        (chars.next()? == marker).then_some(())?;
+        hex.push(chars.next().filter(char::is_ascii_hexdigit)?);
+    }
+
+    Color::from_name(&format!("#{hex}"))
+}
+
+fn push_color(out: &mut String, color: Color, marker: char) {
+    out.push(marker);
+
+    match color.code() {
+        Some(code) => out.push(code),
+        None => {
+            let (r, g, b) = color.rgb();
+            out.push('x');
+
+            format!("{r:02x}{g:02x}{b:02x}").chars().for_each(|digit| {
+                out.push(marker);
+                out.push(digit);
+            })
+        }
+    }
+}
+
+impl TextComponent {
+    /// Parses a string formatted with legacy codes prefixed by `marker`
+    /// (usually [`SECTION`] or [`AMPERSAND`]).
+    ///
+    /// Like in the vanilla client, color codes reset every decoration
+    /// previously applied, and `r` resets the style altogether. The
+    /// BungeeCord hex color format (`§x§R§R§G§G§B§B`) is supported.
+    /// Markers not followed by a valid code are kept as literal text
+    pub fn from_legacy(input: &str, marker: char) -> Self {
+        let mut segments = vec![];
+        let mut style = Style::default();
+        let mut text = String::new();
+
+        let mut flush = |text: &mut String, style: Style| {
+            if !text.is_empty() {
+                let segment = TextComponent::text(std::mem::take(text)).style(style);
+                segments.push(segment);
+            }
+        };
+
+        let mut rest = input;
+        while let Some(c) = rest.chars().next() {
+            rest = &rest[c.len_utf8()..];
+
+            let Some(code) = rest.chars().next().filter(|_| c == marker) else {
+                text.push(c);
+                continue;
+            };
+
+            let mut next = Style::default();
+            let mut consumed = code.len_utf8();
+
+            if let Some(color) = Color::from_code(code) {
+                next.color = Some(color);
+            } else if code.eq_ignore_ascii_case(&'x') {
+                match parse_hex(&rest[consumed..], marker) {
+                    Some(color) => next.color = Some(color),
+                    None => {
+                        text.push(c);
+                        continue;
+                    }
+                }
+
+                consumed += 6 * (marker.len_utf8() + 1);
+            } else if code.eq_ignore_ascii_case(&'r') {
+                // reset: leave `next` unstyled
+            } else if next.set_decoration(code) {
+                next = next.inherit(&style);
+            } else {
+                text.push(c);
+                continue;
+            }
+
+            flush(&mut text, style);
+            style = next;
+            rest = &rest[consumed..];
+        }
+
+        flush(&mut text, style);
+
+        match segments.len() {
+            0 => TextComponent::default(),
+            1 => segments.remove(0),
+            _ => TextComponent {
+                extra: segments,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Renders this component as a legacy formatted string using `marker`
+    /// as the code prefix. Only the codes needed to switch between the
+    /// styles of two consecutive runs are emitted
+    pub fn to_legacy(&self, marker: char) -> String {
+        let mut out = String::new();
+        let mut current = Style::default();
+
+        for (style, text) in self.runs() {
+            let style = style.normalized();
+
+            if style != current {
+                let additive = style.color == current.color
+                    && current
+                        .decorations()
+                        .zip(style.decorations())
+                        .all(|((_, was), (_, is))| !was || is);
+
+                let previous = match additive {
+                    true => current,
+                    false => {
+                        match style.color {
+                            Some(color) => push_color(&mut out, color, marker),
+                            None => out.extend([marker, 'r']),
+                        };
+
+                        Style::default()
+                    }
+                };
+
+                let added = style
+                    .decorations()
+                    .zip(previous.decorations())
+                    .filter(|((_, is), (_, was))| *is && !was);
+
+                for ((code, _), _) in added {
+                    out.extend([marker, code]);
+                }
+
+                current = style;
+            }
+
+            out.push_str(text);
+        }
+
+        out
+    }
+}
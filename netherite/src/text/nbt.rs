@@ -0,0 +1,199 @@
+use bytes::{Buf, BufMut};
+
+use super::{Color, Style, TextComponent};
+use crate::{
+    encoding::{
+        str::Str,
+        versioned::{VersionedDeserialize, VersionedSerialize},
+    },
+    nbt::{Compound, ListTag, Nbt, Tag},
+    protocol::ProtocolVersion,
+    DeError, Deserialize, Serialize,
+};
+
+impl From<&TextComponent> for Tag {
+    fn from(value: &TextComponent) -> Self {
+        if value.style == Style::default() && value.extra.is_empty() {
+            return Tag::String(value.text.clone());
+        }
+
+        let Style {
+            color,
+            bold,
+            italic,
+            underlined,
+            strikethrough,
+            obfuscated,
+        } = value.style;
+
+        let mut compound = Compound::new().with("text", value.text.as_str());
+
+        if let Some(color) = color {
+            compound.insert("color", color.name());
+        }
+
+        let decorations = [
+            ("bold", bold),
+            ("italic", italic),
+            ("underlined", underlined),
+            ("strikethrough", strikethrough),
+            ("obfuscated", obfuscated),
+        ];
+
+        for (key, value) in decorations {
+            if let Some(value) = value {
+                compound.insert(key, value);
+            }
+        }
+
+        if !value.extra.is_empty() {
+            // children are always compounds, lists can't mix types
+            let extra = value.extra.iter().map(|child| match Tag::from(child) {
+                Tag::String(text) => Compound::new().with("text", text).into(),
+                tag => tag,
+            });
+
+            let extra = ListTag::new(extra.collect()).expect("all compounds");
+            compound.insert("extra", extra);
+        }
+
+        Tag::Compound(compound)
+    }
+}
+
+impl TryFrom<&Tag> for TextComponent {
+    type Error = DeError;
+
+    /// Accepts string, compound and list tags. Like with json, content
+    /// other than literal text is ignored, as well as unknown colors
+    fn try_from(value: &Tag) -> Result<Self, Self::Error> {
+        let compound = match value {
+            Tag::String(text) => return Ok(TextComponent::text(text.as_str())),
+            Tag::List(list) => {
+                let mut items = list.items().iter().map(TextComponent::try_from);
+
+                let mut parent = items.next().ok_or(DeError::InvalidData)??;
+                for item in items {
+                    parent.extra.push(item?);
+                }
+
+                return Ok(parent);
+            }
+            Tag::Compound(compound) => compound,
+            _ => return Err(DeError::InvalidData),
+        };
+
+        // the empty key is used for lists of mixed types
+        let text = compound.get("text").or_else(|| compound.get(""));
+        let text = match text {
+            Some(Tag::String(text)) => text.clone(),
+            Some(tag) => tag.as_i64().ok_or(DeError::InvalidData)?.to_string(),
+            None => String::new(),
+        };
+
+        let decoration = |key: &str| compound.get(key).and_then(Tag::as_i64).map(|v| v != 0);
+        let style = Style {
+            color: compound
+                .get("color")
+                .and_then(Tag::as_str)
+                .and_then(Color::from_name),
+            bold: decoration("bold"),
+            italic: decoration("italic"),
+            underlined: decoration("underlined"),
+            strikethrough: decoration("strikethrough"),
+            obfuscated: decoration("obfuscated"),
+        };
+
+        let extra = match compound.get("extra") {
+            Some(Tag::List(list)) => list
+                .items()
+                .iter()
+                .map(TextComponent::try_from)
+                .collect::<Result<_, _>>()?,
+            Some(_) => return Err(DeError::InvalidData),
+            None => vec![],
+        };
+
+        Ok(TextComponent { text, style, extra })
+    }
+}
+
+/// A text component as it's encoded on the wire:
+/// a JSON string before 1.20.3 and an NBT tag since.
+///
+/// It's forwarded as is, without being decoded. The variant
+/// must match the protocol version used to serialize it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Chat {
+    /// JSON encoded component
+    Json(Str),
+    /// NBT encoded component
+    Nbt(Tag),
+}
+
+impl Chat {
+    /// encodes `component` as NBT, for 1.20.3+
+    pub fn nbt(component: &TextComponent) -> Self {
+        Self::Nbt(component.into())
+    }
+
+    /// encodes `component` as JSON, for versions before 1.20.3
+    #[cfg(feature = "serde_json")]
+    pub fn json(component: &TextComponent) -> Self {
+        let json = serde_json::to_string(component).expect("components are valid json");
+        Self::Json(json.into())
+    }
+
+    /// encodes `component` in the format used by `version`
+    #[cfg(feature = "serde_json")]
+    pub fn new(component: &TextComponent, version: ProtocolVersion) -> Self {
+        match is_nbt(version) {
+            true => Self::nbt(component),
+            false => Self::json(component),
+        }
+    }
+
+    /// Decodes the component. JSON components
+    /// require the `serde_json` feature
+    pub fn component(&self) -> Result<TextComponent, DeError> {
+        match self {
+            Chat::Nbt(tag) => tag.try_into(),
+            #[cfg(feature = "serde_json")]
+            Chat::Json(json) => serde_json::from_str(json).map_err(|_| DeError::InvalidData),
+            #[cfg(not(feature = "serde_json"))]
+            Chat::Json(_) => Err(DeError::InvalidData),
+        }
+    }
+}
+
+fn is_nbt(version: ProtocolVersion) -> bool {
+    version >= ProtocolVersion::V1_20_3
+}
+
+impl VersionedSerialize for Chat {
+    fn serialize_versioned(&self, buf: impl BufMut, version: ProtocolVersion) {
+        match self {
+            Chat::Json(json) => json.serialize(buf),
+            Chat::Nbt(tag) => Nbt::write_root(Some(tag), buf, Nbt::named(version)),
+        }
+    }
+
+    fn size_versioned(&self, version: ProtocolVersion) -> usize {
+        match self {
+            Chat::Json(json) => json.size(),
+            Chat::Nbt(tag) => Nbt::root_size(Some(tag), Nbt::named(version)),
+        }
+    }
+}
+
+impl VersionedDeserialize for Chat {
+    fn deserialize_versioned(buffer: impl Buf, version: ProtocolVersion) -> Result<Self, DeError> {
+        match is_nbt(version) {
+            true => match Nbt::deserialize(buffer)? {
+                Nbt(Some(tag)) => Ok(Chat::Nbt(tag)),
+                Nbt(None) => Err(DeError::InvalidData),
+            },
+            false => Str::deserialize(buffer).map(Chat::Json),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use super::{
+    legacy::{AMPERSAND, SECTION},
+    Color, Style, TextComponent,
+};
+
+fn bold() -> Style {
+    Style {
+        bold: Some(true),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn parse_plain() {
+    let component = TextComponent::from_legacy("hello", SECTION);
+    assert_eq!(component, TextComponent::text("hello"));
+}
+
+#[test]
+fn parse_colors_reset_decorations() {
+    let component = TextComponent::from_legacy("§c§lhi §ethere", SECTION);
+
+    let expected = TextComponent::default()
+        .push(TextComponent::text("hi ").style(Style {
+            color: Some(Color::Red),
+            ..bold()
+        }))
+        .push(TextComponent::text("there").color(Color::Yellow));
+
+    assert_eq!(component, expected);
+}
+
+#[test]
+fn parse_reset() {
+    let component = TextComponent::from_legacy("&a&ngreen&rplain", AMPERSAND);
+    let runs = component.runs();
+
+    assert_eq!(runs.len(), 2);
+    assert!(runs[1].0.is_plain());
+    assert_eq!(component.to_plain(), "greenplain");
+}
+
+#[test]
+fn parse_invalid_codes_are_literal() {
+    let component = TextComponent::from_legacy("you & me &z &", AMPERSAND);
+    assert_eq!(component, TextComponent::text("you & me &z &"));
+}
+
+#[test]
+fn parse_hex() {
+    let component = TextComponent::from_legacy("§x§f§f§0§0§a§aPink §x", SECTION);
+
+    let expected = TextComponent::text("Pink §x").color(Color::Rgb(0xff, 0x00, 0xaa));
+    assert_eq!(component, expected);
+}
+
+#[test]
+fn legacy_nested() {
+    let component = TextComponent::text("a")
+        .color(Color::Gold)
+        .push(TextComponent::text("b").style(bold()))
+        .push(TextComponent::text("c").color(Color::Aqua))
+        .push(TextComponent::text("d"));
+
+    assert_eq!(component.to_legacy(SECTION), "§6a§lb§bc§6d");
+}
+
+#[test]
+fn legacy_explicit_false() {
+    let component = TextComponent::text("a")
+        .style(bold())
+        .push(TextComponent::text("b").style(Style {
+            bold: Some(false),
+            ..Default::default()
+        }));
+
+    assert_eq!(component.to_legacy(AMPERSAND), "&la&rb");
+}
+
+#[test]
+fn legacy_roundtrip() {
+    let input = "§7[§c§lAdmin§7] §x§1§2§3§4§5§6§oSteve§r: hi";
+    let component = TextComponent::from_legacy(input, SECTION);
+
+    assert_eq!(component.to_legacy(SECTION), input);
+}
+
+#[test]
+fn ansi() {
+    let component = TextComponent::text("plain ")
+        .push(TextComponent::text("red").color(Color::Red))
+        .push(
+            TextComponent::text("bold")
+                .style(bold())
+                .push(TextComponent::text("rgb").color(Color::Rgb(1, 2, 3))),
+        );
+
+    assert_eq!(
+        component.to_ansi(),
+        "plain \x1b[91mred\x1b[0m\x1b[1mbold\x1b[0m\x1b[1;38;2;1;2;3mrgb\x1b[0m"
+    );
+    assert_eq!(component.to_plain(), "plain redboldrgb");
+}
+
+#[test]
+fn ansi_unstyled() {
+    let component = TextComponent::from_legacy("no §rcodes", SECTION);
+    assert_eq!(component.to_ansi(), "no codes");
+}
+
+mod nbt {
+    use super::super::{Chat, Color, TextComponent};
+    use crate::{
+        encoding::versioned::{VersionedDeserialize, VersionedSerialize},
+        nbt::{Compound, Tag},
+        protocol::ProtocolVersion,
+    };
+
+    #[test]
+    fn plain_is_string() {
+        let tag = Tag::from(&TextComponent::text("hi"));
+        assert_eq!(tag, Tag::String("hi".into()));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let component = TextComponent::text("hi")
+            .color(Color::Red)
+            .push(TextComponent::text("!"));
+
+        let tag = Tag::from(&component);
+        let compound = tag.as_compound().unwrap();
+        assert_eq!(compound.get("color").and_then(Tag::as_str), Some("red"));
+        assert_eq!(TextComponent::try_from(&tag).unwrap(), component);
+    }
+
+    #[test]
+    fn chat_versions() {
+        let chat = Chat::nbt(&TextComponent::text("a"));
+        let version = ProtocolVersion::V1_20_3;
+
+        let mut buf = vec![];
+        chat.serialize_versioned(&mut buf, version);
+        assert_eq!(buf, [0x08, 0x00, 0x01, b'a']);
+        assert_eq!(chat.size_versioned(version), buf.len());
+
+        let parsed = Chat::deserialize_versioned(&buf[..], version).unwrap();
+        assert_eq!(parsed, chat);
+
+        let json = Chat::deserialize_versioned(&b"\x03\"a\""[..], ProtocolVersion::V1_20).unwrap();
+        assert_eq!(json, Chat::Json(String::from("\"a\"").into()));
+    }
+
+    #[test]
+    fn invalid_tag() {
+        let tag = Tag::from(Compound::new().with("extra", 1));
+        assert!(TextComponent::try_from(&tag).is_err());
+    }
+}
+
+#[cfg(feature = "serde_json")]
+mod json {
+    use super::super::{Color, TextComponent};
+
+    #[test]
+    fn deserialize_shapes() {
+        let json = r##"["", {"text": "a", "color": "gold", "extra": ["b"]}, {"text": "c", "color": "#010203", "bold": true}]"##;
+        let component: TextComponent = serde_json::from_str(json).unwrap();
+
+        assert_eq!(component.to_plain(), "abc");
+        assert_eq!(component.extra[0].style.color, Some(Color::Gold));
+        assert_eq!(component.extra[1].style.color, Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(component.extra[1].style.bold, Some(true));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let component = TextComponent::text("hi")
+            .color(Color::Red)
+            .push(TextComponent::text("!"));
+
+        let json = serde_json::to_string(&component).unwrap();
+        assert_eq!(
+            json,
+            r#"{"text":"hi","color":"red","extra":[{"text":"!"}]}"#
+        );
+
+        let parsed: TextComponent = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, component);
+    }
+}
@@ -0,0 +1,122 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bytes::Bytes;
+use thiserror::Error;
+
+use crate::{
+    packet::RawPacket,
+    protocol::{Direction, ProtocolVersion, State},
+    registry::{Key, PacketRegistry},
+    DeError,
+};
+
+mod rules;
+#[cfg(test)]
+mod test;
+
+/// Error translating a packet
+#[derive(Debug, Error)]
+#[error("translating {name}: {source}")]
+pub struct TranslateError {
+    /// name of the packet
+    pub name: String,
+    /// error reading the packet
+    pub source: DeError,
+}
+
+/// Protocol versions involved in a translation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Versions {
+    /// version the packet is encoded with
+    pub from: ProtocolVersion,
+    /// version the packet is being translated to
+    pub to: ProtocolVersion,
+}
+
+type Rule = Box<dyn Fn(Bytes, Versions) -> Result<Bytes, DeError> + Send + Sync>;
+
+/// Translates packets between two protocol versions.
+///
+/// Packet ids are remapped through the logical names of a [`PacketRegistry`],
+/// which must contain the tables of both versions. Packets whose layout
+/// changed are rewritten by a rule registered for their name: bundled rules
+/// use the packet names of minecraft-data and cover keep alives, disconnects,
+/// system chat, player positions and plugin messages.
+///
+/// Everything else is forwarded without copying its data. Translating
+/// text components between JSON and NBT requires the `serde_json` feature
+pub struct Translator {
+    registry: Arc<PacketRegistry>,
+    versions: Versions,
+    rules: HashMap<(State, Direction, &'static str), Rule>,
+}
+
+impl Translator {
+    /// Translator from `from` to `to` with the bundled rules
+    pub fn new(registry: Arc<PacketRegistry>, from: ProtocolVersion, to: ProtocolVersion) -> Self {
+        let translator = Self {
+            registry,
+            versions: Versions { from, to },
+            rules: HashMap::new(),
+        };
+
+        rules::bundled(translator)
+    }
+
+    /// versions this translator converts between
+    pub fn versions(&self) -> Versions {
+        self.versions
+    }
+
+    /// Registers a rule rewriting the data of packet `name`, replacing
+    /// the bundled one. Rules receive the data encoded with `from`
+    /// and return it encoded with `to`
+    pub fn rule<F>(
+        mut self,
+        state: State,
+        direction: Direction,
+        name: &'static str,
+        rule: F,
+    ) -> Self
+    where
+        F: Fn(Bytes, Versions) -> Result<Bytes, DeError> + Send + Sync + 'static,
+    {
+        self.rules.insert((state, direction, name), Box::new(rule));
+        self
+    }
+
+    /// Translates `packet`, received in `state` travelling in `direction`.
+    ///
+    /// Packets unknown to the source table are forwarded as they are.
+    /// Returns `None` for packets that don't exist in the target version
+    pub fn translate(
+        &self,
+        state: State,
+        direction: Direction,
+        packet: RawPacket,
+    ) -> Result<Option<RawPacket>, TranslateError> {
+        let Versions { from, to } = self.versions;
+        if from == to {
+            return Ok(Some(packet));
+        }
+
+        let source = Key::new(from, state, direction);
+        let Some(name) = self.registry.resolve(source, &packet) else {
+            return Ok(Some(packet));
+        };
+
+        let Some(packet_id) = self.registry.id(Key::new(to, state, direction), name) else {
+            return Ok(None);
+        };
+
+        let data = match self.rules.get(&(state, direction, name)) {
+            Some(rule) => rule(packet.data, self.versions).map_err(|source| TranslateError {
+                name: name.to_string(),
+                source,
+            })?,
+            None => packet.data,
+        };
+
+        Ok(Some(RawPacket { packet_id, data }))
+    }
+}
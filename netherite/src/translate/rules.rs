@@ -0,0 +1,279 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use super::{Translator, Versions};
+use crate::{
+    encoding::{
+        str::Str,
+        varint::VarInt,
+        versioned::{VersionedDeserialize, VersionedSerialize},
+    },
+    protocol::{Direction, ProtocolVersion as V, State},
+    text::Chat,
+    DeError, Deserialize, Serialize,
+};
+
+/// channels renamed with the namespaced identifiers of 1.13
+const CHANNELS: [(&str, &str); 3] = [
+    ("MC|Brand", "minecraft:brand"),
+    ("REGISTER", "minecraft:register"),
+    ("UNREGISTER", "minecraft:unregister"),
+];
+
+pub(super) fn bundled(translator: Translator) -> Translator {
+    use Direction::*;
+    use State::*;
+
+    translator
+        .rule(Play, Clientbound, "keep_alive", keep_alive)
+        .rule(Play, Serverbound, "keep_alive", keep_alive)
+        .rule(Play, Clientbound, "kick_disconnect", disconnect)
+        .rule(Configuration, Clientbound, "disconnect", disconnect)
+        .rule(Play, Clientbound, "chat", legacy_chat)
+        .rule(Play, Clientbound, "system_chat", system_chat)
+        .rule(Play, Clientbound, "position", clientbound_position)
+        .rule(Play, Serverbound, "position", serverbound_position)
+        .rule(Play, Clientbound, "custom_payload", custom_payload)
+        .rule(Play, Serverbound, "custom_payload", custom_payload)
+}
+
+/// whether the layout of the packet is the same in both versions
+fn same<T: PartialEq>(Versions { from, to }: Versions, layout: impl Fn(V) -> T) -> bool {
+    layout(from) == layout(to)
+}
+
+fn write(size: usize, write: impl FnOnce(&mut BytesMut)) -> Bytes {
+    let mut buf = BytesMut::with_capacity(size);
+    write(&mut buf);
+
+    buf.freeze()
+}
+
+/// keep alive ids were VarInts before 1.12.2
+fn keep_alive(mut data: Bytes, versions: Versions) -> Result<Bytes, DeError> {
+    let legacy = |version| version < V::V1_12_2;
+    if same(versions, legacy) {
+        return Ok(data);
+    }
+
+    let id = match legacy(versions.from) {
+        true => VarInt::deserialize(&mut data)?.0 as i64,
+        false => i64::deserialize(&mut data)?,
+    };
+
+    let data = match legacy(versions.to) {
+        true => write(5, |buf| VarInt(id as i32).serialize(buf)),
+        false => write(8, |buf| id.serialize(buf)),
+    };
+
+    Ok(data)
+}
+
+fn is_nbt(version: V) -> bool {
+    version >= V::V1_20_3
+}
+
+/// converts a text component to the format of `to`
+fn chat(chat: Chat, to: V) -> Result<Chat, DeError> {
+    match (&chat, is_nbt(to)) {
+        (Chat::Nbt(_), true) | (Chat::Json(_), false) => Ok(chat),
+        (Chat::Json(_), true) => Ok(Chat::nbt(&chat.component()?)),
+        #[cfg(feature = "serde_json")]
+        (Chat::Nbt(_), false) => Ok(Chat::json(&chat.component()?)),
+        #[cfg(not(feature = "serde_json"))]
+        (Chat::Nbt(_), false) => Err(DeError::InvalidData),
+    }
+}
+
+fn disconnect(mut data: Bytes, versions: Versions) -> Result<Bytes, DeError> {
+    if same(versions, is_nbt) {
+        return Ok(data);
+    }
+
+    let reason = Chat::deserialize_versioned(&mut data, versions.from)?;
+    let reason = chat(reason, versions.to)?;
+
+    let size = reason.size_versioned(versions.to);
+    Ok(write(size, |buf| {
+        reason.serialize_versioned(buf, versions.to)
+    }))
+}
+
+fn system_chat(mut data: Bytes, versions: Versions) -> Result<Bytes, DeError> {
+    if same(versions, is_nbt) {
+        return Ok(data);
+    }
+
+    let content = Chat::deserialize_versioned(&mut data, versions.from)?;
+    let content = chat(content, versions.to)?;
+    let overlay = bool::deserialize(&mut data)?;
+
+    let size = content.size_versioned(versions.to) + 1;
+    Ok(write(size, |buf| {
+        content.serialize_versioned(&mut *buf, versions.to);
+        overlay.serialize(buf);
+    }))
+}
+
+/// chat messages got the sender UUID with 1.16, and were
+/// replaced by signed and system chat messages with 1.19
+fn legacy_chat(data: Bytes, versions: Versions) -> Result<Bytes, DeError> {
+    let sender = |version| version >= V::V1_16;
+    if same(versions, sender) {
+        return Ok(data);
+    }
+
+    let data = match sender(versions.from) {
+        true => data.len().checked_sub(16).map(|len| data.slice(..len)),
+        false => Some(write(data.len() + 16, |buf| {
+            buf.put_slice(&data);
+            buf.put_u128(0);
+        })),
+    };
+
+    data.ok_or(DeError::Eof)
+}
+
+/// layout of the clientbound player position
+fn position_layout(version: V) -> u8 {
+    match version {
+        v if v < V::V1_9 => 0,    // no teleport id
+        v if v < V::V1_17 => 1,   // teleport id
+        v if v < V::V1_19_4 => 2, // dismount vehicle
+        v if v < V::V1_21_2 => 3, // no dismount vehicle
+        _ => 4,                   // velocity, teleport id first
+    }
+}
+
+#[derive(Default)]
+struct PlayerPosition {
+    teleport_id: i32,
+    position: [f64; 3],
+    velocity: [f64; 3],
+    rotation: [f32; 2],
+    flags: i32,
+    dismount: bool,
+}
+
+impl PlayerPosition {
+    fn read(mut data: Bytes, layout: u8) -> Result<Self, DeError> {
+        let mut packet = Self::default();
+        let coordinates = |buf: &mut Bytes| -> Result<[f64; 3], DeError> {
+            Ok([
+                f64::deserialize(&mut *buf)?,
+                f64::deserialize(&mut *buf)?,
+                f64::deserialize(&mut *buf)?,
+            ])
+        };
+
+        if layout == 4 {
+            packet.teleport_id = VarInt::deserialize(&mut data)?.0;
+            packet.position = coordinates(&mut data)?;
+            packet.velocity = coordinates(&mut data)?;
+        } else {
+            packet.position = coordinates(&mut data)?;
+        }
+
+        packet.rotation = [f32::deserialize(&mut data)?, f32::deserialize(&mut data)?];
+        packet.flags = match layout {
+            4 => i32::deserialize(&mut data)?,
+            _ => i8::deserialize(&mut data)? as i32,
+        };
+
+        if (1..=3).contains(&layout) {
+            packet.teleport_id = VarInt::deserialize(&mut data)?.0;
+        }
+
+        if layout == 2 {
+            packet.dismount = bool::deserialize(&mut data)?;
+        }
+
+        Ok(packet)
+    }
+
+    fn write(&self, layout: u8) -> Bytes {
+        write(64, |buf| {
+            let coordinates = |buf: &mut BytesMut, coordinates: [f64; 3]| {
+                coordinates.iter().for_each(|c| c.serialize(&mut *buf))
+            };
+
+            if layout == 4 {
+                VarInt(self.teleport_id).serialize(&mut *buf);
+                coordinates(buf, self.position);
+                coordinates(buf, self.velocity);
+            } else {
+                coordinates(buf, self.position);
+            }
+
+            self.rotation.iter().for_each(|r| r.serialize(&mut *buf));
+
+            match layout {
+                4 => self.flags.serialize(&mut *buf),
+                // only relative position and rotation flags existed
+                _ => ((self.flags & 0x1F) as i8).serialize(&mut *buf),
+            }
+
+            if (1..=3).contains(&layout) {
+                VarInt(self.teleport_id).serialize(&mut *buf);
+            }
+
+            if layout == 2 {
+                self.dismount.serialize(buf);
+            }
+        })
+    }
+}
+
+fn clientbound_position(data: Bytes, versions: Versions) -> Result<Bytes, DeError> {
+    if same(versions, position_layout) {
+        return Ok(data);
+    }
+
+    let packet = PlayerPosition::read(data, position_layout(versions.from))?;
+    Ok(packet.write(position_layout(versions.to)))
+}
+
+/// the on ground boolean became a flags byte with 1.21.2
+fn serverbound_position(mut data: Bytes, versions: Versions) -> Result<Bytes, DeError> {
+    let flags = |version| version >= V::V1_21_2;
+    if same(versions, flags) {
+        return Ok(data);
+    }
+
+    if data.remaining() < 24 {
+        return Err(DeError::Eof);
+    }
+
+    let position = data.split_to(24);
+    let flags = match flags(versions.from) {
+        // keep only on ground
+        true => u8::deserialize(&mut data)? & 0x01,
+        false => bool::deserialize(&mut data)? as u8,
+    };
+
+    Ok(write(25, |buf| {
+        buf.put_slice(&position);
+        buf.put_u8(flags);
+    }))
+}
+
+/// channels got namespaced identifiers with 1.13
+fn custom_payload(mut data: Bytes, versions: Versions) -> Result<Bytes, DeError> {
+    let namespaced = |version| version >= V::V1_13;
+    if same(versions, namespaced) {
+        return Ok(data);
+    }
+
+    let channel = Str::deserialize(&mut data)?;
+    let renamed = CHANNELS
+        .iter()
+        .find_map(|&(legacy, modern)| match namespaced(versions.to) {
+            true => (*channel == *legacy).then_some(modern),
+            false => (*channel == *modern).then_some(legacy),
+        });
+
+    let channel = renamed.unwrap_or(&channel);
+    Ok(write(channel.size() + data.len(), |buf| {
+        channel.serialize(&mut *buf);
+        buf.put_slice(&data);
+    }))
+}
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use super::Translator;
+use crate::{
+    packet::RawPacket,
+    protocol::{Direction, ProtocolVersion as V, State},
+    registry::PacketRegistry,
+};
+
+const REGISTRY: &str = "
+[340 play clientbound]
+0x1A kick_disconnect
+0x1F keep_alive
+0x2F position
+0x18 custom_payload
+0x0F chat
+
+[47 play clientbound]
+0x40 kick_disconnect
+0x00 keep_alive
+0x08 position
+0x3F custom_payload
+
+[765 play clientbound]
+0x1B kick_disconnect
+0x24 keep_alive
+0x3E position
+0x10 custom_payload
+0x69 system_chat
+
+[764 play clientbound]
+0x1B kick_disconnect
+0x24 keep_alive
+0x3E position
+0x10 custom_payload
+0x67 system_chat
+";
+
+fn setup(from: V, to: V) -> Translator {
+    let registry: PacketRegistry = REGISTRY.parse().unwrap();
+    Translator::new(Arc::new(registry), from, to)
+}
+
+fn translate(translator: &Translator, packet_id: i32, data: Bytes) -> Option<RawPacket> {
+    let packet = RawPacket { packet_id, data };
+    translator
+        .translate(State::Play, Direction::Clientbound, packet)
+        .unwrap()
+}
+
+#[test]
+fn passthrough() {
+    let translator = setup(V::V1_20_2, V::V1_20_3);
+    let data = Bytes::from_static(&[0; 8]);
+
+    let packet = translate(&translator, 0x24, data.clone()).unwrap();
+    assert_eq!(packet.packet_id, 0x24);
+    assert_eq!(packet.data.as_ptr(), data.as_ptr(), "data was copied");
+
+    // unknown ids are forwarded as they are
+    let packet = translate(&translator, 0x70, data.clone()).unwrap();
+    assert_eq!(packet.packet_id, 0x70);
+}
+
+#[test]
+fn missing_in_target() {
+    let translator = setup(V::V1_12_2, V::V1_8);
+    assert!(translate(&translator, 0x0F, Bytes::new()).is_none());
+}
+
+#[test]
+fn keep_alive() {
+    let translator = setup(V::V1_12_2, V::V1_8);
+    let packet = translate(
+        &translator,
+        0x1F,
+        Bytes::from_static(&[0, 0, 0, 0, 0, 0, 1, 0]),
+    );
+
+    let packet = packet.unwrap();
+    assert_eq!(packet.packet_id, 0x00);
+    assert_eq!(packet.data.as_ref(), [0x80, 0x02]);
+
+    let translator = setup(V::V1_8, V::V1_20_3);
+    let packet = translate(&translator, 0x00, packet.data).unwrap();
+    assert_eq!(packet.data.as_ref(), [0, 0, 0, 0, 0, 0, 1, 0]);
+}
+
+#[test]
+fn position() {
+    let mut modern = BytesMut::new();
+    modern.put_f64(1.0);
+    modern.put_f64(2.0);
+    modern.put_f64(3.0);
+    modern.put_f32(90.0);
+    modern.put_f32(0.0);
+    modern.put_i8(0x03);
+    modern.put_u8(7); // teleport id
+
+    let translator = setup(V::V1_20_3, V::V1_8);
+    let packet = translate(&translator, 0x3E, modern.clone().freeze()).unwrap();
+
+    assert_eq!(packet.packet_id, 0x08);
+    assert_eq!(packet.data.as_ref(), &modern[..modern.len() - 1]);
+
+    let translator = setup(V::V1_8, V::V1_20_3);
+    let packet = translate(&translator, 0x08, packet.data).unwrap();
+    assert_eq!(
+        packet.data.as_ref(),
+        [&modern[..modern.len() - 1], &[0]].concat()
+    );
+}
+
+#[test]
+fn plugin_channels() {
+    let translator = setup(V::V1_8, V::V1_12_2);
+    let data = Bytes::from_static(b"\x08MC|Brand\x07vanilla");
+    assert_eq!(
+        translate(&translator, 0x3F, data.clone()).unwrap().data,
+        data
+    );
+
+    let translator = setup(V::V1_12_2, V::V1_20_3);
+    let packet = translate(&translator, 0x18, data).unwrap();
+    assert_eq!(packet.packet_id, 0x10);
+    assert_eq!(packet.data.as_ref(), b"\x0fminecraft:brand\x07vanilla");
+}
+
+#[test]
+fn system_chat() {
+    let translator = setup(V::V1_20_2, V::V1_20_3);
+
+    let data = Bytes::from_static(b"\x04\"hi\"\x01");
+    let result = translator.translate(
+        State::Play,
+        Direction::Clientbound,
+        RawPacket {
+            packet_id: 0x67,
+            data,
+        },
+    );
+
+    // json components can only be read with serde_json
+    if cfg!(feature = "serde_json") {
+        let packet = result.unwrap().unwrap();
+        assert_eq!(packet.packet_id, 0x69);
+
+        // nameless string tag
+        assert_eq!(packet.data.as_ref(), b"\x08\x00\x02hi\x01");
+    } else {
+        let err = result.unwrap_err();
+        assert_eq!(err.name, "system_chat");
+    }
+}
+
+#[test]
+fn custom_rule() {
+    let translator = setup(V::V1_20_2, V::V1_20_3).rule(
+        State::Play,
+        Direction::Clientbound,
+        "keep_alive",
+        |_, _| Ok(Bytes::from_static(&[1])),
+    );
+
+    let packet = translate(&translator, 0x24, Bytes::new()).unwrap();
+    assert_eq!(packet.data.as_ref(), [1]);
+}
@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 mod serialize {
     use netherite::{assert_serialization, Serialize};
     use std::mem::size_of;
@@ -98,3 +99,92 @@ mod deserialize {
         assert_deserialization!(&[0, 0] => instance);
     }
 }
+
+#[allow(dead_code)]
+mod packet_enum {
+    use bytes::Bytes;
+    use netherite::{
+        encoding::packetid::PacketId, packet::RawPacket, DeError, Deserialize, PacketEnum,
+    };
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Ping {
+        payload: u64,
+    }
+
+    impl PacketId for Ping {
+        const ID: i32 = 0x01;
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Request {}
+
+    impl PacketId for Request {
+        const ID: i32 = 0x00;
+    }
+
+    #[derive(PacketEnum, Debug)]
+    enum Status {
+        Request(Request),
+        Ping(Ping),
+        #[packet(unknown)]
+        Unknown(RawPacket),
+    }
+
+    #[derive(PacketEnum, Debug)]
+    enum Strict {
+        Ping(Ping),
+    }
+
+    fn raw(packet_id: i32, data: &'static [u8]) -> RawPacket {
+        let data = Bytes::from_static(data);
+        RawPacket { packet_id, data }
+    }
+
+    #[test]
+    fn dispatch() {
+        let ping = Status::try_from(&raw(0x01, &[0, 0, 0, 0, 0, 0, 0, 42])).unwrap();
+        assert!(matches!(ping, Status::Ping(Ping { payload: 42 })));
+
+        let request = Status::try_from(raw(0x00, &[])).unwrap();
+        assert!(matches!(request, Status::Request(Request {})));
+    }
+
+    #[test]
+    fn unknown() {
+        let packet = Status::try_from(raw(0x05, &[1, 2])).unwrap();
+        let Status::Unknown(packet) = packet else {
+            panic!("expected unknown packet")
+        };
+
+        assert_eq!(packet.packet_id, 0x05);
+        assert_eq!(packet.data.as_ref(), &[1, 2]);
+
+        assert!(matches!(
+            Strict::try_from(raw(0x05, &[])),
+            Err(DeError::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(matches!(
+            Status::try_from(raw(0x01, &[0])),
+            Err(DeError::Eof)
+        ));
+    }
+}
+
+mod packet_id {
+    use netherite::{encoding::packetid::PacketId, PacketId};
+
+    #[test]
+    fn derive() {
+        #[derive(PacketId)]
+        #[packet(id = 0x2a)]
+        struct MyPacket;
+
+        assert_eq!(MyPacket::ID, 0x2a);
+        assert_eq!(<&MyPacket as PacketId>::ID, 0x2a);
+    }
+}